@@ -5,10 +5,22 @@
 
 use crate::{
     AppState, Event,
-    server::{self, FNP, Inventory, Peer, ServerBackend, protocol::Offer},
+    gameplay::{
+        self, Auction, Duel, Guild, GuildAction, GuildReply, MultiTrade, Order, OrderSide, Party,
+        PublicOffer, Reward, RuleDecision, Search, Shoal, Tournament, TradeLeg, TradeOutcome,
+        TradeRecord,
+    },
+    server::{
+        self, FNP, Inventory, InventoryItem, Peer, ServerBackend, Standings, peerstore::PeerStore,
+        protocol::Offer,
+    },
 };
 use async_channel::Sender;
-use std::net::{self, SocketAddr};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    net::{self, SocketAddr},
+    time::Duration,
+};
 
 /// Handler para quando um peer se disconecta.
 /// Remove da lista de peers conhecidos e anuncia ao usuário
@@ -16,6 +28,7 @@ pub async fn handle_peer_disconnected(
     app_state: &AppState,
     server: &ServerBackend,
     client_addr: net::SocketAddr,
+    server_sender: Sender<FNP>,
 ) {
     if let Some(peer_info) = server.peer_store().unregister_by_client(&client_addr).await {
         let peer = peer_info.peer;
@@ -25,22 +38,135 @@ pub async fn handle_peer_disconnected(
             peer.address()
         ));
         let mut offer_buffers = app_state.offer_buffers.lock();
-        if offer_buffers.offers_made.remove(&peer.address()).is_some() {
+        let made = offer_buffers.offers_made.remove(&peer.address());
+        let received = offer_buffers.offers_received.remove(&peer.address());
+        drop(offer_buffers);
+        if let Some(offer) = made {
             crate::tui::log(&format!(
                 "Oferta feita para {} foi cancelada.",
                 peer.username()
             ));
+            record_history(
+                app_state,
+                TradeOutcome::Expirada,
+                peer.username(),
+                &offer.offered,
+                &offer.requested,
+            );
         }
-        if offer_buffers
-            .offers_received
-            .remove(&peer.address())
-            .is_some()
-        {
+        if let Some(offer) = received {
             crate::tui::log(&format!(
                 "Oferta recebida de {} foi cancelada.",
                 peer.username()
             ));
+            record_history(
+                app_state,
+                TradeOutcome::Expirada,
+                peer.username(),
+                &offer.requested,
+                &offer.offered,
+            );
+        }
+
+        let mut tournament = app_state.tournament.lock();
+        if let Some(t) = tournament.as_ref().filter(|t| !t.finished) {
+            if t.organizer.address() == peer.address() {
+                crate::tui::log(&format!(
+                    "O torneio {} foi cancelado, o organizador saiu da rede.",
+                    t.id
+                ));
+                *tournament = None;
+            } else if t.scores.contains_key(peer.username()) {
+                crate::tui::log(&format!(
+                    "A pontuação de {} no torneio foi mantida.",
+                    peer.username()
+                ));
+            }
+        }
+        drop(tournament);
+
+        // Leilões de quem saiu não podem mais ser concluídos
+        app_state.auctions.lock().auctions.retain(|id, a| {
+            let keep = a.seller.address() != peer.address();
+            if !keep {
+                crate::tui::log(&format!(
+                    "O leilão {} foi cancelado, {} saiu da rede.",
+                    id,
+                    peer.username()
+                ));
+            }
+            keep
+        });
+        app_state
+            .orders
+            .lock()
+            .orders
+            .retain(|_, o| o.owner.address() != peer.address());
+        app_state.wishlist.lock().others.remove(&peer.address());
+        app_state.board.lock().offers.retain(|id, o| {
+            let keep = o.owner.address() != peer.address();
+            if !keep {
+                crate::tui::log(&format!("A oferta pública {} saiu do mural.", id));
+            }
+            keep
+        });
+
+        // Multitrocas só terminam com todos os participantes: as de quem coordenava são
+        // descartadas e as nossas que dependem de quem saiu são abortadas
+        let (dropped, aborted): (Vec<MultiTrade>, Vec<MultiTrade>) = app_state
+            .multitrades
+            .lock()
+            .trades
+            .values()
+            .filter(|t| {
+                t.coordinator.address() == peer.address()
+                    || (t.mine && t.participants().contains(peer.username()))
+            })
+            .cloned()
+            .partition(|t| !t.mine);
+        for trade in dropped {
+            app_state.multitrades.lock().trades.remove(&trade.id);
+            crate::tui::log(&format!(
+                "A multitroca {} foi cancelada, o coordenador saiu da rede.",
+                trade.id
+            ));
+        }
+        for trade in aborted {
+            let reason = format!("{} saiu da rede", peer.username());
+            abort_multi_trade(app_state, server, &trade.id, &reason, server_sender.clone()).await;
         }
+
+        app_state.scarcity.lock().remove_peer(&peer.address());
+
+        // Duelos com quem saiu não têm como terminar, as apostas voltam a ficar disponíveis
+        app_state.duels.lock().duels.retain(|id, duel| {
+            let keep = duel.opponent.address() != peer.address();
+            if !keep {
+                crate::tui::log(&format!(
+                    "O duelo {} foi cancelado, {} saiu da rede.",
+                    id,
+                    peer.username()
+                ));
+            }
+            keep
+        });
+
+        guild_succession(app_state, server, &peer, server_sender.clone()).await;
+        party_member_left(app_state, server, peer.username(), server_sender.clone()).await;
+        app_state.privacy.lock().pending.remove(peer.username());
+
+        // Presentes sem confirmação voltam a ficar disponíveis
+        app_state.gifts.lock().pending.retain(|_, gift| {
+            let keep = gift.to.address() != peer.address();
+            if !keep {
+                crate::tui::log(&format!(
+                    "O presente de {} para {} não foi confirmado e continua com você.",
+                    gift.item,
+                    peer.username()
+                ));
+            }
+            keep
+        });
     } else {
         crate::tui::err(&format!(
             "Peer desconhecido se desconectou: {}",
@@ -50,8 +176,64 @@ pub async fn handle_peer_disconnected(
 }
 
 /// Pesca um peixe e guarda na cesta
-pub async fn handle_pesca(app_state: &AppState) {
-    let plain_fish = crate::gameplay::fishing(&app_state.fish_catalog);
+pub async fn handle_pesca(
+    app_state: &AppState,
+    server: &ServerBackend,
+    server_sender: Sender<FNP>,
+    event_sender: Sender<Event>,
+) {
+    if app_state.basket.lock().total() >= app_state.capacity() {
+        warn_full_basket(app_state);
+        return;
+    }
+    shoal_claims(app_state, server, server_sender.clone()).await;
+    let used_bait = {
+        let mut baits = app_state.baits.lock();
+        let has_bait = *baits > 0;
+        *baits = baits.saturating_sub(1);
+        has_bait
+    };
+    let (bonus_rolls, used_rare_bait) = {
+        let mut consumables = app_state.consumables.lock();
+        let has_rare_bait = consumables.rare_baits > 0;
+        consumables.rare_baits = consumables.rare_baits.saturating_sub(1);
+        (consumables.bonus_rolls(gameplay::now_secs()), has_rare_bait)
+    };
+    if used_bait {
+        crate::tui::log("Você usou uma isca.");
+    }
+    // O clima da rede define as chances de cada raridade e destrava espécies
+    let now = gameplay::now_secs();
+    let mut odds = {
+        let mut environment = app_state.environment.lock();
+        if let Some((weather, time)) = environment.observe(now) {
+            crate::tui::log(&format!("O tempo mudou: {}, {}.", weather, time));
+        }
+        environment.odds(now)
+    };
+    // No modo de escassez, espécies muito pescadas na rede ficam mais difíceis de pescar
+    {
+        let mut scarcity = app_state.scarcity.lock();
+        scarcity.refresh(now);
+        if scarcity.enabled {
+            for (fish, weight) in scarcity.weights(&app_state.fish_catalog) {
+                odds.scale_species(&fish, weight);
+            }
+        }
+    }
+    // Pescar em grupo aumenta as chances das raridades acima de comum
+    if let Some(party) = app_state.party.lock().party.as_ref() {
+        party.apply_bonus(&mut odds);
+    }
+    // Cada isca ou bônus de prato é mais um lançamento, fica o peixe mais raro
+    let casts = 1 + used_bait as u32 + bonus_rolls;
+    let mut plain_fish = crate::gameplay::fishing_best_of(&app_state.fish_catalog, casts, &odds);
+    if used_rare_bait {
+        crate::tui::log("Você usou uma isca rara.");
+        if app_state.fish_catalog.get_rarity_rank(&plain_fish) < 1 {
+            plain_fish = crate::gameplay::fishing_from_rank(&app_state.fish_catalog, 1);
+        }
+    }
     // se houver aquele peixe na sexta, incrementamos sua contagem, senão adicionamos
     // com o valor 1
     app_state
@@ -64,6 +246,480 @@ pub async fn handle_pesca(app_state: &AppState) {
 
     let style = app_state.fish_catalog.get_style_for_fish(&plain_fish);
     println!("Você pescou um(a) {}!", style.style(&plain_fish));
+
+    notify_wishers(app_state, server.host(), &plain_fish, server_sender.clone()).await;
+    party_catch(app_state, server, &plain_fish, server_sender.clone()).await;
+
+    let report = {
+        let mut scarcity = app_state.scarcity.lock();
+        scarcity.record_catch(gameplay::now_secs(), &plain_fish);
+        scarcity
+            .enabled
+            .then(|| catch_report(server.host(), &scarcity))
+    };
+    if let Some(report) = report {
+        server_sender.send(report).await.ok();
+    }
+    tournament_catch(app_state, server, &plain_fish, server_sender.clone()).await;
+
+    let rank = app_state.fish_catalog.get_rarity_rank(&plain_fish);
+    let rewards = {
+        let mut quests = app_state.quests.lock();
+        quests.refresh(gameplay::today(), &app_state.fish_catalog);
+        quests.record_catch(&plain_fish, rank)
+    };
+    grant_rewards(app_state, rewards);
+
+    spawn_shoal(app_state, server, server_sender, event_sender).await;
+}
+
+/// Responde com a nossa pesca aos cardumes abertos que ainda não respondemos. A resposta
+/// fica guardada também fora de quem avistou, só para não responder duas vezes
+async fn shoal_claims(app_state: &AppState, server: &ServerBackend, server_sender: Sender<FNP>) {
+    let now = gameplay::now_millis();
+    let host = server.host();
+    let claims: Vec<(String, String)> = {
+        let mut shoals = app_state.shoals.lock();
+        shoals
+            .shoals
+            .values_mut()
+            .filter(|s| s.is_open(now) && !s.claims.contains_key(host.username()))
+            .map(|s| {
+                s.record_claim(host.username(), now);
+                (s.id.clone(), s.spawner.clone())
+            })
+            .collect()
+    };
+    for (id, spawner) in claims {
+        crate::tui::log(&format!(
+            "Você respondeu ao cardume {}, {} anuncia quem pescou primeiro quando ele for embora.",
+            id, spawner
+        ));
+        let msg = FNP::ShoalClaim {
+            rem: host.clone(),
+            id,
+        };
+        server_sender.send(msg).await.ok();
+    }
+}
+
+/// De vez em quando uma pesca avista um cardume, que é anunciado para a rede toda
+async fn spawn_shoal(
+    app_state: &AppState,
+    server: &ServerBackend,
+    server_sender: Sender<FNP>,
+    event_sender: Sender<Event>,
+) {
+    if rand::random::<u32>() % 100 >= gameplay::SHOAL_CHANCE {
+        return;
+    }
+    let now = gameplay::now_secs();
+    let shoal = {
+        let mut shoals = app_state.shoals.lock();
+        // Um cardume por vez
+        if shoals.open(now * 1000).next().is_some() {
+            return;
+        }
+        let rank = rand::random_range(1..=3);
+        let shoal = Shoal {
+            id: shoals.ids.next(server.host().username()),
+            spawner: server.host().username().to_string(),
+            fish: gameplay::fishing_from_rank(&app_state.fish_catalog, rank),
+            slots: gameplay::SHOAL_SLOTS,
+            start: now,
+            end: now + gameplay::SHOAL_WINDOW.as_secs(),
+            claims: BTreeMap::new(),
+        };
+        shoals.insert(shoal.clone(), now * 1000);
+        shoal
+    };
+    announce_shoal(app_state, &shoal, event_sender);
+    server_sender
+        .send(shoal_event(server.host(), &shoal))
+        .await
+        .ok();
+}
+
+fn shoal_event(host: Peer, shoal: &Shoal) -> FNP {
+    FNP::ShoalEvent {
+        rem: host,
+        id: shoal.id.clone(),
+        spawner: shoal.spawner.clone(),
+        fish: shoal.fish.clone(),
+        slots: shoal.slots,
+        start: shoal.start,
+        end: shoal.end,
+    }
+}
+
+/// Avisa do cardume e agenda o fim da janela, com uma folga para as últimas respostas
+fn announce_shoal(app_state: &AppState, shoal: &Shoal, event_sender: Sender<Event>) {
+    let now = gameplay::now_secs();
+    let style = app_state.fish_catalog.get_style_for_fish(&shoal.fish);
+    crate::tui::log(&format!(
+        "{} avistou um cardume de {}! Os primeiros {} a pescar ($p) nos próximos {}s ganham um peixe extra.",
+        shoal.spawner,
+        style.style(&shoal.fish),
+        shoal.slots,
+        shoal.end.saturating_sub(now)
+    ));
+    crate::event::schedule(
+        event_sender,
+        Duration::from_secs(shoal.end.saturating_sub(now) + gameplay::SHOAL_GRACE),
+        Event::CardumeFim(shoal.id.clone()),
+    );
+}
+
+async fn handle_server_shoal_event(
+    app_state: &AppState,
+    shoal: Shoal,
+    event_sender: Sender<Event>,
+) {
+    let now = gameplay::now_secs();
+    if !shoal.is_valid(now) || shoal.end <= now {
+        return;
+    }
+    // Anúncios repetidos, de quem chegou depois de nós, são ignorados
+    if app_state.shoals.lock().insert(shoal.clone(), now * 1000) {
+        announce_shoal(app_state, &shoal, event_sender);
+    }
+}
+
+/// Fim da janela de um cardume. Se fomos nós que o avistamos, ordenamos as respostas pela
+/// hora em que chegaram e anunciamos os vencedores. Os outros peers esperam esse resultado
+pub async fn handle_shoal_end(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let Some(shoal) = app_state.shoals.lock().finish(id, host.username()) else {
+        return;
+    };
+    let winners = shoal.winners();
+    server_sender
+        .send(FNP::ShoalResult {
+            rem: host,
+            id: shoal.id.clone(),
+            winners: winners.clone(),
+        })
+        .await
+        .ok();
+    shoal_result(app_state, server, shoal, winners, server_sender).await;
+}
+
+async fn handle_server_shoal_result(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    mut winners: Vec<String>,
+    server_sender: Sender<FNP>,
+) {
+    // Só vale o resultado de quem avistou o cardume
+    let Some(shoal) = app_state.shoals.lock().finish(id, rem.username()) else {
+        return;
+    };
+    winners.truncate(shoal.slots as usize);
+    shoal_result(app_state, server, shoal, winners, server_sender).await;
+}
+
+/// Mostra os vencedores de um cardume, cada vencedor dá o peixe extra para si mesmo
+async fn shoal_result(
+    app_state: &AppState,
+    server: &ServerBackend,
+    shoal: Shoal,
+    winners: Vec<String>,
+    server_sender: Sender<FNP>,
+) {
+    if winners.is_empty() {
+        crate::tui::log(&format!(
+            "O cardume {} foi embora sem ninguém pescar.",
+            shoal.id
+        ));
+        return;
+    }
+    crate::tui::log(&format!(
+        "O cardume {} foi embora. Pescaram primeiro: {}.",
+        shoal.id,
+        winners.join(", ")
+    ));
+    if !winners.iter().any(|w| w == server.host().username()) {
+        return;
+    }
+    if app_state.basket.lock().total() >= app_state.capacity() {
+        warn_full_basket(app_state);
+        return;
+    }
+    *app_state
+        .basket
+        .lock()
+        .map_mut()
+        .entry(shoal.fish.clone())
+        .or_insert(0) += 1;
+    let style = app_state.fish_catalog.get_style_for_fish(&shoal.fish);
+    println!(
+        "Você pescou um(a) {} extra do cardume!",
+        style.style(&shoal.fish)
+    );
+    notify_wishers(app_state, server.host(), &shoal.fish, server_sender).await;
+}
+
+/// Efeitos de uma troca concluída por qualquer caminho: atualiza as missões diárias e
+/// abate as ordens permanentes atendidas pela troca
+async fn trade_completed(
+    app_state: &AppState,
+    host: Peer,
+    counterpart: &str,
+    given: &[InventoryItem],
+    received: &[InventoryItem],
+    server_sender: Sender<FNP>,
+) {
+    record_history(
+        app_state,
+        TradeOutcome::Concluida,
+        counterpart,
+        given,
+        received,
+    );
+    quests_trade(app_state, given, received);
+    app_state.prices.lock().record_trade(given, received);
+    for item in received.iter().filter(|i| !gameplay::is_coin(&i.fish_type)) {
+        notify_wishers(
+            app_state,
+            host.clone(),
+            &item.fish_type,
+            server_sender.clone(),
+        )
+        .await;
+    }
+
+    let changed = app_state.orders.lock().record_trade(given, received);
+    for order in changed {
+        let msg = if order.item.quantity == 0 {
+            crate::tui::log(&format!("Sua ordem {} foi completada.", order.id));
+            FNP::OrderCancel {
+                rem: host.clone(),
+                id: order.id,
+            }
+        } else {
+            crate::tui::log(&format!(
+                "Sua ordem {} foi atendida em parte, faltam {}.",
+                order.id, order.item
+            ));
+            order_announce(host.clone(), order)
+        };
+        server_sender.send(msg).await.ok();
+    }
+}
+
+/// Guarda o resultado de uma troca no histórico
+fn record_history(
+    app_state: &AppState,
+    outcome: TradeOutcome,
+    counterpart: &str,
+    given: &[InventoryItem],
+    received: &[InventoryItem],
+) {
+    app_state.history.lock().record(TradeRecord {
+        time: gameplay::now_secs(),
+        outcome,
+        peer: counterpart.to_string(),
+        given: given.to_vec(),
+        received: received.to_vec(),
+    });
+}
+
+/// Avisa os peers que têm o peixe na lista de desejos que acabamos de consegui-lo
+async fn notify_wishers(app_state: &AppState, host: Peer, fish: &str, server_sender: Sender<FNP>) {
+    let wishers = app_state.wishlist.lock().wishers(fish);
+    if wishers.is_empty() {
+        return;
+    }
+    for dest in wishers {
+        let friend = is_friend(app_state, host.username(), dest.username());
+        let quantity = visible_quantity(app_state, fish, friend);
+        if quantity == 0 {
+            continue;
+        }
+        server_sender
+            .send(FNP::WishNotify {
+                rem: host.clone(),
+                dest,
+                fish: fish.to_string(),
+                quantity,
+            })
+            .await
+            .ok();
+    }
+}
+
+/// Atualiza as missões diárias com uma troca concluída
+fn quests_trade(app_state: &AppState, given: &[InventoryItem], received: &[InventoryItem]) {
+    let traded: Vec<(u8, u32)> = given
+        .iter()
+        .chain(received)
+        .map(|i| {
+            (
+                app_state.fish_catalog.get_rarity_rank(&i.fish_type),
+                i.quantity,
+            )
+        })
+        .collect();
+    let rewards = {
+        let mut quests = app_state.quests.lock();
+        quests.refresh(gameplay::today(), &app_state.fish_catalog);
+        quests.record_trade(&traded)
+    };
+    grant_rewards(app_state, rewards);
+}
+
+/// Entrega as recompensas de missões completadas. Peixes que não cabem na cesta são
+/// vendidos ao mercado na hora
+fn grant_rewards(app_state: &AppState, rewards: Vec<Reward>) {
+    let capacity = app_state.capacity();
+    for reward in rewards {
+        crate::tui::log(&format!(
+            "Missão diária completa! Recompensa: {}",
+            reward.description()
+        ));
+        match reward {
+            Reward::Fish { tier, quantity } => {
+                let mut sold = 0;
+                {
+                    let mut basket = app_state.basket.lock();
+                    for _ in 0..quantity {
+                        let fish = gameplay::fishing_from_rank(&app_state.fish_catalog, tier);
+                        let style = app_state.fish_catalog.get_style_for_fish(&fish);
+                        if basket.total() >= capacity {
+                            let price = gameplay::sell_price(&app_state.fish_catalog, &fish);
+                            println!(
+                                "+ {} moeda(s) pelo {}, que não coube na cesta",
+                                price,
+                                style.style(&fish)
+                            );
+                            let mut coins = app_state.coins.lock();
+                            *coins = coins.saturating_add(price);
+                            sold += 1;
+                            continue;
+                        }
+                        println!("+ 1 {}", style.style(&fish));
+                        *basket.map_mut().entry(fish).or_insert(0) += 1;
+                    }
+                }
+                if sold > 0 {
+                    warn_full_basket(app_state);
+                }
+            }
+            Reward::Baits(n) => *app_state.baits.lock() += n,
+        }
+    }
+}
+
+/// Contabiliza um peixe pescado no torneio em andamento, se ele contar pontos
+async fn tournament_catch(
+    app_state: &AppState,
+    server: &ServerBackend,
+    fish: &str,
+    server_sender: Sender<FNP>,
+) {
+    let rank = app_state.fish_catalog.get_rarity_rank(fish);
+    let score_msg = {
+        let mut tournament = app_state.tournament.lock();
+        let Some(t) = tournament
+            .as_mut()
+            .filter(|t| t.counts(rank, gameplay::now_secs()))
+        else {
+            return;
+        };
+        t.my_score += app_state.fish_catalog.get_rarity_points(fish);
+        crate::tui::log(&format!("Pontuação no torneio: {}", t.my_score));
+        if t.organizer.address() == server.host().address() {
+            let username = server.host().username().to_string();
+            let score = t.my_score;
+            t.record_score(&username, score);
+            return;
+        }
+        FNP::TournamentScore {
+            rem: server.host(),
+            dest: t.organizer.clone(),
+            id: t.id.clone(),
+            score: t.my_score,
+        }
+    };
+    server_sender.send(score_msg).await.ok();
+}
+
+/// O torneio começou, avisa o usuário
+pub async fn handle_tournament_start(app_state: &AppState, id: &str) {
+    if let Some(t) = app_state
+        .tournament
+        .lock()
+        .as_ref()
+        .filter(|t| t.id == id && !t.finished)
+    {
+        if t.joined {
+            crate::tui::log(&format!(
+                "O torneio {} começou! Você tem {}s para pescar.",
+                t.id,
+                t.end - t.start
+            ));
+        } else {
+            crate::tui::log(&format!(
+                "O torneio {} começou! Digite '$torneio entrar' para participar.",
+                t.id
+            ));
+        }
+    }
+}
+
+/// O torneio terminou. O organizador calcula e anuncia o resultado, os demais aguardam
+pub async fn handle_tournament_end(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    server_sender: Sender<FNP>,
+) {
+    let result_msg = {
+        let mut tournament = app_state.tournament.lock();
+        let Some(t) = tournament.as_mut().filter(|t| t.id == id && !t.finished) else {
+            return;
+        };
+        if t.organizer.address() != server.host().address() {
+            if t.joined {
+                crate::tui::log(&format!(
+                    "O torneio {} terminou com {} ponto(s) seus. Aguardando o resultado de {}...",
+                    t.id,
+                    t.my_score,
+                    t.organizer.username()
+                ));
+            }
+            return;
+        }
+        t.finished = true;
+        FNP::TournamentResult {
+            rem: server.host(),
+            id: t.id.clone(),
+            standings: Standings {
+                entries: t.standings(),
+            },
+        }
+    };
+    if let FNP::TournamentResult { id, standings, .. } = &result_msg {
+        show_standings(id, standings);
+    }
+    server_sender.send(result_msg).await.ok();
+}
+
+fn show_standings(id: &str, standings: &Standings) {
+    println!("-- RESULTADO DO TORNEIO {} --", id);
+    if standings.entries.is_empty() {
+        crate::tui::log("[Ninguém participou]");
+    }
+    for (pos, (name, score)) in standings.entries.iter().enumerate() {
+        println!("{}º {} - {} ponto(s)", pos + 1, name, score);
+    }
 }
 
 /// Trata mensagens advindas do servidor ou seja de peers pela rede
@@ -77,13 +733,13 @@ pub async fn handle_server_message(
 ) {
     match msg {
         FNP::Message { rem, content, .. } => {
-            handle_server_direct_message(rem, &content).await;
+            handle_server_direct_message(app_state, rem, &content).await;
         }
         FNP::Broadcast { rem, content } => {
-            handle_server_broadcast_message(rem, &content).await;
+            handle_server_broadcast_message(app_state, rem, &content).await;
         }
         FNP::TradeOffer { rem, offer, .. } => {
-            handle_server_tradeoffer(app_state, rem, offer).await;
+            handle_server_tradeoffer(app_state, server, rem, offer, server_sender).await;
         }
         FNP::TradeConfirm {
             rem,
@@ -91,16 +747,20 @@ pub async fn handle_server_message(
             offer,
             ..
         } => {
-            handle_server_tradeconfirm(app_state, response, rem, &offer).await;
+            handle_server_tradeconfirm(app_state, server, response, rem, &offer, server_sender)
+                .await;
         }
         FNP::InventoryInspection { rem, .. } => {
             handle_server_inventory_request(app_state, rem, server, server_sender).await;
         }
+        FNP::InventoryDenied { rem, reason, .. } => {
+            handle_server_inventory_denied(app_state, rem, &reason);
+        }
         FNP::InventoryShowcase { rem, inventory, .. } => {
             handle_server_inventory_showcase(app_state, rem, inventory).await;
         }
         FNP::AnnounceName { rem } => {
-            handle_server_announce_name(server, rem, client_addr, server_sender).await;
+            handle_server_announce_name(app_state, server, rem, client_addr, server_sender).await;
         }
         FNP::PeerList { rem, peers, .. } => {
             handle_server_peerlist(&peers, server, rem, event_sender).await;
@@ -108,44 +768,462 @@ pub async fn handle_server_message(
         FNP::RejectConnection { .. } => {
             handle_rejection().await;
         }
-    }
-}
-
-/// Trata mensagens geradas pela UI pelo usuário
-pub async fn handle_ui_message(app_state: &AppState, msg: FNP, server_sender: Sender<FNP>) {
-    // Lida com mensagens enviadas do cliente para ele mesmo
-    if msg
-        .dest()
-        .is_some_and(|d| d.address() == msg.rem().address())
-    {
-        // Usuário pode ver o próprio inventário
-        if let FNP::InventoryInspection { .. } = msg {
-            handle_ui_inventory_inspection(app_state).await;
-        } else {
-            crate::tui::err("Este comando não é válido para você mesmo");
+        FNP::TournamentAnnounce {
+            rem,
+            id,
+            start,
+            end,
+            min_tier,
+        } => {
+            let tournament = Tournament::new(id, rem, start, end, min_tier);
+            handle_server_tournament_announce(app_state, tournament, event_sender).await;
         }
-        return;
-    }
-    // Lida com mensagens enviadas do cliente para outro peer
-    match &msg {
-        FNP::TradeConfirm {
-            dest,
-            response,
-            offer,
-            ..
+        FNP::TournamentJoin { rem, id, .. } => {
+            handle_server_tournament_join(app_state, rem, &id).await;
+        }
+        FNP::TournamentScore { rem, id, score, .. } => {
+            handle_server_tournament_score(app_state, rem, &id, score).await;
+        }
+        FNP::TournamentResult {
+            rem, id, standings, ..
         } => {
-            handle_ui_tradeconfirm(app_state, *response, offer, dest).await;
+            handle_server_tournament_result(app_state, rem, &id, &standings).await;
         }
-        FNP::TradeOffer { .. } => {
-            handle_ui_tradeoffer().await;
+        FNP::AuctionAnnounce {
+            rem,
+            id,
+            item,
+            min_bid,
+            end,
+        } => {
+            let auction = Auction {
+                id,
+                seller: rem,
+                item,
+                min_bid,
+                end,
+                highest: None,
+                bids: Vec::new(),
+                mine: false,
+                my_bid: 0,
+            };
+            handle_server_auction_announce(app_state, auction).await;
         }
-        _ => (),
-    }
-    // Enviando a mensagem para o servidor mandar aos peers
-    server_sender.send(msg).await.ok();
-}
-
-async fn reject_homonym(server: &ServerBackend, rem: &Peer, client_addr: SocketAddr) {
+        FNP::AuctionBid {
+            rem, id, amount, ..
+        } => {
+            handle_server_auction_bid(app_state, server, rem, &id, amount, server_sender).await;
+        }
+        FNP::AuctionUpdate {
+            rem,
+            id,
+            bidder,
+            amount,
+        } => {
+            handle_server_auction_update(app_state, server, rem, &id, bidder, amount).await;
+        }
+        FNP::AuctionClose {
+            rem,
+            id,
+            winner,
+            amount,
+        } => {
+            handle_server_auction_close(app_state, server, rem, &id, winner, amount, server_sender)
+                .await;
+        }
+        FNP::PublicOffer { rem, id, offer } => {
+            let public_offer = PublicOffer {
+                id,
+                owner: rem,
+                offer,
+                mine: false,
+                claimed: false,
+            };
+            handle_server_public_offer(app_state, public_offer).await;
+        }
+        FNP::PublicOfferClaim { rem, id, .. } => {
+            handle_server_public_offer_claim(app_state, server, rem, &id, server_sender).await;
+        }
+        FNP::PublicOfferClosed { rem, id, taker } => {
+            handle_server_public_offer_closed(app_state, server, rem, &id, taker, server_sender)
+                .await;
+        }
+        FNP::OrderAnnounce {
+            rem,
+            id,
+            side,
+            item,
+            price,
+        } => {
+            let order = Order {
+                id,
+                owner: rem,
+                side,
+                item,
+                price,
+                mine: false,
+            };
+            handle_server_order_announce(app_state, server, order, server_sender).await;
+        }
+        FNP::OrderCancel { rem, id } => {
+            handle_server_order_cancel(app_state, rem, &id).await;
+        }
+        FNP::FishSearch {
+            rem,
+            id,
+            fish,
+            hops,
+        } => {
+            let search = Search {
+                fish,
+                back: Some(rem),
+                results: HashMap::new(),
+            };
+            handle_server_fish_search(
+                app_state,
+                server,
+                id,
+                search,
+                hops,
+                server_sender,
+                event_sender,
+            )
+            .await;
+        }
+        FNP::Wishlist { rem, fish } => {
+            handle_server_wishlist(app_state, rem, fish).await;
+        }
+        FNP::MultiTradePropose { rem, id, legs, .. } => {
+            handle_server_multi_trade_propose(app_state, server, rem, id, legs).await;
+        }
+        FNP::MultiTradeVote {
+            rem,
+            id,
+            voter,
+            approve,
+            ..
+        } => {
+            handle_server_multi_trade_vote(
+                app_state,
+                server,
+                rem,
+                &id,
+                voter,
+                approve,
+                server_sender,
+            )
+            .await;
+        }
+        FNP::MultiTradeCommit {
+            rem, id, commit, ..
+        } => {
+            handle_server_multi_trade_commit(app_state, server, rem, &id, commit, server_sender)
+                .await;
+        }
+        FNP::ShoalEvent {
+            id,
+            spawner,
+            fish,
+            slots,
+            start,
+            end,
+            ..
+        } => {
+            if !app_state.fish_catalog.contains(&fish) {
+                return;
+            }
+            let shoal = Shoal {
+                id,
+                spawner,
+                fish,
+                slots,
+                start,
+                end,
+                claims: BTreeMap::new(),
+            };
+            handle_server_shoal_event(app_state, shoal, event_sender).await;
+        }
+        FNP::ShoalClaim { rem, id } => {
+            // Marcamos a resposta com o nosso relógio, se fomos nós que avistamos o cardume
+            app_state.shoals.lock().record_claim(
+                &id,
+                server.host().username(),
+                rem.username(),
+                gameplay::now_millis(),
+            );
+        }
+        FNP::ShoalResult { rem, id, winners } => {
+            handle_server_shoal_result(app_state, server, rem, &id, winners, server_sender).await;
+        }
+        FNP::WeatherSchedule { seed, since, .. } => {
+            let now = gameplay::now_secs();
+            let mut environment = app_state.environment.lock();
+            if environment.adopt(seed, since) {
+                crate::tui::log(&format!(
+                    "Clima sincronizado com a rede: {}, {}.",
+                    environment.weather_at(now),
+                    gameplay::Environment::time_of_day(now)
+                ));
+            }
+        }
+        FNP::CatchReport { rem, catches } => {
+            let catches = catches
+                .into_iter()
+                .filter(|c| app_state.fish_catalog.contains(&c.fish_type))
+                .map(|c| (c.fish_type, c.quantity.min(gameplay::MAX_REPORTED_CATCHES)))
+                .collect();
+            app_state
+                .scarcity
+                .lock()
+                .record_report(gameplay::now_secs(), rem.address(), catches);
+        }
+        FNP::Gift { rem, id, item, .. } => {
+            handle_server_gift(app_state, server, rem, id, item, server_sender).await;
+        }
+        FNP::GiftAck {
+            rem, id, accepted, ..
+        } => {
+            handle_server_gift_ack(app_state, rem, &id, accepted);
+        }
+        FNP::DuelChallenge {
+            rem,
+            id,
+            casts,
+            stake,
+            commitment,
+            ..
+        } => {
+            // Nossa metade da semente é sorteada agora, mas só vai no aceite
+            let duel = Duel {
+                id,
+                opponent: rem,
+                challenger: false,
+                casts,
+                my_stake: Vec::new(),
+                their_stake: stake,
+                nonce: rand::random(),
+                their_commitment: Some(commitment),
+                accepted: false,
+                deadline: gameplay::now_secs() + gameplay::DUEL_TIMEOUT.as_secs(),
+            };
+            handle_server_duel_challenge(app_state, server, duel, server_sender, event_sender)
+                .await;
+        }
+        FNP::DuelAnswer {
+            rem,
+            id,
+            accepted,
+            stake,
+            commitment,
+            ..
+        } => {
+            let answer = accepted.then_some((stake, commitment));
+            handle_server_duel_answer(
+                app_state,
+                server,
+                rem,
+                &id,
+                answer,
+                server_sender,
+                event_sender,
+            )
+            .await;
+        }
+        FNP::GuildState {
+            rem,
+            name,
+            leader,
+            members,
+            quorum,
+            basket,
+            pending,
+            version,
+            ..
+        } => {
+            let guild = Guild {
+                name,
+                leader,
+                members: members.into_iter().collect(),
+                quorum,
+                basket: basket
+                    .into_iter()
+                    .map(|i| (i.fish_type, i.quantity))
+                    .collect(),
+                pending,
+                version,
+            };
+            handle_server_guild_state(app_state, server, rem, guild).await;
+        }
+        FNP::GuildRequest {
+            rem,
+            name,
+            id,
+            action,
+            ..
+        } => {
+            handle_guild_request(app_state, server, rem, &name, id, action, server_sender).await;
+        }
+        FNP::GuildAck {
+            rem,
+            name,
+            id,
+            accepted,
+            ..
+        } => {
+            // Só o líder responde pedidos
+            let leads = app_state
+                .guilds
+                .lock()
+                .guilds
+                .get(&name)
+                .is_some_and(|g| g.leader == rem.username());
+            if leads {
+                handle_guild_ack(app_state, server, &name, &id, accepted, server_sender).await;
+            }
+        }
+        FNP::PartyInvite { rem, id, .. } => {
+            handle_server_party_invite(app_state, server, rem, id, server_sender).await;
+        }
+        FNP::PartyAnswer {
+            rem, id, accepted, ..
+        } => {
+            handle_server_party_answer(app_state, server, rem, &id, accepted, server_sender).await;
+        }
+        FNP::PartyState {
+            rem,
+            id,
+            leader,
+            members,
+            version,
+            ..
+        } => {
+            let party = Party {
+                id,
+                leader,
+                members: members.into_iter().collect(),
+                version,
+            };
+            handle_server_party_state(app_state, server, &rem, party);
+        }
+        FNP::PartyLeave { rem, id, .. } => {
+            let same_party = app_state
+                .party
+                .lock()
+                .party
+                .as_ref()
+                .is_some_and(|p| p.id == id);
+            if same_party {
+                party_member_left(app_state, server, rem.username(), server_sender).await;
+            }
+        }
+        FNP::PartyMessage {
+            rem, id, content, ..
+        } => {
+            if party_member(app_state, &id, rem.username()) {
+                let tag = app_state.guilds.lock().tag(rem.username());
+                println!("[grupo] {}{}: {}", rem.username(), tag, content);
+            }
+        }
+        FNP::PartyCatch { rem, id, fish, .. } => {
+            if party_member(app_state, &id, rem.username()) {
+                let style = app_state.fish_catalog.get_style_for_fish(&fish);
+                println!(
+                    "[grupo] {} pescou um(a) {}!",
+                    rem.username(),
+                    style.style(&fish)
+                );
+            }
+        }
+        FNP::DuelReveal { rem, id, nonce, .. } => {
+            handle_server_duel_reveal(app_state, server, rem, &id, nonce, server_sender).await;
+        }
+        FNP::WishNotify {
+            rem,
+            fish,
+            quantity,
+            ..
+        } => {
+            handle_server_wish_notify(app_state, rem, &fish, quantity).await;
+        }
+        FNP::FishSearchResult {
+            id,
+            owner,
+            quantity,
+            ..
+        } => {
+            handle_server_fish_search_result(
+                app_state,
+                server,
+                &id,
+                owner,
+                quantity,
+                server_sender,
+            )
+            .await;
+        }
+    }
+}
+
+/// Trata mensagens geradas pela UI pelo usuário
+pub async fn handle_ui_message(
+    app_state: &AppState,
+    server: &ServerBackend,
+    msg: FNP,
+    server_sender: Sender<FNP>,
+) {
+    // Lida com mensagens enviadas do cliente para ele mesmo
+    if msg
+        .dest()
+        .is_some_and(|d| d.address() == msg.rem().address())
+    {
+        // Usuário pode ver o próprio inventário
+        if let FNP::InventoryInspection { rem, .. } = msg {
+            handle_ui_inventory_inspection(app_state, rem.address()).await;
+        } else if let FNP::GuildRequest {
+            rem,
+            name,
+            id,
+            action,
+            ..
+        } = msg
+        {
+            // Pedidos à guilda que lideramos são atendidos aqui mesmo
+            handle_guild_request(app_state, server, rem, &name, id, action, server_sender).await;
+        } else {
+            crate::tui::err("Este comando não é válido para você mesmo");
+        }
+        return;
+    }
+    // Lida com mensagens enviadas do cliente para outro peer
+    match &msg {
+        // Respostas que não puderam ser executadas não são enviadas ao peer
+        FNP::TradeConfirm {
+            dest,
+            response,
+            offer,
+            ..
+        } if !handle_ui_tradeconfirm(
+            app_state,
+            msg.rem().clone(),
+            *response,
+            offer,
+            dest,
+            server_sender.clone(),
+        )
+        .await =>
+        {
+            return;
+        }
+        FNP::TradeOffer { .. } => {
+            handle_ui_tradeoffer().await;
+        }
+        _ => (),
+    }
+    // Enviando a mensagem para o servidor mandar aos peers
+    server_sender.send(msg).await.ok();
+}
+
+async fn reject_homonym(server: &ServerBackend, rem: &Peer, client_addr: SocketAddr) {
     let reject_msg = FNP::RejectConnection {
         rem: server.host(),
         dest: rem.clone(),
@@ -170,6 +1248,7 @@ async fn handle_rejection() {
 }
 
 async fn handle_server_announce_name(
+    app_state: &AppState,
     server: &ServerBackend,
     rem: Peer,
     client_addr: SocketAddr,
@@ -218,82 +1297,460 @@ async fn handle_server_announce_name(
             peers,
         };
         server_sender.send(peer_list_msg).await.ok();
+
+        // Se organizamos um torneio em andamento, o anunciamos de novo para quem chegou
+        let announce = app_state
+            .tournament
+            .lock()
+            .as_ref()
+            .filter(|t| !t.finished && t.organizer.address() == server.host().address())
+            .map(|t| FNP::TournamentAnnounce {
+                rem: server.host(),
+                id: t.id.clone(),
+                start: t.start,
+                end: t.end,
+                min_tier: t.min_tier,
+            });
+        if let Some(announce) = announce {
+            server_sender.send(announce).await.ok();
+        }
+
+        // Anunciamos também os nossos leilões abertos e seus maiores lances
+        let auction_msgs: Vec<FNP> = app_state
+            .auctions
+            .lock()
+            .auctions
+            .values()
+            .filter(|a| a.mine)
+            .flat_map(|a| {
+                let announce = FNP::AuctionAnnounce {
+                    rem: server.host(),
+                    id: a.id.clone(),
+                    item: a.item.clone(),
+                    min_bid: a.min_bid,
+                    end: a.end,
+                };
+                let update = a
+                    .highest
+                    .clone()
+                    .map(|(bidder, amount)| FNP::AuctionUpdate {
+                        rem: server.host(),
+                        id: a.id.clone(),
+                        bidder,
+                        amount,
+                    });
+                std::iter::once(announce).chain(update)
+            })
+            .collect();
+        let board_msgs: Vec<FNP> = app_state
+            .board
+            .lock()
+            .offers
+            .values()
+            .filter(|o| o.mine)
+            .map(|o| FNP::PublicOffer {
+                rem: server.host(),
+                id: o.id.clone(),
+                offer: o.offer.clone(),
+            })
+            .collect();
+        let order_msgs: Vec<FNP> = app_state
+            .orders
+            .lock()
+            .orders
+            .values()
+            .filter(|o| o.mine)
+            .map(|o| order_announce(server.host(), o.clone()))
+            .collect();
+        // E a nossa lista de desejos, se houver
+        let wishlist_msg = {
+            let wishlist = app_state.wishlist.lock();
+            (!wishlist.mine.is_empty()).then(|| FNP::Wishlist {
+                rem: server.host(),
+                fish: wishlist.mine.iter().cloned().collect(),
+            })
+        };
+        // E o nosso cronograma de clima, para a rede pescar nas mesmas condições
+        let weather_msg = {
+            let environment = app_state.environment.lock();
+            FNP::WeatherSchedule {
+                rem: server.host(),
+                seed: environment.seed,
+                since: environment.since,
+            }
+        };
+        // E o estado das guildas que lideramos, para quem chegou ver os membros
+        let guild_msgs: Vec<FNP> = app_state
+            .guilds
+            .lock()
+            .guilds
+            .values()
+            .filter(|g| g.leader == server.host().username())
+            .map(|g| guild_state(server.host(), g))
+            .collect();
+        // Quem chega no meio de um cardume recebe o anúncio de todos que o conhecem e pode
+        // responder a quem avistou
+        let shoal_msgs: Vec<FNP> = app_state
+            .shoals
+            .lock()
+            .open(gameplay::now_millis())
+            .map(|s| shoal_event(server.host(), s))
+            .collect();
+        for msg in auction_msgs
+            .into_iter()
+            .chain(board_msgs)
+            .chain(order_msgs)
+            .chain(wishlist_msg)
+            .chain(std::iter::once(weather_msg))
+            .chain(shoal_msgs)
+            .chain(guild_msgs)
+        {
+            server_sender.send(msg).await.ok();
+        }
     }
 }
 
-async fn handle_server_direct_message(rem: Peer, content: &str) {
-    println!("DM de {}: {}", rem.username(), content);
+async fn handle_server_direct_message(app_state: &AppState, rem: Peer, content: &str) {
+    let tag = app_state.guilds.lock().tag(rem.username());
+    println!("DM de {}{}: {}", rem.username(), tag, content);
 }
 
-async fn handle_server_broadcast_message(rem: Peer, content: &str) {
-    println!("{} - {}", rem.username(), content);
+async fn handle_server_broadcast_message(app_state: &AppState, rem: Peer, content: &str) {
+    let tag = app_state.guilds.lock().tag(rem.username());
+    println!("{}{} - {}", rem.username(), tag, content);
 }
 
+/// Pedido de um peer para ver o nosso inventário, respondido conforme a privacidade
 async fn handle_server_inventory_request(
     app_state: &AppState,
     peer: Peer,
     server: &ServerBackend,
     server_sender: Sender<FNP>,
 ) {
-    let basket = app_state.basket.lock();
+    let username = peer.username().to_string();
+    let muted = is_muted(app_state, &username);
+    let decision = if muted {
+        gameplay::InspectionDecision::Negar("o pedido foi recusado".to_string())
+    } else {
+        let friend = is_friend(app_state, server.host().username(), &username);
+        app_state.privacy.lock().decide(friend)
+    };
+    let reply = match decision {
+        gameplay::InspectionDecision::Mostrar => inventory_showcase(app_state, server.host(), peer),
+        gameplay::InspectionDecision::Negar(reason) => FNP::InventoryDenied {
+            rem: server.host(),
+            dest: peer,
+            reason,
+        },
+        gameplay::InspectionDecision::Perguntar => {
+            crate::tui::log(&format!(
+                "{} quer ver o seu inventário. Digite '$privacidade permitir {}' ou '$privacidade negar {}'.",
+                username, username, username
+            ));
+            app_state.privacy.lock().pending.insert(username, peer);
+            return;
+        }
+    };
+    server_sender.send(reply).await.ok();
+}
+
+/// Checa se o peer está silenciado com a regra de recusar silenciados ativa
+fn is_muted(app_state: &AppState, username: &str) -> bool {
+    let rules = app_state.rules.lock();
+    rules
+        .enabled
+        .contains(&gameplay::TradeRule::RecusarSilenciados)
+        && rules.muted.contains(username)
+}
+
+/// Amigos, membros do nosso grupo e da nossa guilda
+fn is_friend(app_state: &AppState, me: &str, username: &str) -> bool {
+    let friend = app_state.privacy.lock().friends.contains(username);
+    let party = app_state
+        .party
+        .lock()
+        .party
+        .as_ref()
+        .is_some_and(|p| p.members.contains(username));
+    let guild = app_state
+        .guilds
+        .lock()
+        .of_member(me)
+        .is_some_and(|g| g.members.contains(username));
+    friend || party || guild
+}
+
+/// Quantos peixes de uma espécie um peer pode saber que temos, conforme a privacidade do
+/// inventário. Sem permissão direta para ver o inventário, ou com a raridade escondida, é 0
+fn visible_quantity(app_state: &AppState, fish: &str, friend: bool) -> u32 {
+    let quantity = app_state.available(fish, None);
+    let privacy = app_state.privacy.lock();
+    if privacy.decide(friend) != gameplay::InspectionDecision::Mostrar {
+        return 0;
+    }
+    privacy
+        .visible(
+            &app_state.fish_catalog,
+            vec![InventoryItem::new(fish.to_string(), quantity)],
+        )
+        .first()
+        .map_or(0, |i| i.quantity)
+}
 
-    let mut inventory_items: Vec<server::InventoryItem> = basket
+/// Nosso inventário, ordenado por raridade e sem as raridades escondidas
+pub fn inventory_showcase(app_state: &AppState, host: Peer, dest: Peer) -> FNP {
+    let mut inventory_items: Vec<server::InventoryItem> = app_state
+        .basket
+        .lock()
         .map()
         .iter()
         .map(|(k, v)| server::InventoryItem::new(k.to_string(), *v))
         .collect();
 
-    drop(basket);
-
     // Ordena o vetor de itens com base na raridade
     inventory_items.sort_by(|a, b| {
         let rank_a = app_state.fish_catalog.get_rarity_rank(&a.fish_type);
         let rank_b = app_state.fish_catalog.get_rarity_rank(&b.fish_type);
         rank_a.cmp(&rank_b)
     });
+    let items = app_state
+        .privacy
+        .lock()
+        .visible(&app_state.fish_catalog, inventory_items);
 
-    let fnp = server::FNP::InventoryShowcase {
-        rem: server.host(),
-        dest: peer,
-        inventory: server::Inventory {
-            items: inventory_items,
-        },
-    };
+    server::FNP::InventoryShowcase {
+        rem: host,
+        dest,
+        inventory: server::Inventory { items },
+    }
+}
 
-    server_sender.send(fnp).await.ok();
+fn handle_server_inventory_denied(app_state: &AppState, peer: Peer, reason: &str) {
+    app_state.inspections.lock().remove(&peer.address());
+    app_state.comparisons.lock().remove(&peer.address());
+    crate::tui::warn(&format!(
+        "{} preferiu não mostrar o inventário: {}.",
+        peer.username(),
+        reason
+    ));
 }
 
 async fn handle_server_inventory_showcase(app_state: &AppState, peer: Peer, inventory: Inventory) {
+    if app_state.comparisons.lock().remove(&peer.address()) {
+        show_trade_suggestions(app_state, &peer, &inventory);
+        return;
+    }
+    let view = app_state
+        .inspections
+        .lock()
+        .remove(&peer.address())
+        .unwrap_or_default();
     println!("-- INVENTÁRIO DE {} --", peer.username().to_uppercase());
     if inventory.items.is_empty() {
         crate::tui::log("[Nenhum peixe aqui]");
     } else {
-        // Style the inventory for display
-        for item in &inventory.items {
-            let style = app_state.fish_catalog.get_style_for_fish(&item.fish_type);
-            println!("> [{}] {}", item.quantity, style.style(&item.fish_type));
-        }
+        show_inventory(app_state, &inventory.items, &view);
+    }
+}
+
+/// Mostra os peixes de um inventário com os filtros e a ordem de `$i`, seguidos de um
+/// resumo por raridade e do valor estimado do que foi mostrado
+fn show_inventory(app_state: &AppState, items: &[InventoryItem], view: &gameplay::InventoryView) {
+    let catalog = &app_state.fish_catalog;
+    let shown = view.apply(catalog, items);
+    if shown.is_empty() {
+        crate::tui::log("[Nenhum peixe com esses filtros]");
+        return;
+    }
+    for item in &shown {
+        let style = catalog.get_style_for_fish(&item.fish_type);
+        println!("> [{}] {}", item.quantity, style.style(&item.fish_type));
+    }
+    let totals = gameplay::tier_totals(catalog, &shown);
+    let tiers = totals
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| **n > 0)
+        .map(|(rank, n)| format!("{} {}", n, gameplay::RARITY_NAMES[rank]))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let value = app_state.prices.lock().value(catalog, &shown);
+    crate::tui::log(&format!(
+        "Total: {} peixe(s) ({}), valor estimado: {} moedas",
+        totals.iter().copied().fold(0, u32::saturating_add),
+        tiers,
+        value
+    ));
+    if view.is_filtered() {
+        crate::tui::log(&format!(
+            "Mostrando {} de {} espécies.",
+            shown.len(),
+            items.len()
+        ));
+    }
+}
+
+/// Responde a oferta sozinho se alguma regra ativa se aplica a ela, registrando a decisão.
+/// Retorna se a oferta foi respondida
+async fn apply_trade_rules(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: &Peer,
+    offer: &Offer,
+    server_sender: Sender<FNP>,
+) -> bool {
+    let Some(rule) =
+        app_state
+            .rules
+            .lock()
+            .evaluate(&app_state.fish_catalog, rem.username(), offer)
+    else {
+        return false;
+    };
+    // Sem peixes para pagar a oferta, ela fica para o usuário decidir
+    let accepted = rule.accepts();
+    if accepted && app_state.find_missing(&offer.requested, None).is_some() {
+        return false;
+    }
+
+    crate::tui::log(&format!(
+        "[regra {}] Oferta de {} ({}) {} automaticamente.",
+        rule,
+        rem.username(),
+        offer,
+        if accepted { "aceita" } else { "recusada" }
+    ));
+    if !handle_ui_tradeconfirm(
+        app_state,
+        server.host(),
+        accepted,
+        offer,
+        rem,
+        server_sender.clone(),
+    )
+    .await
+    {
+        return false;
+    }
+    app_state.rules.lock().record(RuleDecision {
+        time: gameplay::now_secs(),
+        peer: rem.username().to_string(),
+        offer: offer.clone(),
+        rule,
+        accepted,
+    });
+    server_sender
+        .send(FNP::TradeConfirm {
+            rem: server.host(),
+            dest: rem.clone(),
+            response: accepted,
+            offer: offer.clone(),
+        })
+        .await
+        .ok();
+    true
+}
+
+/// Mostra o valor estimado dos dois lados de uma troca, avisando quando ela é desigual
+pub fn show_valuation(
+    app_state: &AppState,
+    incoming: &[InventoryItem],
+    outgoing: &[InventoryItem],
+) {
+    let valuation = app_state
+        .prices
+        .lock()
+        .evaluate(&app_state.fish_catalog, incoming, outgoing);
+    let msg = format!(
+        "Valor estimado: você recebe ~{} e entrega ~{} moeda(s).",
+        valuation.incoming, valuation.outgoing
+    );
+    if !valuation.is_lopsided() {
+        crate::tui::log(&msg);
+    } else if valuation.in_our_favor() {
+        crate::tui::warn(&format!("{} Troca desigual a seu favor.", msg));
+    } else {
+        crate::tui::warn(&format!("{} Atenção: troca desigual contra você!", msg));
+    }
+}
+
+/// Cruza o inventário do peer com a nossa cesta e mostra as trocas sugeridas já no formato
+/// do `$t`, prontas para enviar
+fn show_trade_suggestions(app_state: &AppState, peer: &Peer, inventory: &Inventory) {
+    let fishes: Vec<String> = app_state.basket.lock().map().keys().cloned().collect();
+    let mine: HashMap<String, u32> = fishes
+        .into_iter()
+        .map(|fish| {
+            let available = app_state.available(&fish, None);
+            (fish, available)
+        })
+        .collect();
+    let coins = app_state.available(gameplay::COIN_ITEM, None);
+    let suggestions =
+        gameplay::suggest_trades(&app_state.fish_catalog, &mine, &inventory.items, coins);
+
+    println!(
+        "-- SUGESTÕES DE TROCA COM {} --",
+        peer.username().to_uppercase()
+    );
+    if suggestions.is_empty() {
+        crate::tui::log("[Nenhuma troca em que os dois completam a coleção]");
+    }
+    for offer in suggestions {
+        println!("> $t {} {}", peer.username(), offer);
     }
 }
 
-async fn handle_server_tradeoffer(app_state: &AppState, rem: Peer, offer: Offer) {
+async fn handle_server_tradeoffer(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    offer: Offer,
+    server_sender: Sender<FNP>,
+) {
     // Adicionando ao buffer de ofertas recebidas
     app_state
         .offer_buffers
         .lock()
         .offers_received
         .insert(rem.address(), offer.clone());
+    if settle_duel(app_state, server, &rem, &offer, server_sender.clone()).await {
+        return;
+    }
+    if apply_trade_rules(app_state, server, &rem, &offer, server_sender).await {
+        return;
+    }
     // Exibindo os peixes ofertados e requisitados pelo remetente
     println!("{} quer realizar a seguinte troca:", rem.username());
-    offer.offered.into_iter().for_each(|f| {
-        let style = app_state.fish_catalog.get_style_for_fish(&f.fish_type);
-        println!("> {} {}(s)", f.quantity, style.style(&f.fish_type));
-    });
+    offer
+        .offered
+        .iter()
+        .for_each(|item| show_item(app_state, ">", item));
     println!("por");
-    offer.requested.into_iter().for_each(|f| {
-        let style = app_state.fish_catalog.get_style_for_fish(&f.fish_type);
-        println!("> {} {}(s)", f.quantity, style.style(&f.fish_type))
-    });
+    offer
+        .requested
+        .iter()
+        .for_each(|item| show_item(app_state, ">", item));
+    show_valuation(app_state, &offer.offered, &offer.requested);
+    // Avisamos se a oferta atende alguma das nossas ordens de compra
+    let matched: Vec<String> = app_state
+        .orders
+        .lock()
+        .orders
+        .values()
+        .filter(|o| {
+            o.mine
+                && o.side == OrderSide::Compra
+                && offer
+                    .offered
+                    .iter()
+                    .any(|i| i.fish_type == o.item.fish_type)
+        })
+        .map(|o| o.id.clone())
+        .collect();
+    for id in matched {
+        crate::tui::log(&format!("Esta oferta atende sua ordem de compra {}.", id));
+    }
     crate::tui::log(&format!(
         "Digite '$c [s]im {}' para aceitar, ou '$c [n]ao {}' para recusar",
         rem.username(),
@@ -303,41 +1760,52 @@ async fn handle_server_tradeoffer(app_state: &AppState, rem: Peer, offer: Offer)
 
 async fn handle_server_tradeconfirm(
     app_state: &AppState,
+    server: &ServerBackend,
     response: bool,
     rem: Peer,
     offer: &Offer,
+    server_sender: Sender<FNP>,
 ) {
     if response {
         crate::tui::log(&format!(
             "{} aceitou sua oferta de troca :)",
             rem.username()
         ));
-        let mut inventory = app_state.basket.lock();
-        // Removendo os peixes que você deu
-        for item in &offer.offered {
-            let style = app_state.fish_catalog.get_style_for_fish(&item.fish_type);
-            println!("- {} {}(s)", item.quantity, style.style(&item.fish_type));
-            if let Some(count) = inventory.map_mut().get_mut(&item.fish_type) {
-                *count -= item.quantity;
-                if *count == 0 {
-                    inventory.map_mut().remove(&item.fish_type);
-                }
-            }
+        // Os itens estavam reservados para esta oferta, então não a descontamos
+        if let Some((item, available)) = app_state.find_missing(&offer.offered, Some(rem.address()))
+        {
+            crate::tui::err(&format!(
+                "* Atenção: você só tinha {} {} dos {} ofertados.",
+                available, item.fish_type, item.quantity
+            ));
         }
-        // Adicionando os peixes que você recebeu
-        for item in &offer.requested {
-            let style = app_state.fish_catalog.get_style_for_fish(&item.fish_type);
-            println!("+ {} {}(s)", item.quantity, style.style(&item.fish_type));
-            *inventory
-                .map_mut()
-                .entry(item.fish_type.clone())
-                .or_insert(0) += item.quantity;
+        // O peer já executou a troca, então ela acontece mesmo que a cesta passe do limite
+        let over = !app_state.has_room(&offer.offered, &offer.requested);
+        execute_trade(app_state, &offer.offered, &offer.requested);
+        if over {
+            warn_full_basket(app_state);
         }
+        trade_completed(
+            app_state,
+            server.host(),
+            rem.username(),
+            &offer.offered,
+            &offer.requested,
+            server_sender,
+        )
+        .await;
     } else {
         crate::tui::log(&format!(
             "{} recusou sua oferta de troca :(",
             rem.username()
         ));
+        record_history(
+            app_state,
+            TradeOutcome::Recusada,
+            rem.username(),
+            &offer.offered,
+            &offer.requested,
+        );
     }
     app_state
         .offer_buffers
@@ -346,6 +1814,69 @@ async fn handle_server_tradeconfirm(
         .remove(&rem.address());
 }
 
+/// Executa uma troca: remove da cesta os itens dados e adiciona os recebidos.
+/// Itens de moedas são debitados e creditados na carteira
+pub fn execute_trade(app_state: &AppState, given: &[InventoryItem], received: &[InventoryItem]) {
+    let mut basket = app_state.basket.lock();
+    let mut coins = app_state.coins.lock();
+    for item in given {
+        show_item(app_state, "-", item);
+        if gameplay::is_coin(&item.fish_type) {
+            *coins = coins.saturating_sub(item.quantity);
+        } else if let Some(count) = basket.map_mut().get_mut(&item.fish_type) {
+            *count = count.saturating_sub(item.quantity);
+            if *count == 0 {
+                basket.map_mut().remove(&item.fish_type);
+            }
+        }
+    }
+    for item in received {
+        show_item(app_state, "+", item);
+        if gameplay::is_coin(&item.fish_type) {
+            *coins = coins.saturating_add(item.quantity);
+        } else {
+            let count = basket.map_mut().entry(item.fish_type.clone()).or_insert(0);
+            *count = count.saturating_add(item.quantity);
+        }
+    }
+}
+
+/// Relato das nossas pescas na janela atual, enviado a toda a rede no modo de escassez
+pub fn catch_report(host: Peer, scarcity: &gameplay::Scarcity) -> FNP {
+    FNP::CatchReport {
+        rem: host,
+        catches: scarcity
+            .mine
+            .iter()
+            .map(|(fish, count)| InventoryItem::new(fish.clone(), *count))
+            .collect(),
+    }
+}
+
+/// Avisa que a cesta está cheia e como liberar espaço
+pub fn warn_full_basket(app_state: &AppState) {
+    crate::tui::warn(&format!(
+        "Sua cesta está cheia ({}/{})! Solte peixes com '$soltar <peixe> [n]', venda com '$vender' ou aumente o espaço com '$aquario comprar'.",
+        app_state.basket.lock().total(),
+        app_state.capacity()
+    ));
+}
+
+/// Exibe um item de oferta (peixe ou moedas) precedido de um prefixo
+fn show_item(app_state: &AppState, prefix: &str, item: &InventoryItem) {
+    if gameplay::is_coin(&item.fish_type) {
+        println!("{} {} moeda(s)", prefix, item.quantity);
+    } else {
+        let style = app_state.fish_catalog.get_style_for_fish(&item.fish_type);
+        println!(
+            "{} {} {}(s)",
+            prefix,
+            item.quantity,
+            style.style(&item.fish_type)
+        );
+    }
+}
+
 async fn handle_server_peerlist(
     peers: &[Peer],
     server: &ServerBackend,
@@ -385,25 +1916,2099 @@ async fn handle_server_peerlist(
     }
 }
 
-async fn handle_ui_inventory_inspection(app_state: &AppState) {
-    let inventory = app_state.basket.lock();
-    println!("-- INVENTÁRIO --");
-    if inventory.map().is_empty() {
-        crate::tui::log("[Nenhum peixe aqui, digite $[p]esca para pescar]");
+async fn handle_server_tournament_announce(
+    app_state: &AppState,
+    tournament: Tournament,
+    event_sender: Sender<Event>,
+) {
+    let now = gameplay::now_secs();
+    if !tournament.is_valid() || tournament.end <= now {
+        return;
+    }
+    {
+        let mut current = app_state.tournament.lock();
+        // Ignoramos anúncios repetidos e torneios concorrentes
+        if current.as_ref().is_some_and(|t| !t.finished) {
+            return;
+        }
+        crate::tui::log(&format!(
+            "{} anunciou o torneio {}! Duração: {}s, raridade mínima: {}.",
+            tournament.organizer.username(),
+            tournament.id,
+            tournament.end - tournament.start,
+            gameplay::RARITY_NAMES[tournament.min_tier as usize]
+        ));
+        crate::tui::log("Digite '$torneio entrar' para participar.");
+        *current = Some(tournament.clone());
+    }
+    schedule_tournament(&tournament, event_sender, 0);
+}
+
+/// Agenda os eventos de início e fim de um torneio. `grace` atrasa o fim em alguns segundos
+pub fn schedule_tournament(tournament: &Tournament, event_sender: Sender<Event>, grace: u64) {
+    let now = gameplay::now_secs();
+    if tournament.start > now {
+        crate::event::schedule(
+            event_sender.clone(),
+            Duration::from_secs(tournament.start - now),
+            Event::TorneioInicio(tournament.id.clone()),
+        );
+    }
+    crate::event::schedule(
+        event_sender,
+        Duration::from_secs(tournament.end.saturating_sub(now) + grace),
+        Event::TorneioFim(tournament.id.clone()),
+    );
+}
+
+async fn handle_server_tournament_join(app_state: &AppState, rem: Peer, id: &str) {
+    if let Some(t) = app_state
+        .tournament
+        .lock()
+        .as_mut()
+        .filter(|t| t.id == id && !t.finished)
+    {
+        t.record_score(rem.username(), 0);
+        crate::tui::log(&format!("{} entrou no torneio.", rem.username()));
+    }
+}
+
+async fn handle_server_tournament_score(app_state: &AppState, rem: Peer, id: &str, score: u32) {
+    if let Some(t) = app_state
+        .tournament
+        .lock()
+        .as_mut()
+        .filter(|t| t.id == id && !t.finished)
+    {
+        t.record_score(rem.username(), score);
+    }
+}
+
+/// Resultado final de um torneio, que só vale se vier do organizador
+async fn handle_server_tournament_result(
+    app_state: &AppState,
+    rem: Peer,
+    id: &str,
+    standings: &Standings,
+) {
+    let mut tournament = app_state.tournament.lock();
+    if let Some(t) = tournament
+        .as_mut()
+        .filter(|t| t.id == id && t.organizer.address() == rem.address())
+    {
+        t.finished = true;
+        show_standings(id, standings);
+    }
+}
+
+async fn handle_server_auction_announce(app_state: &AppState, auction: Auction) {
+    let now = gameplay::now_secs();
+    // Um leilão só é anunciado pelo vendedor que criou o id, e anúncios repetidos não
+    // mudam o leilão que já conhecemos
+    if auction.end <= now || !gameplay::made_by(&auction.id, auction.seller.username()) {
+        return;
+    }
+    let mut auctions = app_state.auctions.lock();
+    if auctions.auctions.contains_key(&auction.id) {
+        return;
+    }
+    let style = app_state
+        .fish_catalog
+        .get_style_for_fish(&auction.item.fish_type);
+    crate::tui::log(&format!(
+        "{} abriu o leilão {}: {} {}(s), lance mínimo de {} moeda(s), termina em {}s.",
+        auction.seller.username(),
+        auction.id,
+        auction.item.quantity,
+        style.style(&auction.item.fish_type),
+        auction.min_bid,
+        auction.end - now
+    ));
+    crate::tui::log(&format!(
+        "Digite '$lance {} <valor>' para dar um lance.",
+        auction.id
+    ));
+    auctions.auctions.insert(auction.id.clone(), auction);
+}
+
+async fn handle_server_auction_bid(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    amount: u32,
+    server_sender: Sender<FNP>,
+) {
+    let update = {
+        let mut auctions = app_state.auctions.lock();
+        let Some(auction) = auctions
+            .auctions
+            .get_mut(id)
+            .filter(|a| a.mine && gameplay::now_secs() < a.end)
+        else {
+            return;
+        };
+        if amount >= auction.next_min_bid() {
+            auction.bids.push((rem.clone(), amount));
+            auction.highest = Some((rem.username().to_string(), amount));
+            crate::tui::log(&format!(
+                "{} deu um lance de {} moeda(s) no leilão {}.",
+                rem.username(),
+                amount,
+                id
+            ));
+        }
+        // Sempre anunciamos o maior lance atual, assim um lance recusado libera as moedas
+        // de quem o deu
+        auction
+            .highest
+            .clone()
+            .map(|(bidder, amount)| FNP::AuctionUpdate {
+                rem: server.host(),
+                id: id.to_string(),
+                bidder,
+                amount,
+            })
+    };
+    if let Some(update) = update {
+        server_sender.send(update).await.ok();
+    }
+}
+
+async fn handle_server_auction_update(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    bidder: String,
+    amount: u32,
+) {
+    let mut auctions = app_state.auctions.lock();
+    // Só o vendedor anuncia o maior lance
+    let Some(auction) = auctions
+        .auctions
+        .get_mut(id)
+        .filter(|a| a.seller.address() == rem.address())
+    else {
+        return;
+    };
+    // As moedas reservadas são as do lance que nós mesmos demos, nunca o valor anunciado
+    if bidder == server.host().username() {
+        crate::tui::log(&format!(
+            "Seu lance de {} moeda(s) é o maior do leilão {}.",
+            amount, id
+        ));
+    } else {
+        if auction.my_bid > 0 {
+            crate::tui::log(&format!("Seu lance no leilão {} foi superado.", id));
+            auction.my_bid = 0;
+        }
+        crate::tui::log(&format!(
+            "Maior lance no leilão {}: {} moeda(s) de {}.",
+            id, amount, bidder
+        ));
+    }
+    auction.highest = Some((bidder, amount));
+}
+
+async fn handle_server_auction_close(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    winner: Option<String>,
+    amount: u32,
+    server_sender: Sender<FNP>,
+) {
+    // Só o vendedor encerra o leilão. Removemos o leilão antes de qualquer coisa, liberando
+    // as moedas reservadas no lance
+    let auction = {
+        let mut house = app_state.auctions.lock();
+        match house.auctions.get(id) {
+            Some(a) if a.seller.username() == rem.username() => house.auctions.remove(id),
+            _ => None,
+        }
+    };
+    let Some(auction) = auction else {
+        return;
+    };
+    let Some(winner) = winner else {
+        crate::tui::log(&format!("O leilão {} terminou sem lances.", id));
+        return;
+    };
+    if winner != server.host().username() {
+        crate::tui::log(&format!(
+            "O leilão {} terminou, {} venceu com {} moeda(s).",
+            id, winner, amount
+        ));
+        // O lance vencedor também entra no histórico de preços
+        if let Some(price) = amount.checked_div(auction.item.quantity) {
+            app_state
+                .prices
+                .lock()
+                .record_price(&auction.item.fish_type, price);
+        }
+        return;
+    }
+
+    // Vencemos: a venda é concluída pelo mesmo caminho de uma troca aceita
+    crate::tui::log(&format!("Você venceu o leilão {}!", id));
+    let offer = Offer {
+        offered: vec![auction.item],
+        requested: vec![InventoryItem::new(gameplay::COIN_ITEM.to_string(), amount)],
+    };
+    accept_remote_offer(app_state, server, rem, offer, server_sender).await;
+}
+
+/// Aceita uma oferta que o outro peer já reservou para nós (leilão vencido, oferta pública
+/// pega...). Executa a troca do nosso lado e confirma com um `TradeConfirm`, que o outro
+/// peer trata como uma troca aceita comum
+async fn accept_remote_offer(
+    app_state: &AppState,
+    server: &ServerBackend,
+    owner: Peer,
+    offer: Offer,
+    server_sender: Sender<FNP>,
+) {
+    let response = if let Some((item, available)) = app_state.find_missing(&offer.requested, None) {
+        crate::tui::err(&format!(
+            "Troca inválida! Você só tem {} de {} {}.",
+            available, item.quantity, item.fish_type
+        ));
+        false
+    } else if !app_state.has_room(&offer.requested, &offer.offered) {
+        warn_full_basket(app_state);
+        false
+    } else {
+        execute_trade(app_state, &offer.requested, &offer.offered);
+        trade_completed(
+            app_state,
+            server.host(),
+            owner.username(),
+            &offer.requested,
+            &offer.offered,
+            server_sender.clone(),
+        )
+        .await;
+        true
+    };
+    server_sender
+        .send(FNP::TradeConfirm {
+            rem: server.host(),
+            dest: owner,
+            response,
+            offer,
+        })
+        .await
+        .ok();
+}
+
+/// O prazo do nosso leilão acabou. Vende para o maior lance de alguém que ainda está na
+/// rede, reservando o peixe como uma oferta feita até o vencedor confirmar
+pub async fn handle_auction_end(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    server_sender: Sender<FNP>,
+) {
+    let Some(auction) = app_state
+        .auctions
+        .lock()
+        .auctions
+        .remove(id)
+        .filter(|a| a.mine)
+    else {
+        return;
+    };
+
+    let mut winner = None;
+    for (bidder, amount) in auction.ranked_bids() {
+        // Uma oferta pendente nossa para o mesmo peer seria substituída, então ele passa a vez
+        if app_state
+            .offer_buffers
+            .lock()
+            .offers_made
+            .contains_key(&bidder.address())
+        {
+            crate::tui::warn(&format!(
+                "{} tem uma oferta sua pendente, o leilão {} passa para o próximo lance.",
+                bidder.username(),
+                id
+            ));
+            continue;
+        }
+        let online = server
+            .peer_store()
+            .get_by_username(bidder.username())
+            .await
+            .is_some_and(|i| i.peer.address() == bidder.address());
+        if online {
+            winner = Some((bidder, amount));
+            break;
+        }
+    }
+
+    let close = match winner {
+        Some((bidder, amount)) => {
+            crate::tui::log(&format!(
+                "O leilão {} terminou, {} venceu com {} moeda(s). Aguardando o pagamento...",
+                id,
+                bidder.username(),
+                amount
+            ));
+            app_state.offer_buffers.lock().offers_made.insert(
+                bidder.address(),
+                Offer {
+                    offered: vec![auction.item],
+                    requested: vec![InventoryItem::new(gameplay::COIN_ITEM.to_string(), amount)],
+                },
+            );
+            FNP::AuctionClose {
+                rem: server.host(),
+                id: id.to_string(),
+                winner: Some(bidder.username().to_string()),
+                amount,
+            }
+        }
+        None => {
+            crate::tui::log(&format!("O leilão {} terminou sem lances.", id));
+            FNP::AuctionClose {
+                rem: server.host(),
+                id: id.to_string(),
+                winner: None,
+                amount: 0,
+            }
+        }
+    };
+    server_sender.send(close).await.ok();
+}
+
+async fn handle_server_public_offer(app_state: &AppState, public_offer: PublicOffer) {
+    let mut board = app_state.board.lock();
+    if board.offers.contains_key(&public_offer.id) {
+        return;
+    }
+    println!(
+        "{} publicou no mural [{}]:",
+        public_offer.owner.username(),
+        public_offer.id
+    );
+    public_offer
+        .offer
+        .offered
+        .iter()
+        .for_each(|item| show_item(app_state, ">", item));
+    println!("por");
+    public_offer
+        .offer
+        .requested
+        .iter()
+        .for_each(|item| show_item(app_state, ">", item));
+    crate::tui::log(&format!(
+        "Digite '$mural pegar {}' para ficar com a oferta.",
+        public_offer.id
+    ));
+    board.offers.insert(public_offer.id.clone(), public_offer);
+}
+
+/// Alguém quer nossa oferta pública. O primeiro pedido leva, e avisamos toda a rede que a
+/// oferta saiu do mural
+async fn handle_server_public_offer_claim(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    server_sender: Sender<FNP>,
+) {
+    // Uma oferta pendente nossa para o mesmo peer seria substituída, a oferta pública
+    // continua no mural
+    if app_state
+        .offer_buffers
+        .lock()
+        .offers_made
+        .contains_key(&rem.address())
+    {
+        crate::tui::warn(&format!(
+            "{} tentou pegar a oferta pública {}, mas tem uma oferta sua pendente.",
+            rem.username(),
+            id
+        ));
+        return;
+    }
+    let Some(public_offer) = app_state.board.lock().offers.remove(id).filter(|o| o.mine) else {
+        return;
+    };
+    crate::tui::log(&format!(
+        "{} pegou sua oferta pública {}. Aguardando a confirmação...",
+        rem.username(),
+        id
+    ));
+    // Os peixes continuam reservados, agora como uma oferta feita a quem pegou
+    app_state
+        .offer_buffers
+        .lock()
+        .offers_made
+        .insert(rem.address(), public_offer.offer);
+    server_sender
+        .send(FNP::PublicOfferClosed {
+            rem: server.host(),
+            id: id.to_string(),
+            taker: Some(rem.username().to_string()),
+        })
+        .await
+        .ok();
+}
+
+async fn handle_server_public_offer_closed(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    taker: Option<String>,
+    server_sender: Sender<FNP>,
+) {
+    // Só o dono encerra a oferta
+    let public_offer = {
+        let mut board = app_state.board.lock();
+        match board.offers.get(id) {
+            Some(o) if o.owner.username() == rem.username() => board.offers.remove(id),
+            _ => None,
+        }
+    };
+    let Some(public_offer) = public_offer else {
+        return;
+    };
+    match taker {
+        Some(taker) if taker == server.host().username() => {
+            crate::tui::log(&format!("Você ficou com a oferta pública {}!", id));
+            accept_remote_offer(app_state, server, rem, public_offer.offer, server_sender).await;
+        }
+        Some(taker) if public_offer.claimed => {
+            crate::tui::log(&format!(
+                "A oferta pública {} já foi pega por {}.",
+                id, taker
+            ));
+        }
+        Some(_) => (),
+        None => {
+            crate::tui::log(&format!("A oferta pública {} foi retirada do mural.", id));
+        }
+    }
+}
+
+/// Monta o anúncio de uma ordem
+fn order_announce(host: Peer, order: Order) -> FNP {
+    FNP::OrderAnnounce {
+        rem: host,
+        id: order.id,
+        side: order.side,
+        item: order.item,
+        price: order.price,
+    }
+}
+
+async fn handle_server_order_announce(
+    app_state: &AppState,
+    server: &ServerBackend,
+    order: Order,
+    server_sender: Sender<FNP>,
+) {
+    let is_new = {
+        let mut orders = app_state.orders.lock();
+        // Só o dono de uma ordem pode anunciá-la de novo
+        if orders
+            .orders
+            .get(&order.id)
+            .is_some_and(|o| o.owner.address() != order.owner.address())
+        {
+            return;
+        }
+        orders
+            .orders
+            .insert(order.id.clone(), order.clone())
+            .is_none()
+    };
+    if is_new {
+        crate::tui::log(&format!(
+            "{} registrou a ordem de {} {}: {} por {} cada.",
+            order.owner.username(),
+            order.side,
+            order.id,
+            order.item,
+            order.price
+        ));
+    }
+    for offer in match_orders(app_state, server.host()) {
+        server_sender.send(offer).await.ok();
+    }
+}
+
+async fn handle_server_order_cancel(app_state: &AppState, rem: Peer, id: &str) {
+    let mut orders = app_state.orders.lock();
+    if orders
+        .orders
+        .get(id)
+        .is_some_and(|o| o.owner.address() == rem.address())
+    {
+        orders.orders.remove(id);
+    }
+}
+
+/// Procura ordens de compra que casam com as nossas ordens de venda e propõe a troca pelo
+/// fluxo normal de `TradeOffer`. Quem vende propõe, assim os dois lados nunca propõem a
+/// mesma troca ao mesmo tempo
+pub fn match_orders(app_state: &AppState, host: Peer) -> Vec<FNP> {
+    let matches: Vec<(Order, Order)> = {
+        let orders = app_state.orders.lock();
+        orders
+            .orders
+            .values()
+            .filter(|o| o.mine && o.side == OrderSide::Venda)
+            .filter_map(|sell| orders.find_match(sell).map(|buy| (sell.clone(), buy)))
+            .collect()
+    };
+
+    let mut proposals = Vec::new();
+    for (sell, buy) in matches {
+        // Só uma oferta aberta por peer, e só com os peixes que temos livres
+        if app_state
+            .offer_buffers
+            .lock()
+            .offers_made
+            .contains_key(&buy.owner.address())
+        {
+            continue;
+        }
+        let quantity = sell
+            .item
+            .quantity
+            .min(buy.item.quantity)
+            .min(app_state.available(&sell.item.fish_type, None));
+        if quantity == 0 {
+            continue;
+        }
+        let offer = Offer {
+            offered: vec![InventoryItem::new(sell.item.fish_type.clone(), quantity)],
+            requested: vec![InventoryItem::new(
+                sell.price.fish_type.clone(),
+                sell.price.quantity * quantity,
+            )],
+        };
+        crate::tui::log(&format!(
+            "Sua ordem {} casou com a ordem {} de {}, oferta enviada: {}",
+            sell.id,
+            buy.id,
+            buy.owner.username(),
+            offer
+        ));
+        app_state
+            .offer_buffers
+            .lock()
+            .offers_made
+            .insert(buy.owner.address(), offer.clone());
+        proposals.push(FNP::TradeOffer {
+            rem: host.clone(),
+            dest: buy.owner,
+            offer,
+        });
+    }
+    proposals
+}
+
+async fn handle_server_fish_search(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: String,
+    search: Search,
+    hops: u8,
+    server_sender: Sender<FNP>,
+    event_sender: Sender<Event>,
+) {
+    // A mesma busca pode chegar por vários caminhos, só tratamos a primeira
+    {
+        let mut searches = app_state.searches.lock();
+        if searches.searches.contains_key(&id) {
+            return;
+        }
+        searches.searches.insert(id.clone(), search.clone());
+    }
+    let Search {
+        fish,
+        back: Some(back),
+        ..
+    } = search
+    else {
+        return;
+    };
+    // Guardamos o caminho de volta só enquanto a busca de origem espera respostas
+    crate::event::schedule(
+        event_sender,
+        gameplay::SEARCH_TIMEOUT,
+        Event::ProcuraFim(id.clone()),
+    );
+
+    // Não sabemos quem começou a busca, então ela é respondida como a um desconhecido
+    let quantity = visible_quantity(app_state, &fish, false);
+    if quantity > 0 {
+        server_sender
+            .send(FNP::FishSearchResult {
+                rem: server.host(),
+                dest: back,
+                id: id.clone(),
+                owner: server.host().username().to_string(),
+                quantity,
+            })
+            .await
+            .ok();
+    }
+    // Saltos vindos da rede não passam do limite, mesmo que o peer de origem peça mais
+    let hops = hops.min(gameplay::MAX_SEARCH_HOPS);
+    if hops > 0 {
+        server_sender
+            .send(FNP::FishSearch {
+                rem: server.host(),
+                id,
+                fish,
+                hops: hops - 1,
+            })
+            .await
+            .ok();
+    }
+}
+
+async fn handle_server_fish_search_result(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    owner: String,
+    quantity: u32,
+    server_sender: Sender<FNP>,
+) {
+    let quantity = quantity.min(gameplay::MAX_SEARCH_QUANTITY);
+    let back = {
+        let mut searches = app_state.searches.lock();
+        let Some(search) = searches.searches.get_mut(id) else {
+            return;
+        };
+        match &search.back {
+            None => {
+                search.results.insert(owner, quantity);
+                return;
+            }
+            Some(back) => back.clone(),
+        }
+    };
+    // A busca não é nossa, devolvemos a resposta pelo caminho de onde ela veio
+    server_sender
+        .send(FNP::FishSearchResult {
+            rem: server.host(),
+            dest: back,
+            id: id.to_string(),
+            owner,
+            quantity,
+        })
+        .await
+        .ok();
+}
+
+/// Fim do prazo de uma busca: mostra o resultado se a busca é nossa
+pub async fn handle_search_end(app_state: &AppState, id: &str) {
+    let Some(search) = app_state.searches.lock().searches.remove(id) else {
+        return;
+    };
+    if !search.is_mine() {
+        return;
+    }
+    let style = app_state.fish_catalog.get_style_for_fish(&search.fish);
+    println!("-- QUEM TEM {} --", style.style(&search.fish));
+    if search.results.is_empty() {
+        crate::tui::log("[Ninguém na rede respondeu com esse peixe]");
+        return;
+    }
+    let mut results: Vec<(String, u32)> = search.results.into_iter().collect();
+    results.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    for (owner, quantity) in &results {
+        println!("> [{}] {}", quantity, owner);
+    }
+    let total = results.iter().fold(0u32, |a, (_, q)| a.saturating_add(*q));
+    crate::tui::log(&format!(
+        "{} peixe(s) com {} pescador(es).",
+        total,
+        results.len()
+    ));
+}
+
+async fn handle_server_wishlist(app_state: &AppState, rem: Peer, fish: Vec<String>) {
+    app_state
+        .wishlist
+        .lock()
+        .set_peer_list(rem, fish.into_iter().collect());
+}
+
+async fn handle_server_wish_notify(app_state: &AppState, rem: Peer, fish: &str, quantity: u32) {
+    // Só avisamos se o peixe continua na nossa lista
+    if !app_state.wishlist.lock().mine.contains(fish) {
+        return;
+    }
+    let style = app_state.fish_catalog.get_style_for_fish(fish);
+    crate::tui::log(&format!(
+        "{} conseguiu um(a) {} da sua lista de desejos e tem {} livre(s).",
+        rem.username(),
+        style.style(fish),
+        quantity
+    ));
+    crate::tui::log(&format!(
+        "Proponha uma troca com '$t {} peixe|n > {}|1'",
+        rem.username(),
+        fish
+    ));
+}
+
+async fn handle_server_gift(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: String,
+    item: InventoryItem,
+    server_sender: Sender<FNP>,
+) {
+    // Presentes de peers silenciados são recusados se a regra estiver ativa
+    let muted = is_muted(app_state, rem.username());
+    let valid = item.quantity > 0
+        && (gameplay::is_coin(&item.fish_type) || app_state.fish_catalog.contains(&item.fish_type));
+    let room = app_state.has_room(&[], std::slice::from_ref(&item));
+    if valid && !muted && !room {
+        crate::tui::log(&format!(
+            "{} tentou te dar {}, mas não há espaço na cesta.",
+            rem.username(),
+            item
+        ));
+        warn_full_basket(app_state);
+    }
+    let accepted = valid && !muted && room;
+
+    if accepted {
+        crate::tui::log(&format!("{} te deu um presente!", rem.username()));
+        execute_trade(app_state, &[], std::slice::from_ref(&item));
+        record_history(
+            app_state,
+            TradeOutcome::Concluida,
+            rem.username(),
+            &[],
+            std::slice::from_ref(&item),
+        );
+        if !gameplay::is_coin(&item.fish_type) {
+            notify_wishers(
+                app_state,
+                server.host(),
+                &item.fish_type,
+                server_sender.clone(),
+            )
+            .await;
+        }
+    }
+    server_sender
+        .send(FNP::GiftAck {
+            rem: server.host(),
+            dest: rem,
+            id,
+            accepted,
+        })
+        .await
+        .ok();
+}
+
+fn handle_server_gift_ack(app_state: &AppState, rem: Peer, id: &str, accepted: bool) {
+    let gift = {
+        let mut gifts = app_state.gifts.lock();
+        if gifts
+            .pending
+            .get(id)
+            .is_none_or(|g| g.to.address() != rem.address())
+        {
+            return;
+        }
+        gifts.pending.remove(id)
+    };
+    let Some(gift) = gift else {
+        return;
+    };
+    let given = std::slice::from_ref(&gift.item);
+    if accepted {
+        crate::tui::log(&format!("{} recebeu o seu presente.", rem.username()));
+        execute_trade(app_state, given, &[]);
+        record_history(
+            app_state,
+            TradeOutcome::Concluida,
+            rem.username(),
+            given,
+            &[],
+        );
+    } else {
+        crate::tui::warn(&format!(
+            "{} recusou o seu presente de {}.",
+            rem.username(),
+            gift.item
+        ));
+        record_history(
+            app_state,
+            TradeOutcome::Recusada,
+            rem.username(),
+            given,
+            &[],
+        );
+    }
+}
+
+/// Estado completo de uma guilda, anunciado pelo líder a toda a rede
+pub fn guild_state(host: Peer, guild: &Guild) -> FNP {
+    FNP::GuildState {
+        rem: host,
+        name: guild.name.clone(),
+        leader: guild.leader.clone(),
+        members: guild.members.iter().cloned().collect(),
+        quorum: guild.quorum,
+        basket: guild
+            .basket
+            .iter()
+            .map(|(fish, n)| InventoryItem::new(fish.clone(), *n))
+            .collect(),
+        pending: guild.pending.clone(),
+        version: guild.version,
+    }
+}
+
+/// Avisa o que mudou na nossa guilda entre duas versões do estado
+fn log_guild_changes(old: Option<&Guild>, new: Option<&Guild>, me: &str) {
+    let Some(guild) = new.or(old) else {
+        return;
+    };
+    let member = |g: Option<&Guild>| g.is_some_and(|g| g.members.contains(me));
+    if !member(old) && !member(new) {
+        return;
+    }
+    let name = &guild.name;
+    let members = |g: Option<&Guild>| g.map(|g| g.members.clone()).unwrap_or_default();
+    let (before, after) = (members(old), members(new));
+    for m in after.difference(&before).filter(|m| *m != me) {
+        crate::tui::log(&format!("{} entrou na guilda {}.", m, name));
+    }
+    for m in before.difference(&after).filter(|m| *m != me) {
+        crate::tui::log(&format!("{} saiu da guilda {}.", m, name));
+    }
+    let Some(new) = new else {
+        crate::tui::log(&format!("A guilda {} foi desfeita.", name));
+        return;
+    };
+    if old.is_some_and(|o| o.leader != new.leader) {
+        if new.leader == me {
+            crate::tui::log(&format!("Você agora lidera a guilda {}.", name));
+        } else {
+            crate::tui::log(&format!("{} agora lidera a guilda {}.", new.leader, name));
+        }
+    }
+    for w in &new.pending {
+        let known = old.is_some_and(|o| o.pending.iter().any(|p| p.id == w.id));
+        if !known && w.requester != me {
+            crate::tui::log(&format!(
+                "{} pediu para sacar {} do cesto da guilda. Digite '$guilda aprovar {}' ou '$guilda recusar {}'.",
+                w.requester, w.item, w.id, w.id
+            ));
+        }
+    }
+}
+
+async fn handle_server_guild_state(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    guild: Guild,
+) {
+    let online = online_usernames(server).await;
+    let old = app_state
+        .guilds
+        .lock()
+        .update(guild.clone(), rem.username(), &online);
+    if let Some(old) = old {
+        let new = (!guild.members.is_empty()).then_some(&guild);
+        log_guild_changes(old.as_ref(), new, server.host().username());
+    }
+}
+
+/// Pedido de um membro ao líder da guilda. Só o líder muda o estado, então todas as
+/// réplicas recebem as mudanças na mesma ordem
+async fn handle_guild_request(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    name: &str,
+    id: String,
+    action: GuildAction,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let online = online_usernames(server).await;
+    // O cesto só guarda peixes do catálogo
+    let unknown = matches!(
+        &action,
+        GuildAction::Depositar(item) if !app_state.fish_catalog.contains(&item.fish_type)
+    );
+    let result = {
+        let mut guilds = app_state.guilds.lock();
+        match guilds.guilds.get_mut(name) {
+            Some(guild) if guild.leader == host.username() && unknown => {
+                Err("o cesto da guilda só guarda peixes".to_string())
+            }
+            Some(guild) if guild.leader == host.username() => {
+                let old = guild.clone();
+                let result = guild.apply(rem.username(), &id, &action);
+                // Se o líder saiu, a liderança vai para o primeiro membro online
+                if guild.leader != old.leader
+                    && let Some(next) = guild.members.iter().find(|m| online.contains(*m))
+                {
+                    guild.leader = next.clone();
+                }
+                let new = guild.clone();
+                if new.members.is_empty() {
+                    guilds.guilds.remove(name);
+                }
+                result.map(|replies| (old, new, replies))
+            }
+            _ => Err(format!("{} não lidera a guilda", host.username())),
+        }
+    };
+    let (old, guild, replies) = match result {
+        Ok(result) => result,
+        Err(reason) if rem.address() == host.address() => {
+            app_state.guilds.lock().requests.remove(&id);
+            crate::tui::err(&format!(
+                "A guilda {} recusou o seu pedido de {}: {}.",
+                name, action, reason
+            ));
+            return;
+        }
+        Err(_) => {
+            let reply = GuildReply {
+                id,
+                requester: rem.username().to_string(),
+                accepted: false,
+            };
+            guild_reply(app_state, server, name, reply, server_sender).await;
+            return;
+        }
+    };
+    let new = (!guild.members.is_empty()).then_some(&guild);
+    log_guild_changes(Some(&old), new, host.username());
+    for reply in replies {
+        guild_reply(app_state, server, name, reply, server_sender.clone()).await;
+    }
+    server_sender.send(guild_state(host, &guild)).await.ok();
+}
+
+/// Entrega a resposta do líder a quem pediu, que pode ser o próprio líder
+async fn guild_reply(
+    app_state: &AppState,
+    server: &ServerBackend,
+    name: &str,
+    reply: GuildReply,
+    server_sender: Sender<FNP>,
+) {
+    if reply.requester == server.host().username() {
+        handle_guild_ack(
+            app_state,
+            server,
+            name,
+            &reply.id,
+            reply.accepted,
+            server_sender,
+        )
+        .await;
+        return;
+    }
+    if let Some(info) = server.peer_store().get_by_username(&reply.requester).await {
+        let ack = FNP::GuildAck {
+            rem: server.host(),
+            dest: info.peer,
+            name: name.to_string(),
+            id: reply.id,
+            accepted: reply.accepted,
+        };
+        server_sender.send(ack).await.ok();
+    }
+}
+
+/// Resposta do líder a um pedido nosso. Depósitos só saem da cesta e saques só entram
+/// nela quando o líder confirma
+async fn handle_guild_ack(
+    app_state: &AppState,
+    server: &ServerBackend,
+    name: &str,
+    id: &str,
+    accepted: bool,
+    server_sender: Sender<FNP>,
+) {
+    let Some((_, action)) = app_state.guilds.lock().requests.remove(id) else {
+        return;
+    };
+    if !accepted {
+        crate::tui::warn(&format!(
+            "A guilda {} recusou o seu pedido de {}.",
+            name, action
+        ));
+        return;
+    }
+    match action {
+        GuildAction::Entrar => crate::tui::log(&format!("Você entrou na guilda {}!", name)),
+        GuildAction::Sair => crate::tui::log(&format!("Você saiu da guilda {}.", name)),
+        GuildAction::Depositar(item) => {
+            crate::tui::log(&format!("Você depositou no cesto da guilda {}:", name));
+            execute_trade(app_state, std::slice::from_ref(&item), &[]);
+        }
+        GuildAction::Sacar(item) if !app_state.has_room(&[], std::slice::from_ref(&item)) => {
+            crate::tui::warn(&format!(
+                "A guilda {} aprovou o seu saque de {}, mas a sua cesta está cheia. Ele voltou para o cesto da guilda.",
+                name, item
+            ));
+            warn_full_basket(app_state);
+            return_withdrawal(app_state, server, name, item, server_sender).await;
+        }
+        GuildAction::Sacar(item) => {
+            crate::tui::log(&format!("A guilda {} aprovou o seu saque:", name));
+            execute_trade(app_state, &[], std::slice::from_ref(&item));
+        }
+        GuildAction::Votar { .. } | GuildAction::Quorum(_) => {}
+    }
+}
+
+/// Devolve ao cesto da guilda um saque que não coube na cesta. O depósito não é guardado
+/// nos nossos pedidos, então a confirmação do líder não tira nada da cesta
+async fn return_withdrawal(
+    app_state: &AppState,
+    server: &ServerBackend,
+    name: &str,
+    item: InventoryItem,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let action = GuildAction::Depositar(item);
+    let (leader, id) = {
+        let mut guilds = app_state.guilds.lock();
+        let Some(leader) = guilds.guilds.get(name).map(|g| g.leader.clone()) else {
+            return;
+        };
+        (leader, guilds.ids.next(host.username()))
+    };
+    // Se lideramos a guilda, o depósito é aplicado aqui mesmo
+    if leader == host.username() {
+        let guild = {
+            let mut guilds = app_state.guilds.lock();
+            let Some(guild) = guilds.guilds.get_mut(name) else {
+                return;
+            };
+            if guild.apply(host.username(), &id, &action).is_err() {
+                return;
+            }
+            guild.clone()
+        };
+        server_sender.send(guild_state(host, &guild)).await.ok();
+        return;
+    }
+    if let Some(info) = server.peer_store().get_by_username(&leader).await {
+        let request = FNP::GuildRequest {
+            rem: host,
+            dest: info.peer,
+            name: name.to_string(),
+            id,
+            action,
+        };
+        server_sender.send(request).await.ok();
+    }
+}
+
+/// Nomes dos peers conectados, incluindo o nosso
+async fn online_usernames(server: &ServerBackend) -> HashSet<String> {
+    let mut online: HashSet<String> = server
+        .peer_store()
+        .all_pears()
+        .await
+        .iter()
+        .map(|p| p.username().to_string())
+        .collect();
+    online.insert(server.host().username().to_string());
+    online
+}
+
+/// Quando o líder de uma guilda sai da rede, o primeiro membro online em ordem alfabética
+/// assume com a réplica que tem. Pedidos ao líder antigo que não foram respondidos são
+/// cancelados, menos os saques, que continuam no estado da guilda
+async fn guild_succession(
+    app_state: &AppState,
+    server: &ServerBackend,
+    departed: &Peer,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let mut online = online_usernames(server).await;
+    online.remove(departed.username());
+
+    let successions: Vec<Guild> = {
+        let mut guilds = app_state.guilds.lock();
+        let led: HashSet<String> = guilds
+            .guilds
+            .values()
+            .filter(|g| g.leader == departed.username())
+            .map(|g| g.name.clone())
+            .collect();
+        guilds.requests.retain(|_, (name, action)| {
+            let keep = !led.contains(name) || matches!(action, GuildAction::Sacar(_));
+            if !keep {
+                crate::tui::warn(&format!(
+                    "O seu pedido de {} à guilda {} foi cancelado, o líder saiu da rede.",
+                    action, name
+                ));
+            }
+            keep
+        });
+        guilds
+            .guilds
+            .values_mut()
+            .filter(|g| led.contains(&g.name))
+            .filter(|g| g.successor(&online).is_some_and(|s| s == host.username()))
+            .map(|g| {
+                g.take_leadership(host.username());
+                g.clone()
+            })
+            .collect()
+    };
+    for guild in successions {
+        crate::tui::log(&format!(
+            "Você agora lidera a guilda {} no lugar de {}.",
+            guild.name,
+            departed.username()
+        ));
+        server_sender
+            .send(guild_state(host.clone(), &guild))
+            .await
+            .ok();
+    }
+}
+
+/// Uma mensagem para cada um dos outros membros do grupo que está online
+pub async fn party_messages(
+    peer_store: &PeerStore,
+    party: &Party,
+    me: &str,
+    make: impl Fn(Peer) -> FNP,
+) -> Vec<FNP> {
+    let mut msgs = Vec::new();
+    for member in party.others(me) {
+        if let Some(info) = peer_store.get_by_username(member).await {
+            msgs.push(make(info.peer));
+        }
+    }
+    msgs
+}
+
+/// O líder anuncia a lista de membros a cada um dos outros
+async fn announce_party(server: &ServerBackend, party: &Party, server_sender: Sender<FNP>) {
+    let host = server.host();
+    let make = |dest| FNP::PartyState {
+        rem: host.clone(),
+        dest,
+        id: party.id.clone(),
+        leader: party.leader.clone(),
+        members: party.members.iter().cloned().collect(),
+        version: party.version,
+    };
+    for msg in party_messages(&server.peer_store(), party, host.username(), make).await {
+        server_sender.send(msg).await.ok();
+    }
+}
+
+/// Checa se a mensagem é de um membro do nosso grupo
+fn party_member(app_state: &AppState, id: &str, username: &str) -> bool {
+    app_state
+        .party
+        .lock()
+        .party
+        .as_ref()
+        .is_some_and(|p| p.id == id && p.members.contains(username))
+}
+
+/// Avisa o que mudou no nosso grupo entre duas versões da lista
+fn log_party_changes(old: Option<&Party>, new: Option<&Party>, me: &str) {
+    match (old, new) {
+        (None, None) => {}
+        (Some(_), None) => crate::tui::log("O grupo foi desfeito."),
+        (None, Some(new)) => {
+            let leader = if new.leader == me {
+                "Grupo formado!".to_string()
+            } else {
+                format!("Você está no grupo de {}!", new.leader)
+            };
+            crate::tui::log(&format!(
+                "{} Membros: {}. Bônus de {}% nas raridades acima de comum.",
+                leader,
+                new.members.iter().cloned().collect::<Vec<_>>().join(", "),
+                new.bonus()
+            ));
+        }
+        (Some(old), Some(new)) => {
+            for m in new.members.difference(&old.members).filter(|m| *m != me) {
+                crate::tui::log(&format!(
+                    "{} entrou no grupo, bônus agora de {}%.",
+                    m,
+                    new.bonus()
+                ));
+            }
+            for m in old.members.difference(&new.members) {
+                crate::tui::log(&format!(
+                    "{} saiu do grupo, bônus agora de {}%.",
+                    m,
+                    new.bonus()
+                ));
+            }
+            if old.leader != new.leader {
+                if new.leader == me {
+                    crate::tui::log("Você agora lidera o grupo.");
+                } else {
+                    crate::tui::log(&format!("{} agora lidera o grupo.", new.leader));
+                }
+            }
+        }
+    }
+}
+
+async fn handle_server_party_invite(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: String,
+    server_sender: Sender<FNP>,
+) {
+    // Convites de peers silenciados, ou quando já estamos num grupo, são recusados
+    let muted = is_muted(app_state, rem.username());
+    let busy = app_state.party.lock().party.is_some();
+    if muted || busy {
+        let answer = FNP::PartyAnswer {
+            rem: server.host(),
+            dest: rem,
+            id,
+            accepted: false,
+        };
+        server_sender.send(answer).await.ok();
+        return;
+    }
+    crate::tui::log(&format!(
+        "{} te convidou para um grupo de pesca! Digite '$grupo aceitar {}' ou '$grupo recusar {}'.",
+        rem.username(),
+        rem.username(),
+        rem.username()
+    ));
+    app_state
+        .party
+        .lock()
+        .invites
+        .insert(rem.username().to_string(), id);
+}
+
+/// Resposta a um convite nosso. Só o líder, ou quem ainda não tem grupo, põe alguém novo
+async fn handle_server_party_answer(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    accepted: bool,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let guest = rem.username().to_string();
+    let result = {
+        let mut book = app_state.party.lock();
+        if book.sent.get(&guest).is_none_or(|sent| sent != id) {
+            return;
+        }
+        book.sent.remove(&guest);
+        if !accepted {
+            None
+        } else {
+            let old = book.party.clone();
+            match book.party.as_mut() {
+                None => {
+                    book.party = Some(Party::new(
+                        id.to_string(),
+                        host.username().to_string(),
+                        guest.clone(),
+                    ));
+                    Some(Ok((old, book.party.clone().unwrap())))
+                }
+                Some(party)
+                    if party.leader == host.username()
+                        && party.members.len() < gameplay::PARTY_SIZE =>
+                {
+                    party.members.insert(guest.clone());
+                    party.version += 1;
+                    Some(Ok((old, party.clone())))
+                }
+                Some(_) => Some(Err(())),
+            }
+        }
+    };
+    match result {
+        None => crate::tui::warn(&format!("{} recusou o convite para o grupo.", guest)),
+        Some(Err(())) => crate::tui::warn(&format!(
+            "{} aceitou o convite, mas o grupo não aceita mais membros.",
+            guest
+        )),
+        Some(Ok((old, party))) => {
+            log_party_changes(old.as_ref(), Some(&party), host.username());
+            announce_party(server, &party, server_sender).await;
+        }
+    }
+}
+
+fn handle_server_party_state(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: &Peer,
+    party: Party,
+) {
+    let host = server.host();
+    let mut book = app_state.party.lock();
+    if let Some(old) = book.update(party, rem.username(), host.username()) {
+        if book.party.is_some() {
+            book.invites.clear();
+        }
+        log_party_changes(old.as_ref(), book.party.as_ref(), host.username());
+    }
+}
+
+/// Um membro saiu do grupo ou da rede. Todos tiram ele da lista do mesmo jeito e o
+/// líder, talvez um novo, anuncia a versão seguinte
+async fn party_member_left(
+    app_state: &AppState,
+    server: &ServerBackend,
+    username: &str,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let (old, party) = {
+        let mut book = app_state.party.lock();
+        book.invites.remove(username);
+        book.sent.remove(username);
+        let Some(party) = book.party.as_mut() else {
+            return;
+        };
+        let old = party.clone();
+        if !party.remove(username) {
+            return;
+        }
+        if party.leader == host.username() {
+            party.version += 1;
+        }
+        let party = party.clone();
+        if party.is_dissolved() {
+            book.party = None;
+        }
+        (old, party)
+    };
+    let new = (!party.is_dissolved()).then_some(&party);
+    log_party_changes(Some(&old), new, host.username());
+    if new.is_some() && party.leader == host.username() {
+        announce_party(server, &party, server_sender).await;
+    }
+}
+
+/// Pescas raras são anunciadas só para o grupo
+async fn party_catch(
+    app_state: &AppState,
+    server: &ServerBackend,
+    fish: &str,
+    server_sender: Sender<FNP>,
+) {
+    if app_state.fish_catalog.get_rarity_rank(fish) < gameplay::PARTY_RARE_RANK {
+        return;
+    }
+    let Some(party) = app_state.party.lock().party.clone() else {
+        return;
+    };
+    let host = server.host();
+    let make = |dest| FNP::PartyCatch {
+        rem: host.clone(),
+        dest,
+        id: party.id.clone(),
+        fish: fish.to_string(),
+    };
+    for msg in party_messages(&server.peer_store(), &party, host.username(), make).await {
+        server_sender.send(msg).await.ok();
+    }
+}
+
+/// Checa se as apostas de um duelo são peixes do catálogo ou moedas
+fn valid_stake(app_state: &AppState, stake: &[InventoryItem]) -> bool {
+    stake.iter().all(|i| {
+        i.quantity > 0
+            && (gameplay::is_coin(&i.fish_type) || app_state.fish_catalog.contains(&i.fish_type))
+    })
+}
+
+/// Apostas de um duelo separadas por vírgula, ex: `atum|2, moedas|10`
+pub fn stake_to_string(stake: &[InventoryItem]) -> String {
+    stake
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn handle_server_duel_challenge(
+    app_state: &AppState,
+    server: &ServerBackend,
+    duel: Duel,
+    server_sender: Sender<FNP>,
+    event_sender: Sender<Event>,
+) {
+    let opponent = duel.opponent.username().to_string();
+    // Desafios de peers silenciados são recusados se a regra estiver ativa
+    let muted = is_muted(app_state, &opponent);
+    let busy = app_state.duels.lock().with_peer(&opponent).is_some();
+    let valid = (1..=gameplay::DUEL_CASTS * 4).contains(&duel.casts)
+        && valid_stake(app_state, &duel.their_stake);
+    if muted || busy || !valid {
+        server_sender
+            .send(FNP::DuelAnswer {
+                rem: server.host(),
+                dest: duel.opponent,
+                id: duel.id,
+                accepted: false,
+                stake: Vec::new(),
+                commitment: 0,
+            })
+            .await
+            .ok();
+        return;
+    }
+
+    crate::tui::log(&format!(
+        "{} te desafiou para um duelo de pesca de {} lançamentos!",
+        opponent, duel.casts
+    ));
+    if !duel.their_stake.is_empty() {
+        crate::tui::log(&format!(
+            "Aposta de {}: {}",
+            opponent,
+            stake_to_string(&duel.their_stake)
+        ));
+    }
+    crate::tui::log(&format!(
+        "Digite '$duelo aceitar {} [peixe|n]' para aceitar, apostando algo se quiser, ou '$duelo recusar {}'.",
+        opponent, opponent
+    ));
+    crate::event::schedule(
+        event_sender,
+        gameplay::DUEL_TIMEOUT,
+        Event::DueloFim(duel.id.clone()),
+    );
+    app_state.duels.lock().duels.insert(duel.id.clone(), duel);
+}
+
+/// Resposta ao nosso desafio. Se foi aceito, já temos o compromisso do oponente e podemos
+/// revelar a nossa metade da semente
+async fn handle_server_duel_answer(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    answer: Option<(Vec<InventoryItem>, u64)>,
+    server_sender: Sender<FNP>,
+    event_sender: Sender<Event>,
+) {
+    let nonce = {
+        let mut duels = app_state.duels.lock();
+        if duels
+            .duels
+            .get(id)
+            .is_none_or(|d| !d.challenger || d.accepted || d.opponent.address() != rem.address())
+        {
+            return;
+        }
+        let Some((stake, commitment)) = answer.filter(|(stake, _)| valid_stake(app_state, stake))
+        else {
+            duels.duels.remove(id);
+            crate::tui::warn(&format!("{} recusou o duelo.", rem.username()));
+            return;
+        };
+        let Some(duel) = duels.duels.get_mut(id) else {
+            return;
+        };
+        duel.their_stake = stake;
+        duel.their_commitment = Some(commitment);
+        duel.accepted = true;
+        // O oponente tem um prazo inteiro, a partir de agora, para revelar a sua metade
+        duel.deadline = gameplay::now_secs() + gameplay::DUEL_TIMEOUT.as_secs();
+        crate::event::schedule(
+            event_sender,
+            gameplay::DUEL_TIMEOUT,
+            Event::DueloFim(id.to_string()),
+        );
+        if !duel.their_stake.is_empty() {
+            crate::tui::log(&format!(
+                "Aposta de {}: {}",
+                rem.username(),
+                stake_to_string(&duel.their_stake)
+            ));
+        }
+        duel.nonce
+    };
+    crate::tui::log(&format!(
+        "{} aceitou o duelo! Revelando as sementes...",
+        rem.username()
+    ));
+    server_sender
+        .send(FNP::DuelReveal {
+            rem: server.host(),
+            dest: rem,
+            id: id.to_string(),
+            nonce,
+        })
+        .await
+        .ok();
+}
+
+/// Semente revelada pelo oponente. Quem foi desafiado revela a sua em seguida, e os dois
+/// calculam o resultado com a semente combinada
+async fn handle_server_duel_reveal(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    nonce: u64,
+    server_sender: Sender<FNP>,
+) {
+    let duel = {
+        let mut duels = app_state.duels.lock();
+        if duels
+            .duels
+            .get(id)
+            .is_none_or(|d| !d.accepted || d.opponent.address() != rem.address())
+        {
+            return;
+        }
+        duels.duels.remove(id)
+    };
+    let Some(duel) = duel else {
+        return;
+    };
+    if !duel.verify(nonce) {
+        crate::tui::err(&format!(
+            "{} revelou uma semente que não bate com o compromisso. Duelo {} cancelado.",
+            rem.username(),
+            id
+        ));
+        return;
+    }
+    if !duel.challenger {
+        server_sender
+            .send(FNP::DuelReveal {
+                rem: server.host(),
+                dest: rem,
+                id: id.to_string(),
+                nonce: duel.nonce,
+            })
+            .await
+            .ok();
+    }
+    finish_duel(app_state, server, &duel, nonce, server_sender).await;
+}
+
+/// Mostra as pescas dos dois lados e acerta as apostas. O perdedor manda a sua aposta
+/// numa oferta de troca, que o vencedor aceita sozinho se ela bater com o que foi apostado
+async fn finish_duel(
+    app_state: &AppState,
+    server: &ServerBackend,
+    duel: &Duel,
+    their_nonce: u64,
+    server_sender: Sender<FNP>,
+) {
+    let result = duel.result(&app_state.fish_catalog, their_nonce);
+    let opponent = duel.opponent.username();
+    let casts = |fish: &[String]| {
+        fish.iter()
+            .map(|f| {
+                app_state
+                    .fish_catalog
+                    .get_style_for_fish(f)
+                    .style(f)
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    println!("-- DUELO {} --", duel.id);
+    crate::tui::log(&format!(
+        "Você: {} = {} ponto(s)",
+        casts(&result.mine),
+        result.my_score
+    ));
+    crate::tui::log(&format!(
+        "{}: {} = {} ponto(s)",
+        opponent,
+        casts(&result.theirs),
+        result.their_score
+    ));
+    match result.my_score.cmp(&result.their_score) {
+        std::cmp::Ordering::Greater => {
+            crate::tui::log("Você venceu o duelo!");
+            duel_won(app_state, duel);
+        }
+        std::cmp::Ordering::Less => {
+            crate::tui::warn(&format!("{} venceu o duelo.", opponent));
+            pay_stake(app_state, server, duel, server_sender).await;
+        }
+        std::cmp::Ordering::Equal => {
+            crate::tui::log("Empate! Ninguém perde a aposta.");
+        }
+    }
+}
+
+/// Guarda a aposta do perdedor, que só entra na cesta quando ele mandar a oferta de troca
+fn duel_won(app_state: &AppState, duel: &Duel) {
+    if duel.their_stake.is_empty() {
+        return;
+    }
+    crate::tui::log(&format!(
+        "Aguardando {} entregar a aposta: {}",
+        duel.opponent.username(),
+        stake_to_string(&duel.their_stake)
+    ));
+    app_state.duels.lock().owed.insert(
+        duel.opponent.username().to_string(),
+        duel.their_stake.clone(),
+    );
+}
+
+/// Perdemos o duelo: a aposta vai ao vencedor como uma oferta de troca sem nada pedido,
+/// reservada até ele confirmar
+async fn pay_stake(
+    app_state: &AppState,
+    server: &ServerBackend,
+    duel: &Duel,
+    server_sender: Sender<FNP>,
+) {
+    if duel.my_stake.is_empty() {
+        return;
+    }
+    let opponent = &duel.opponent;
+    let offer = Offer {
+        offered: duel.my_stake.clone(),
+        requested: Vec::new(),
+    };
+    {
+        let mut buffers = app_state.offer_buffers.lock();
+        if buffers.offers_made.contains_key(&opponent.address()) {
+            crate::tui::err(&format!(
+                "* Você tem uma oferta pendente para {}, a aposta do duelo não pôde ser entregue.",
+                opponent.username()
+            ));
+            return;
+        }
+        buffers
+            .offers_made
+            .insert(opponent.address(), offer.clone());
+    }
+    crate::tui::log(&format!(
+        "Entregando a aposta para {}: {}",
+        opponent.username(),
+        stake_to_string(&duel.my_stake)
+    ));
+    server_sender
+        .send(FNP::TradeOffer {
+            rem: server.host(),
+            dest: opponent.clone(),
+            offer,
+        })
+        .await
+        .ok();
+}
+
+/// Aceita sozinho a oferta com a aposta de um duelo que ganhamos. Com a cesta cheia, a
+/// oferta fica esperando o usuário. Retorna se a oferta foi respondida
+async fn settle_duel(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: &Peer,
+    offer: &Offer,
+    server_sender: Sender<FNP>,
+) -> bool {
+    if !app_state
+        .duels
+        .lock()
+        .settle(rem.username(), &offer.offered, &offer.requested)
+    {
+        return false;
+    }
+    crate::tui::log(&format!("{} entregou a aposta do duelo.", rem.username()));
+    if !handle_ui_tradeconfirm(
+        app_state,
+        server.host(),
+        true,
+        offer,
+        rem,
+        server_sender.clone(),
+    )
+    .await
+    {
+        crate::tui::log(&format!(
+            "Libere espaço e digite '$c [s]im {}' para receber a aposta.",
+            rem.username()
+        ));
+        return true;
+    }
+    server_sender
+        .send(FNP::TradeConfirm {
+            rem: server.host(),
+            dest: rem.clone(),
+            response: true,
+            offer: offer.clone(),
+        })
+        .await
+        .ok();
+    true
+}
+
+/// Prazo de um duelo. Sem resposta, ele é cancelado e as apostas ficam com os donos.
+/// Depois do aceite, o duelo só continua aberto se o oponente não revelou a semente, o
+/// que conta como derrota dele
+pub async fn handle_duel_end(app_state: &AppState, id: &str) {
+    let duel = {
+        let mut duels = app_state.duels.lock();
+        // Avisos de prazos antigos, de antes do aceite, são ignorados
+        if duels
+            .duels
+            .get(id)
+            .is_none_or(|d| gameplay::now_secs() < d.deadline)
+        {
+            return;
+        }
+        duels.duels.remove(id)
+    };
+    let Some(duel) = duel else {
+        return;
+    };
+    if duel.accepted {
+        crate::tui::log(&format!(
+            "{} não revelou a semente a tempo e perdeu o duelo {}.",
+            duel.opponent.username(),
+            id
+        ));
+        duel_won(app_state, &duel);
     } else {
-        let mut items: Vec<(&String, &u32)> = inventory.map().iter().collect();
+        crate::tui::warn(&format!(
+            "O duelo {} com {} expirou sem terminar.",
+            id,
+            duel.opponent.username()
+        ));
+    }
+}
 
-        // Ordenando a lista de peixes com base na raridade para exibição
-        items.sort_by(|(fish_a, _), (fish_b, _)| {
-            let rank_a = app_state.fish_catalog.get_rarity_rank(fish_a);
-            let rank_b = app_state.fish_catalog.get_rarity_rank(fish_b);
-            rank_a.cmp(&rank_b)
-        });
+async fn handle_server_multi_trade_propose(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: String,
+    legs: Vec<TradeLeg>,
+) {
+    // Quem propõe já aprova a própria multitroca
+    let trade = MultiTrade {
+        id,
+        approvals: BTreeSet::from([rem.username().to_string()]),
+        coordinator: rem,
+        legs,
+        reserved: Vec::new(),
+        peers: Vec::new(),
+        mine: false,
+    };
+    let host = server.host();
+    if !trade.participants().contains(host.username()) {
+        return;
+    }
+    {
+        // Uma proposta repetida não pode apagar os votos nem as reservas da que já temos
+        let mut multitrades = app_state.multitrades.lock();
+        if multitrades.trades.contains_key(&trade.id) {
+            return;
+        }
+        multitrades.trades.insert(trade.id.clone(), trade.clone());
+    }
+
+    println!(
+        "{} propôs a multitroca {}:",
+        trade.coordinator.username(),
+        trade.id
+    );
+    for leg in &trade.legs {
+        let items: Vec<String> = leg.items.iter().map(|i| i.to_string()).collect();
+        println!("> {} entrega {} para {}", leg.from, items.join(","), leg.to);
+    }
+    show_valuation(
+        app_state,
+        &trade.receives(host.username()),
+        &trade.gives(host.username()),
+    );
+    crate::tui::log(&format!(
+        "Digite '$multitroca aceitar {}' para aprovar, ou '$multitroca recusar {}' para recusar",
+        trade.id, trade.id
+    ));
+}
+
+async fn handle_server_multi_trade_vote(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    voter: String,
+    approve: bool,
+    server_sender: Sender<FNP>,
+) {
+    let (forward, done) = {
+        let mut multitrades = app_state.multitrades.lock();
+        let Some(trade) = multitrades.trades.get_mut(id) else {
+            return;
+        };
+        if !trade.mine {
+            // Votos repassados pelo coordenador, só para acompanharmos as aprovações
+            if rem.address() == trade.coordinator.address() && approve {
+                trade.approvals.insert(voter.clone());
+                crate::tui::log(&format!(
+                    "{} aprovou a multitroca {} ({}/{}).",
+                    voter,
+                    id,
+                    trade.approvals.len(),
+                    trade.participants().len()
+                ));
+            }
+            return;
+        }
+        // Cada participante só vota por si
+        if voter != rem.username() || !trade.participants().contains(&voter) {
+            return;
+        }
+        if approve {
+            trade.approvals.insert(voter.clone());
+            crate::tui::log(&format!(
+                "{} aprovou a multitroca {} ({}/{}).",
+                voter,
+                id,
+                trade.approvals.len(),
+                trade.participants().len()
+            ));
+        }
+        // Repassamos o voto para que todos acompanhem quem já aprovou
+        let forward: Vec<FNP> = trade
+            .peers
+            .iter()
+            .filter(|p| p.address() != rem.address())
+            .map(|p| FNP::MultiTradeVote {
+                rem: server.host(),
+                dest: p.clone(),
+                id: id.to_string(),
+                voter: voter.clone(),
+                approve,
+            })
+            .collect();
+        (forward, trade.all_approved())
+    };
+
+    if !approve {
+        let reason = format!("{} recusou", voter);
+        abort_multi_trade(app_state, server, id, &reason, server_sender).await;
+        return;
+    }
+    for msg in forward {
+        server_sender.send(msg).await.ok();
+    }
+    if done {
+        commit_multi_trade(app_state, server, id, server_sender).await;
+    }
+}
 
-        for (fish_type, quantity) in items {
-            let style = app_state.fish_catalog.get_style_for_fish(fish_type);
-            println!("> [{}] {}", quantity, style.style(fish_type));
+async fn handle_server_multi_trade_commit(
+    app_state: &AppState,
+    server: &ServerBackend,
+    rem: Peer,
+    id: &str,
+    commit: bool,
+    server_sender: Sender<FNP>,
+) {
+    let trade = {
+        let mut multitrades = app_state.multitrades.lock();
+        if !multitrades
+            .trades
+            .get(id)
+            .is_some_and(|t| !t.mine && t.coordinator.address() == rem.address())
+        {
+            return;
         }
+        // Removê-la libera o que estava reservado, que agora é entregue de vez
+        multitrades.trades.remove(id)
+    };
+    let Some(trade) = trade else {
+        return;
+    };
+    if commit {
+        apply_multi_trade(app_state, server, &trade, server_sender).await;
+    } else {
+        crate::tui::log(&format!("A multitroca {} foi abortada.", id));
+    }
+}
+
+/// Fim do prazo da nossa multitroca: aborta se ainda falta alguém aprovar
+pub async fn handle_multi_trade_end(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    server_sender: Sender<FNP>,
+) {
+    if app_state
+        .multitrades
+        .lock()
+        .trades
+        .get(id)
+        .is_some_and(|t| t.mine)
+    {
+        abort_multi_trade(app_state, server, id, "tempo esgotado", server_sender).await;
+    }
+}
+
+/// Todos aprovaram: o coordenador manda efetivar e aplica a sua parte
+async fn commit_multi_trade(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    server_sender: Sender<FNP>,
+) {
+    let Some(trade) = app_state.multitrades.lock().trades.remove(id) else {
+        return;
+    };
+    for peer in &trade.peers {
+        server_sender
+            .send(FNP::MultiTradeCommit {
+                rem: server.host(),
+                dest: peer.clone(),
+                id: id.to_string(),
+                commit: true,
+            })
+            .await
+            .ok();
+    }
+    apply_multi_trade(app_state, server, &trade, server_sender).await;
+}
+
+/// Aborta a nossa multitroca, avisando todos os participantes
+pub async fn abort_multi_trade(
+    app_state: &AppState,
+    server: &ServerBackend,
+    id: &str,
+    reason: &str,
+    server_sender: Sender<FNP>,
+) {
+    let Some(trade) = app_state.multitrades.lock().trades.remove(id) else {
+        return;
+    };
+    crate::tui::log(&format!("A multitroca {} foi abortada: {}.", id, reason));
+    for peer in trade.peers {
+        server_sender
+            .send(FNP::MultiTradeCommit {
+                rem: server.host(),
+                dest: peer,
+                id: id.to_string(),
+                commit: false,
+            })
+            .await
+            .ok();
+    }
+}
+
+/// Aplica a nossa parte de uma multitroca efetivada
+async fn apply_multi_trade(
+    app_state: &AppState,
+    server: &ServerBackend,
+    trade: &MultiTrade,
+    server_sender: Sender<FNP>,
+) {
+    let host = server.host();
+    let given = trade.gives(host.username());
+    let received = trade.receives(host.username());
+    let others: Vec<String> = trade
+        .participants()
+        .into_iter()
+        .filter(|p| p != host.username())
+        .collect();
+
+    crate::tui::log(&format!("-- MULTITROCA {} EFETIVADA --", trade.id));
+    let over = !app_state.has_room(&given, &received);
+    execute_trade(app_state, &given, &received);
+    if over {
+        warn_full_basket(app_state);
+    }
+    trade_completed(
+        app_state,
+        host,
+        &others.join("+"),
+        &given,
+        &received,
+        server_sender,
+    )
+    .await;
+}
+
+async fn handle_ui_inventory_inspection(app_state: &AppState, host: SocketAddr) {
+    let capacity = app_state.capacity();
+    let aquariums = *app_state.aquariums.lock();
+    let view = app_state
+        .inspections
+        .lock()
+        .remove(&host)
+        .unwrap_or_default();
+    let (items, total) = {
+        let inventory = app_state.basket.lock();
+        let items: Vec<InventoryItem> = inventory
+            .map()
+            .iter()
+            .map(|(fish, quantity)| InventoryItem::new(fish.clone(), *quantity))
+            .collect();
+        (items, inventory.total())
+    };
+    println!("-- INVENTÁRIO --");
+    if items.is_empty() {
+        crate::tui::log("[Nenhum peixe aqui, digite $[p]esca para pescar]");
+    } else {
+        show_inventory(app_state, &items, &view);
+    }
+    crate::tui::log(&format!("Moedas: {}", app_state.coins.lock()));
+    crate::tui::log(&format!(
+        "Cesta: {}/{} peixes ({} aquário(s))",
+        total, capacity, aquariums
+    ));
+    let baits = *app_state.baits.lock();
+    let consumables = app_state.consumables.lock();
+    if baits > 0 || consumables.rare_baits > 0 {
+        crate::tui::log(&format!(
+            "Iscas: {} | Iscas raras: {}",
+            baits, consumables.rare_baits
+        ));
+    }
+    if let Some(bonus) = consumables
+        .bonus
+        .as_ref()
+        .filter(|b| b.until > gameplay::now_secs())
+    {
+        crate::tui::log(&format!(
+            "Bônus de {}: +{} lançamento(s) por pesca, faltam {}",
+            bonus.dish,
+            bonus.rolls,
+            gameplay::format_elapsed(bonus.until - gameplay::now_secs())
+        ));
     }
 }
 
@@ -411,57 +4016,56 @@ async fn handle_ui_tradeoffer() {
     crate::tui::log("-- OFERTA FEITA --");
 }
 
-async fn handle_ui_tradeconfirm(app_state: &AppState, response: bool, offer: &Offer, dest: &Peer) {
+/// Executa a resposta do usuário a uma oferta recebida. Retorna false se a resposta não
+/// deve ser enviada ao peer
+async fn handle_ui_tradeconfirm(
+    app_state: &AppState,
+    host: Peer,
+    response: bool,
+    offer: &Offer,
+    dest: &Peer,
+    server_sender: Sender<FNP>,
+) -> bool {
     if response {
-        let mut is_valid = true;
-        // Criando um escopo para evitar bugs com o Lock
-        {
-            let mut inventory = app_state.basket.lock();
-            // Validação da oferta de troca recebida checando se o cliente tem
-            // peixes o suficiente para aceitar a troca
-            for item in &offer.requested {
-                let available = inventory.map().get(&item.fish_type).copied().unwrap_or(0);
-                if available < item.quantity {
-                    crate::tui::err(&format!(
-                        "Troca inválida! Você não tem {} {}(s) para trocar.",
-                        item.quantity, item.fish_type
-                    ));
-                    is_valid = false;
-                    break;
-                }
-            }
+        // Validação da oferta de troca recebida checando se o cliente tem
+        // peixes e moedas o suficiente para aceitar a troca
+        if let Some((item, _)) = app_state.find_missing(&offer.requested, None) {
+            crate::tui::err(&format!(
+                "Troca inválida! Você não tem {} {}(s) para trocar.",
+                item.quantity, item.fish_type
+            ));
             // Se a troca não for válida, mantém a proposta no buffer
-            // Caso contrário, execute a resposta decidida pelo usuário
-            if !is_valid {
-                return;
-            } else {
-                crate::tui::log("-- OFERTA ACEITA --");
-                for item in &offer.offered {
-                    let style = app_state.fish_catalog.get_style_for_fish(&item.fish_type);
-                    println!("+ {} {}(s)", item.quantity, style.style(&item.fish_type));
-                    *inventory
-                        .map_mut()
-                        .entry(item.fish_type.clone())
-                        .or_insert(0) += item.quantity;
-                }
-                for item in &offer.requested {
-                    let style = app_state.fish_catalog.get_style_for_fish(&item.fish_type);
-                    println!("- {} {}(s)", item.quantity, style.style(&item.fish_type));
-                    if let Some(count) = inventory.map_mut().get_mut(&item.fish_type) {
-                        *count = count.saturating_sub(item.quantity);
-                        if *count == 0 {
-                            inventory.map_mut().remove(&item.fish_type);
-                        }
-                    }
-                }
-            }
+            return false;
+        }
+        if !app_state.has_room(&offer.requested, &offer.offered) {
+            warn_full_basket(app_state);
+            return false;
         }
+        crate::tui::log("-- OFERTA ACEITA --");
+        execute_trade(app_state, &offer.requested, &offer.offered);
+        trade_completed(
+            app_state,
+            host,
+            dest.username(),
+            &offer.requested,
+            &offer.offered,
+            server_sender,
+        )
+        .await;
     } else {
         crate::tui::log("-- OFERTA RECUSADA --");
+        record_history(
+            app_state,
+            TradeOutcome::Recusada,
+            dest.username(),
+            &offer.requested,
+            &offer.offered,
+        );
     }
     app_state
         .offer_buffers
         .lock()
         .offers_received
         .remove(&dest.address());
+    true
 }
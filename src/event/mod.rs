@@ -9,9 +9,11 @@
 pub mod handlers;
 
 use crate::server;
+use async_channel::Sender;
 use std::net::SocketAddr;
+use std::time::Duration;
 
-/// Os 4 tipos de eventos com os quais o dispatcher lida
+/// Os tipos de eventos com os quais o dispatcher lida
 pub enum Event {
     /// Foi percebido que um peer saiu da rede
     PeerDisconnected(SocketAddr),
@@ -21,4 +23,27 @@ pub enum Event {
     UIMessage(server::FNP),
     /// O peer está tentando pescar
     Pesca,
+    /// O torneio com o id dado começou
+    TorneioInicio(String),
+    /// O torneio com o id dado terminou
+    TorneioFim(String),
+    /// O prazo do nosso leilão com o id dado acabou
+    LeilaoFim(String),
+    /// O prazo para respostas da busca com o id dado acabou
+    ProcuraFim(String),
+    /// A janela do cardume com o id dado fechou
+    CardumeFim(String),
+    /// O prazo do duelo com o id dado acabou
+    DueloFim(String),
+    /// O prazo para aprovarem a nossa multitroca com o id dado acabou
+    MultiTrocaFim(String),
+}
+
+/// Agenda o envio de um evento para o dispatcher depois de um intervalo de tempo
+pub fn schedule(sender: Sender<Event>, delay: Duration, event: Event) {
+    smol::spawn(async move {
+        smol::Timer::after(delay).await;
+        sender.send(event).await.ok();
+    })
+    .detach();
 }
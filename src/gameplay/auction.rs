@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use super::ids::IdGenerator;
+use crate::server::{InventoryItem, Peer};
+
+/// Leilão de um peixe. O vendedor coordena o leilão: recebe os lances, anuncia o maior
+/// lance para todos e, no fim do prazo, negocia com o vencedor
+#[derive(Debug, Clone)]
+pub struct Auction {
+    pub id: String,
+    pub seller: Peer,
+    pub item: InventoryItem,
+    pub min_bid: u32,
+    /// Prazo do leilão, em segundos desde a época UNIX
+    pub end: u64,
+    /// Maior lance conhecido: nome do pescador e valor
+    pub highest: Option<(String, u32)>,
+    /// Todos os lances recebidos, só é preenchido no vendedor
+    pub bids: Vec<(Peer, u32)>,
+    /// Se o leilão é nosso, o peixe fica reservado até o fim
+    pub mine: bool,
+    /// Moedas reservadas enquanto somos o maior lance
+    pub my_bid: u32,
+}
+
+/// Todos os leilões abertos conhecidos pelo peer
+#[derive(Debug, Default)]
+pub struct AuctionHouse {
+    pub auctions: HashMap<String, Auction>,
+    pub ids: IdGenerator,
+}
+
+impl Auction {
+    /// Menor lance aceito neste momento
+    pub fn next_min_bid(&self) -> u32 {
+        self.highest
+            .as_ref()
+            .map(|(_, amount)| amount + 1)
+            .unwrap_or(self.min_bid)
+    }
+
+    /// Lances do maior para o menor
+    pub fn ranked_bids(&self) -> Vec<(Peer, u32)> {
+        let mut bids = self.bids.clone();
+        bids.sort_by(|(_, a), (_, b)| b.cmp(a));
+        bids
+    }
+}
+
+impl AuctionHouse {
+    /// Quantidade de um item reservada em leilões: peixes que estamos leiloando e moedas
+    /// dos lances que estamos vencendo
+    pub fn reserved(&self, item: &str) -> u32 {
+        self.auctions
+            .values()
+            .map(|a| {
+                if super::is_coin(item) {
+                    a.my_bid
+                } else if a.mine && a.item.fish_type == item {
+                    a.item.quantity
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use super::ids::IdGenerator;
+use crate::server::{Peer, protocol::Offer};
+
+/// Oferta pública, anunciada para toda a rede. Quem a publicou decide quem fica com ela:
+/// o primeiro pedido que chegar leva
+#[derive(Debug, Clone)]
+pub struct PublicOffer {
+    pub id: String,
+    pub owner: Peer,
+    pub offer: Offer,
+    /// Se a oferta é nossa, os peixes ofertados ficam reservados
+    pub mine: bool,
+    /// Se já pedimos para pegar essa oferta
+    pub claimed: bool,
+}
+
+/// Mural com todas as ofertas públicas conhecidas pelo peer
+#[derive(Debug, Default)]
+pub struct OfferBoard {
+    pub offers: HashMap<String, PublicOffer>,
+    pub ids: IdGenerator,
+}
+
+impl OfferBoard {
+    /// Quantidade de um item reservada nas nossas ofertas públicas
+    pub fn reserved(&self, item: &str) -> u32 {
+        self.offers
+            .values()
+            .filter(|o| o.mine)
+            .flat_map(|o| &o.offer.offered)
+            .filter(|i| i.fish_type == item)
+            .map(|i| i.quantity)
+            .sum()
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Horário atual em segundos desde a época UNIX. Como todos os peers usam o mesmo
+/// relógio de referência, podemos combinar horários absolutos pela rede
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Horário atual em milissegundos desde a época UNIX, para ordenar eventos próximos
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Formata um intervalo em segundos de forma curta, ex: `45s`, `12m`, `3h`, `2d`
+pub fn format_elapsed(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Dia atual (UTC) contado desde a época UNIX. Serve de semente para conteúdos diários
+pub fn today() -> u64 {
+    now_secs() / 86400
+}
+
+/// Converte uma duração no formato `30s`, `10m` ou `1h` para um `Duration`.
+/// Números sem unidade são interpretados como minutos. Durações que não cabem em
+/// segundos num `u64` são inválidas
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim().to_lowercase();
+    let (num, mult) = if let Some(n) = s.strip_suffix('s') {
+        (n, 1)
+    } else if let Some(n) = s.strip_suffix('m') {
+        (n, 60)
+    } else if let Some(n) = s.strip_suffix('h') {
+        (n, 3600)
+    } else {
+        (s.as_str(), 60)
+    };
+    let n: u64 = num.parse().ok()?;
+    if n == 0 {
+        return None;
+    }
+    Some(Duration::from_secs(n.checked_mul(mult)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("99999999999999999h"), None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rand::{RngCore, SeedableRng, rngs::StdRng};
+
+use super::fisher::{FishCatalog, FishingOdds, RARITY_POINTS, fishing_with_rng};
+use super::ids::IdGenerator;
+use crate::server::{InventoryItem, Peer};
+
+/// Lançamentos de cada jogador num duelo
+pub const DUEL_CASTS: u32 = 5;
+/// Prazo para o duelo ser respondido e as sementes reveladas
+pub const DUEL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Duelo de pesca entre dois peers. A semente das pescas é combinada por compromisso e
+/// revelação: cada lado manda o compromisso da sua metade antes de ver a do outro, então
+/// ninguém escolhe a semente sozinho e os dois calculam o mesmo resultado. Quem foi
+/// desafiado vê a semente do desafiante antes de revelar a sua, então, depois do aceite,
+/// não revelar até o prazo conta como derrota. As apostas não são creditadas pelo
+/// resultado: o perdedor manda uma oferta de troca com a sua aposta ao vencedor
+#[derive(Debug, Clone)]
+pub struct Duel {
+    pub id: String,
+    pub opponent: Peer,
+    /// Se fomos nós que desafiamos
+    pub challenger: bool,
+    pub casts: u32,
+    /// Apostas de cada lado, vão para o vencedor
+    pub my_stake: Vec<InventoryItem>,
+    pub their_stake: Vec<InventoryItem>,
+    /// Nossa metade da semente, só é revelada depois de recebermos o compromisso do outro
+    pub nonce: u64,
+    pub their_commitment: Option<u64>,
+    /// Se o desafio foi aceito
+    pub accepted: bool,
+    /// Prazo para a resposta ou, depois do aceite, para a revelação, em segundos desde a
+    /// época UNIX
+    pub deadline: u64,
+}
+
+/// Resultado de um duelo, visto do nosso lado
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuelResult {
+    pub mine: Vec<String>,
+    pub theirs: Vec<String>,
+    pub my_score: u32,
+    pub their_score: u32,
+}
+
+/// Duelos em andamento, no máximo um com cada peer
+#[derive(Debug, Default)]
+pub struct DuelBook {
+    pub duels: HashMap<String, Duel>,
+    /// Apostas que ganhamos, esperando a oferta de troca do perdedor, pelo nome dele
+    pub owed: HashMap<String, Vec<InventoryItem>>,
+    pub ids: IdGenerator,
+}
+
+/// Compromisso de uma metade da semente. A saída do `StdRng` não revela a semente, e
+/// todos os peers rodam o mesmo programa, então calculam o mesmo valor
+pub fn commitment(nonce: u64) -> u64 {
+    StdRng::seed_from_u64(nonce).next_u64()
+}
+
+/// Pescas dos dois jogadores com a semente combinada: primeiro as do desafiante
+pub fn duel_casts(catalog: &FishCatalog, seed: u64, casts: u32) -> (Vec<String>, Vec<String>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let odds = FishingOdds::default();
+    let mut cast = |_| fishing_with_rng(catalog, &odds, &mut rng);
+    let first = (0..casts).map(&mut cast).collect();
+    let second = (0..casts).map(&mut cast).collect();
+    (first, second)
+}
+
+/// Soma dos pontos de raridade dos peixes pescados
+pub fn duel_score(catalog: &FishCatalog, fish: &[String]) -> u32 {
+    fish.iter()
+        .map(|f| RARITY_POINTS[catalog.get_rarity_rank(f) as usize])
+        .sum()
+}
+
+impl Duel {
+    /// Checa se a metade revelada pelo oponente bate com o compromisso que ele mandou
+    pub fn verify(&self, their_nonce: u64) -> bool {
+        self.their_commitment == Some(commitment(their_nonce))
+    }
+
+    pub fn result(&self, catalog: &FishCatalog, their_nonce: u64) -> DuelResult {
+        let (challenger, challenged) = duel_casts(catalog, self.nonce ^ their_nonce, self.casts);
+        let (mine, theirs) = if self.challenger {
+            (challenger, challenged)
+        } else {
+            (challenged, challenger)
+        };
+        DuelResult {
+            my_score: duel_score(catalog, &mine),
+            their_score: duel_score(catalog, &theirs),
+            mine,
+            theirs,
+        }
+    }
+}
+
+impl DuelBook {
+    /// Checa se a oferta de um peer é a aposta que ele perdeu para nós, e a dá como paga
+    pub fn settle(
+        &mut self,
+        username: &str,
+        offered: &[InventoryItem],
+        requested: &[InventoryItem],
+    ) -> bool {
+        let matches = requested.is_empty()
+            && self
+                .owed
+                .get(username)
+                .is_some_and(|stake| stake.as_slice() == offered);
+        if matches {
+            self.owed.remove(username);
+        }
+        matches
+    }
+
+    /// Duelo em andamento com um peer, se houver
+    pub fn with_peer(&self, username: &str) -> Option<&Duel> {
+        self.duels
+            .values()
+            .find(|d| d.opponent.username() == username)
+    }
+
+    /// Quantidade de um item apostada em duelos em andamento
+    pub fn reserved(&self, item: &str) -> u32 {
+        self.duels
+            .values()
+            .flat_map(|d| &d.my_stake)
+            .filter(|i| i.fish_type == item)
+            .map(|i| i.quantity)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duel(challenger: bool, nonce: u64, their_nonce: u64) -> Duel {
+        Duel {
+            id: "alice-1".to_string(),
+            opponent: Peer::new("bob".to_string(), "127.0.0.1:6001".parse().unwrap()),
+            challenger,
+            casts: DUEL_CASTS,
+            my_stake: Vec::new(),
+            their_stake: Vec::new(),
+            nonce,
+            their_commitment: Some(commitment(their_nonce)),
+            accepted: true,
+            deadline: 0,
+        }
+    }
+
+    #[test]
+    fn both_sides_agree_on_result() {
+        let catalog = FishCatalog::new();
+        let alice = duel(true, 11, 22);
+        let bob = duel(false, 22, 11);
+        assert!(alice.verify(22));
+        assert!(!alice.verify(23));
+        let a = alice.result(&catalog, 22);
+        let b = bob.result(&catalog, 11);
+        assert_eq!(a.mine, b.theirs);
+        assert_eq!(a.theirs, b.mine);
+        assert_eq!(a.my_score, duel_score(&catalog, &a.mine));
+        assert_eq!(a.mine.len(), DUEL_CASTS as usize);
+    }
+
+    #[test]
+    fn only_the_lost_stake_settles() {
+        let mut book = DuelBook::default();
+        let stake = vec![InventoryItem::new("atum".to_string(), 2)];
+        book.owed.insert("bob".to_string(), stake.clone());
+        let more = vec![InventoryItem::new("atum".to_string(), 3)];
+        assert!(!book.settle("bob", &more, &[]));
+        assert!(!book.settle("bob", &stake, &stake));
+        assert!(!book.settle("carol", &stake, &[]));
+        assert!(book.settle("bob", &stake, &[]));
+        assert!(!book.settle("bob", &stake, &[]));
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use super::fisher::FishingOdds;
+
+/// Duração de cada período de clima
+pub const WEATHER_SLOT: Duration = Duration::from_secs(15 * 60);
+
+/// Duração de um dia no jogo, os últimos `NIGHT_LENGTH` são de noite
+pub const GAME_DAY: Duration = Duration::from_secs(60 * 60);
+
+pub const NIGHT_LENGTH: Duration = Duration::from_secs(20 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    Sol,
+    Chuva,
+    Tempestade,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Dia,
+    Noite,
+}
+
+/// Condição que destrava uma espécie exclusiva
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Weather(Weather),
+    Time(TimeOfDay),
+}
+
+/// Espécies do catálogo que só podem ser pescadas numa condição
+pub const EXCLUSIVE_SPECIES: [(&str, Condition); 3] = [
+    ("peixe-vagalume", Condition::Time(TimeOfDay::Noite)),
+    ("peixe-arco-íris", Condition::Weather(Weather::Chuva)),
+    ("peixe-relâmpago", Condition::Weather(Weather::Tempestade)),
+];
+
+/// Cronograma de clima da rede. O clima de cada período é sorteado a partir da semente,
+/// então todos os peers com a mesma semente pescam nas mesmas condições. A rede adota a
+/// semente mais antiga (`since`), desempatando pela menor
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub seed: u64,
+    pub since: u64,
+    /// Últimas condições vistas, para avisar quando o tempo muda
+    last_seen: Option<(Weather, TimeOfDay)>,
+}
+
+impl Environment {
+    pub fn new(seed: u64, since: u64) -> Self {
+        Self {
+            seed,
+            since,
+            last_seen: None,
+        }
+    }
+
+    /// Clima no instante dado (segundos desde a época)
+    pub fn weather_at(&self, now: u64) -> Weather {
+        let slot = now / WEATHER_SLOT.as_secs();
+        match splitmix64(self.seed ^ slot) % 100 {
+            0..=59 => Weather::Sol,
+            60..=89 => Weather::Chuva,
+            _ => Weather::Tempestade,
+        }
+    }
+
+    /// Período do dia no instante dado, igual para todos pois só depende do relógio
+    pub fn time_of_day(now: u64) -> TimeOfDay {
+        let day = GAME_DAY.as_secs();
+        if now % day >= day - NIGHT_LENGTH.as_secs() {
+            TimeOfDay::Noite
+        } else {
+            TimeOfDay::Dia
+        }
+    }
+
+    /// Adota o cronograma de outro peer se ele for mais antigo que o nosso
+    pub fn adopt(&mut self, seed: u64, since: u64) -> bool {
+        if (since, seed) < (self.since, self.seed) {
+            self.seed = seed;
+            self.since = since;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Retorna as condições atuais se elas mudaram desde a última vez
+    pub fn observe(&mut self, now: u64) -> Option<(Weather, TimeOfDay)> {
+        let current = (self.weather_at(now), Self::time_of_day(now));
+        let changed = self.last_seen.is_some_and(|last| last != current);
+        self.last_seen = Some(current);
+        changed.then_some(current)
+    }
+
+    /// Chances de pesca sob as condições do instante dado
+    pub fn odds(&self, now: u64) -> FishingOdds {
+        let weather = self.weather_at(now);
+        let time = Self::time_of_day(now);
+        let mut odds = FishingOdds::default();
+        for (rank, tier) in odds.tiers.iter_mut().enumerate() {
+            *tier =
+                *tier * weather.tier_modifiers()[rank] / 100 * time.tier_modifiers()[rank] / 100;
+        }
+        for (fish, condition) in EXCLUSIVE_SPECIES {
+            if condition == Condition::Weather(weather) || condition == Condition::Time(time) {
+                odds.species.remove(fish);
+            }
+        }
+        odds
+    }
+}
+
+impl Weather {
+    /// Multiplicador de cada raridade em porcentagem, indexado pelo rank
+    pub fn tier_modifiers(&self) -> [u32; 7] {
+        match self {
+            Weather::Sol => [100; 7],
+            Weather::Chuva => [100, 150, 150, 100, 100, 100, 100],
+            Weather::Tempestade => [50, 100, 100, 100, 200, 200, 200],
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Weather::Sol => "chances normais",
+            Weather::Chuva => "mais peixes raros e épicos",
+            Weather::Tempestade => "menos comuns, o dobro de lendários, míticos e abissais",
+        }
+    }
+}
+
+impl TimeOfDay {
+    /// Multiplicador de cada raridade em porcentagem, indexado pelo rank
+    pub fn tier_modifiers(&self) -> [u32; 7] {
+        match self {
+            TimeOfDay::Dia => [100; 7],
+            TimeOfDay::Noite => [100, 100, 100, 150, 100, 100, 300],
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TimeOfDay::Dia => "chances normais",
+            TimeOfDay::Noite => "mais shinies e o triplo de abissais",
+        }
+    }
+}
+
+impl Display for Weather {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Weather::Sol => write!(f, "sol"),
+            Weather::Chuva => write!(f, "chuva"),
+            Weather::Tempestade => write!(f, "tempestade"),
+        }
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeOfDay::Dia => write!(f, "dia"),
+            TimeOfDay::Noite => write!(f, "noite"),
+        }
+    }
+}
+
+/// Embaralha um número de forma determinística (SplitMix64), igual em todos os peers
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_schedule() {
+        let a = Environment::new(42, 1000);
+        let mut b = Environment::new(7, 2000);
+        assert!(b.adopt(a.seed, a.since));
+        assert!(!b.adopt(99, 3000));
+        for slot in 0..50 {
+            let now = slot * WEATHER_SLOT.as_secs();
+            assert_eq!(a.weather_at(now), b.weather_at(now));
+        }
+    }
+
+    #[test]
+    fn night_unlocks_species() {
+        let env = Environment::new(42, 0);
+        let day = GAME_DAY.as_secs();
+        assert_eq!(Environment::time_of_day(0), TimeOfDay::Dia);
+        let night = day - 1;
+        assert_eq!(Environment::time_of_day(night), TimeOfDay::Noite);
+        assert_eq!(env.odds(0).species.get("peixe-vagalume"), Some(&0));
+        assert_eq!(env.odds(night).species.get("peixe-vagalume"), None);
+        assert_eq!(env.odds(night).tiers[6] % 3, 0);
+    }
+}
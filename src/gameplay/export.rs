@@ -0,0 +1,168 @@
+use regex::Regex;
+
+use super::fisher::{FishCatalog, RARITY_NAMES};
+use super::history::json_escape;
+use super::market::{COIN_ITEM, is_coin};
+use crate::server::InventoryItem;
+
+/// Cabeçalho do CSV da cesta
+const CSV_HEADER: &str = "peixe,raridade,quantidade";
+
+/// Arquivo onde a cesta é salva ao sair, lido pelo `fishnet export`. Tem um nome
+/// diferente do padrão das exportações para que elas não sobrescrevam a cesta salva
+pub fn basket_path(username: &str) -> String {
+    format!("cesta-salva-{}.csv", username)
+}
+
+/// Exporta a cesta no formato pedido (`csv` ou `json`), ordenada por raridade e nome.
+/// Retorna `None` se o formato não existe
+pub fn export_basket(
+    catalog: &FishCatalog,
+    format: &str,
+    mut items: Vec<InventoryItem>,
+    coins: u32,
+) -> Option<String> {
+    items.sort_by(|a, b| {
+        catalog
+            .get_rarity_rank(&a.fish_type)
+            .cmp(&catalog.get_rarity_rank(&b.fish_type))
+            .then_with(|| a.fish_type.cmp(&b.fish_type))
+    });
+    match format {
+        "csv" => Some(basket_to_csv(catalog, &items, coins)),
+        "json" => Some(basket_to_json(catalog, &items, coins)),
+        _ => None,
+    }
+}
+
+/// Exporta a cesta para CSV, com as moedas numa linha própria sem raridade
+pub fn basket_to_csv(catalog: &FishCatalog, items: &[InventoryItem], coins: u32) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    for item in items {
+        let rank = catalog.get_rarity_rank(&item.fish_type) as usize;
+        csv.push_str(&format!(
+            "\n{},{},{}",
+            item.fish_type, RARITY_NAMES[rank], item.quantity
+        ));
+    }
+    csv.push_str(&format!("\n{},,{}\n", COIN_ITEM, coins));
+    csv
+}
+
+/// Exporta a cesta para um objeto JSON com as moedas e a lista de peixes
+pub fn basket_to_json(catalog: &FishCatalog, items: &[InventoryItem], coins: u32) -> String {
+    let fish: Vec<String> = items
+        .iter()
+        .map(|i| {
+            let rank = catalog.get_rarity_rank(&i.fish_type) as usize;
+            format!(
+                "    {{\"peixe\": \"{}\", \"raridade\": \"{}\", \"quantidade\": {}}}",
+                json_escape(&i.fish_type),
+                RARITY_NAMES[rank],
+                i.quantity
+            )
+        })
+        .collect();
+    format!(
+        "{{\n  \"{}\": {},\n  \"peixes\": [\n{}\n  ]\n}}\n",
+        COIN_ITEM,
+        coins,
+        fish.join(",\n")
+    )
+}
+
+/// Lê uma cesta exportada em CSV. As moedas voltam como o item `moedas`
+pub fn basket_from_csv(content: &str) -> Result<Vec<InventoryItem>, String> {
+    content
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.trim().split(',').collect();
+            let [fish, _, quantity] = fields[..] else {
+                return Err(format!("Linha inválida: {}", line));
+            };
+            let quantity = quantity
+                .parse()
+                .map_err(|_| format!("Quantidade inválida: {}", line))?;
+            Ok(InventoryItem::new(fish.to_string(), quantity))
+        })
+        .collect()
+}
+
+/// Lê uma cesta exportada em JSON. Só os campos `moedas`, `peixe` e `quantidade` importam
+pub fn basket_from_json(content: &str) -> Result<Vec<InventoryItem>, String> {
+    let coins_re = Regex::new(&format!(r#""{}"\s*:\s*(\d+)"#, COIN_ITEM)).unwrap();
+    let fish_re = Regex::new(
+        r#"\{[^{}]*"peixe"\s*:\s*"((?:[^"\\]|\\.)*)"[^{}]*"quantidade"\s*:\s*(\d+)[^{}]*\}"#,
+    )
+    .unwrap();
+    let mut items: Vec<InventoryItem> = fish_re
+        .captures_iter(content)
+        .map(|caps| {
+            let fish = caps[1].replace("\\\"", "\"").replace("\\\\", "\\");
+            let quantity = caps[2].parse().map_err(|_| "Quantidade inválida")?;
+            Ok(InventoryItem::new(fish, quantity))
+        })
+        .collect::<Result<_, String>>()?;
+    if let Some(caps) = coins_re.captures(content) {
+        let coins = caps[1].parse().map_err(|_| "Moedas inválidas")?;
+        items.push(InventoryItem::new(COIN_ITEM.to_string(), coins));
+    }
+    if items.is_empty() && !content.contains("\"peixes\"") {
+        return Err("Arquivo não é uma cesta exportada".to_string());
+    }
+    Ok(items)
+}
+
+/// Separa os peixes das moedas de uma cesta importada. Falha se as moedas somam mais
+/// do que cabe num `u32`
+pub fn split_coins(items: Vec<InventoryItem>) -> Result<(Vec<InventoryItem>, u32), String> {
+    let (coins, fish): (Vec<InventoryItem>, Vec<InventoryItem>) =
+        items.into_iter().partition(|i| is_coin(&i.fish_type));
+    let coins = coins
+        .iter()
+        .try_fold(0u32, |sum, i| sum.checked_add(i.quantity))
+        .ok_or("Moedas demais")?;
+    Ok((fish, coins))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn basket() -> Vec<InventoryItem> {
+        vec![
+            InventoryItem::new("atum".to_string(), 2),
+            InventoryItem::new("salmão".to_string(), 1),
+        ]
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let catalog = FishCatalog::new();
+        let csv = basket_to_csv(&catalog, &basket(), 30);
+        assert!(csv.starts_with(CSV_HEADER));
+        let (fish, coins) = split_coins(basket_from_csv(&csv).unwrap()).unwrap();
+        assert_eq!(fish, basket());
+        assert_eq!(coins, 30);
+        assert!(basket_from_csv("peixe,raridade,quantidade\natum,comum").is_err());
+        let coins = format!("peixe,raridade,quantidade\nmoedas,,{}\nmoedas,,1", u32::MAX);
+        assert!(basket_from_csv(&coins).and_then(split_coins).is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let catalog = FishCatalog::new();
+        let json = basket_to_json(&catalog, &basket(), 7);
+        let (fish, coins) = split_coins(basket_from_json(&json).unwrap()).unwrap();
+        assert_eq!(fish, basket());
+        assert_eq!(coins, 7);
+        let empty = basket_to_json(&catalog, &[], 0);
+        assert_eq!(
+            basket_from_json(&empty).and_then(split_coins),
+            Ok((Vec::new(), 0))
+        );
+        assert!(basket_from_json("[1, 2]").is_err());
+    }
+}
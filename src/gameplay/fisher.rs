@@ -1,6 +1,26 @@
+use std::collections::BTreeMap;
+
 use owo_colors::Style;
+use rand::Rng;
 use rand::seq::IndexedRandom;
 
+use super::environment::EXCLUSIVE_SPECIES;
+
+/// Nomes das raridades, indexados pelo rank (0 = comum, 6 = abissal)
+pub const RARITY_NAMES: [&str; 7] = [
+    "comum",
+    "raro",
+    "épico",
+    "shiny",
+    "lendário",
+    "mítico",
+    "abissal",
+];
+
+/// Pontos que cada raridade vale, indexados pelo rank. Usado para pontuar torneios
+/// e comparar o valor de peixes de raridades diferentes
+pub const RARITY_POINTS: [u32; 7] = [1, 2, 4, 8, 20, 50, 100];
+
 /// Catálogo de todos os peixes possíveis classificados por raridade
 pub struct FishCatalog {
     abyssals: Vec<String>,
@@ -60,6 +80,8 @@ impl FishCatalog {
                 String::from("baiacu"),
                 String::from("peixe-lua"),
                 String::from("água-viva"),
+                // Só aparece à noite
+                String::from("peixe-vagalume"),
             ],
             // Peixes shiny: 6% de ocorrência
             shiny: vec![
@@ -71,6 +93,8 @@ impl FishCatalog {
                 String::from("dragão-marinho-comum"),
                 String::from("peixe-leão"),
                 String::from("cavalo-marinho"),
+                // Só aparece com chuva
+                String::from("peixe-arco-íris"),
             ],
             // Peixes míticos: 2.5% de ocorrência
             legendaries: vec![
@@ -81,6 +105,8 @@ impl FishCatalog {
                 String::from("enguia-pelicano"),
                 String::from("quimera"),
                 String::from("dragão-marinho-folhado"),
+                // Só aparece com tempestade
+                String::from("peixe-relâmpago"),
             ],
             // Peixes míticos: 1% de ocorrência
             mythicals: vec![
@@ -138,6 +164,41 @@ impl FishCatalog {
             0
         }
     }
+    /// Retorna todos os peixes de um rank de raridade
+    pub fn get_fish_by_rank(&self, rank: u8) -> &[String] {
+        match rank {
+            6 => &self.abyssals,
+            5 => &self.mythicals,
+            4 => &self.legendaries,
+            3 => &self.shiny,
+            2 => &self.epics,
+            1 => &self.rares,
+            _ => &self.commons,
+        }
+    }
+    /// Checa se o peixe existe no catálogo
+    pub fn contains(&self, fish_name: &str) -> bool {
+        (0..RARITY_NAMES.len() as u8)
+            .any(|rank| self.get_fish_by_rank(rank).iter().any(|f| f == fish_name))
+    }
+    /// Retorna quantos pontos um peixe vale de acordo com sua raridade
+    pub fn get_rarity_points(&self, fish_name: &str) -> u32 {
+        RARITY_POINTS[self.get_rarity_rank(fish_name) as usize]
+    }
+}
+
+/// Converte o nome de uma raridade para o seu rank, aceitando nomes com ou sem acento
+pub fn rarity_rank_from_name(name: &str) -> Option<u8> {
+    match name.to_lowercase().as_str() {
+        "comum" | "comuns" => Some(0),
+        "raro" | "raros" => Some(1),
+        "épico" | "epico" | "épicos" | "epicos" => Some(2),
+        "shiny" | "shinies" => Some(3),
+        "lendário" | "lendario" | "lendários" | "lendarios" => Some(4),
+        "mítico" | "mitico" | "míticos" | "miticos" => Some(5),
+        "abissal" | "abissais" => Some(6),
+        _ => None,
+    }
 }
 
 impl Default for FishCatalog {
@@ -146,21 +207,100 @@ impl Default for FishCatalog {
     }
 }
 
+/// Peso de cada raridade na pesca, indexado pelo rank. Em 200 lançamentos:
+/// 50% comum, 25% raro, 15% épico, 6% shiny, 2.5% lendário, 1% mítico, 0.5% abissal
+pub const TIER_WEIGHTS: [u32; 7] = [100, 50, 30, 12, 5, 2, 1];
+
+/// Chances de uma pesca: o peso de cada raridade e, dentro dela, o peso de cada espécie
+/// em porcentagem. Espécies fora de `species` pesam 100, e as com peso 0 não são pescadas
+#[derive(Debug, Clone, PartialEq)]
+pub struct FishingOdds {
+    pub tiers: [u32; 7],
+    pub species: BTreeMap<String, u32>,
+}
+
+impl FishingOdds {
+    /// Multiplica o peso de uma espécie por `percent`/100
+    pub fn scale_species(&mut self, fish: &str, percent: u32) {
+        let weight = self.species.entry(fish.to_string()).or_insert(100);
+        *weight = *weight * percent / 100;
+    }
+}
+
+impl Default for FishingOdds {
+    /// Chances normais, com as espécies exclusivas de um clima trancadas
+    fn default() -> Self {
+        Self {
+            tiers: TIER_WEIGHTS,
+            species: EXCLUSIVE_SPECIES
+                .iter()
+                .map(|(fish, _)| (fish.to_string(), 0))
+                .collect(),
+        }
+    }
+}
+
 /// Função de pesca, retorna um peixe aleatório do catálogo com distribuição proporcional a raridade
 pub fn fishing(fish_catalog: &FishCatalog) -> String {
+    fishing_with_odds(fish_catalog, &FishingOdds::default())
+}
+
+/// Pesca sorteando a raridade e depois a espécie com os pesos dados
+pub fn fishing_with_odds(fish_catalog: &FishCatalog, odds: &FishingOdds) -> String {
+    fishing_with_rng(fish_catalog, odds, &mut rand::rng())
+}
+
+/// Pesca com um gerador dado, útil para pescas que precisam ser reproduzidas por outro peer
+pub fn fishing_with_rng(
+    fish_catalog: &FishCatalog,
+    odds: &FishingOdds,
+    rng: &mut impl Rng,
+) -> String {
+    let weight = |f: &String| odds.species.get(f).copied().unwrap_or(100);
+    // Raridades sem nenhuma espécie disponível não são sorteadas
+    let ranks: Vec<u8> = (0..odds.tiers.len() as u8)
+        .filter(|rank| {
+            fish_catalog
+                .get_fish_by_rank(*rank)
+                .iter()
+                .any(|f| weight(f) > 0)
+        })
+        .collect();
+    let rank = *ranks
+        .choose_weighted(rng, |rank| odds.tiers[*rank as usize])
+        .unwrap_or(&0);
+
+    let fish_list = fish_catalog.get_fish_by_rank(rank);
+    fish_list
+        .choose_weighted(rng, weight)
+        .unwrap_or(&fish_list[0])
+        .clone()
+}
+
+/// Pesca com isca: joga duas vezes e fica com o peixe mais raro
+pub fn fishing_with_bait(fish_catalog: &FishCatalog) -> String {
+    fishing_best_of(fish_catalog, 2, &FishingOdds::default())
+}
+
+/// Joga `casts` vezes e fica com o peixe mais raro, com as chances dadas
+pub fn fishing_best_of(fish_catalog: &FishCatalog, casts: u32, odds: &FishingOdds) -> String {
+    let mut best = fishing_with_odds(fish_catalog, odds);
+    for _ in 1..casts {
+        let next = fishing_with_odds(fish_catalog, odds);
+        if fish_catalog.get_rarity_rank(&next) > fish_catalog.get_rarity_rank(&best) {
+            best = next;
+        }
+    }
+    best
+}
+
+/// Retorna um peixe aleatório de uma raridade específica, sem as espécies exclusivas de clima
+pub fn fishing_from_rank(fish_catalog: &FishCatalog, rank: u8) -> String {
     let mut rng = rand::rng();
-    // Probabilidades: 50% comum, 25% raro, 15% épico, 6% shiny, 2.5% lendário, 1% mítico, 0.5% abissal
-    let x: u32 = rand::random::<u32>() % 200;
-
-    let fish_list = match x {
-        0 => &fish_catalog.abyssals,
-        1..=2 => &fish_catalog.mythicals,
-        3..=7 => &fish_catalog.legendaries,
-        8..=19 => &fish_catalog.shiny,
-        20..=49 => &fish_catalog.epics,
-        50..=99 => &fish_catalog.rares,
-        _ => &fish_catalog.commons,
-    };
-
-    fish_list.choose(&mut rng).unwrap().clone()
+    let fish: Vec<&String> = fish_catalog
+        .get_fish_by_rank(rank)
+        .iter()
+        .filter(|f| !EXCLUSIVE_SPECIES.iter().any(|(e, _)| e == f))
+        .collect();
+    fish.choose(&mut rng).unwrap().to_string()
 }
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use super::ids::IdGenerator;
+use crate::server::{InventoryItem, Peer};
+
+/// Presente enviado que o destinatário ainda não confirmou
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gift {
+    pub id: String,
+    pub to: Peer,
+    pub item: InventoryItem,
+}
+
+/// Presentes enviados aguardando confirmação. O item fica reservado até o destinatário
+/// responder, e só sai do cesto se ele aceitar
+#[derive(Debug, Default)]
+pub struct GiftBook {
+    pub pending: HashMap<String, Gift>,
+    pub ids: IdGenerator,
+}
+
+impl GiftBook {
+    /// Quantidade de um item reservada em presentes não confirmados
+    pub fn reserved(&self, item: &str) -> u32 {
+        self.pending
+            .values()
+            .filter(|g| g.item.fish_type == item)
+            .map(|g| g.item.quantity)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_gifts_are_reserved() {
+        let mut gifts = GiftBook::default();
+        let bob = Peer::new("bob".to_string(), "127.0.0.1:6001".parse().unwrap());
+        for quantity in [1, 2] {
+            let id = gifts.ids.next("alice");
+            gifts.pending.insert(
+                id.clone(),
+                Gift {
+                    id,
+                    to: bob.clone(),
+                    item: InventoryItem::new("atum".to_string(), quantity),
+                },
+            );
+        }
+        assert_eq!(gifts.reserved("atum"), 3);
+        assert_eq!(gifts.reserved("salmão"), 0);
+        gifts.pending.remove("alice-2");
+        assert_eq!(gifts.reserved("atum"), 1);
+    }
+}
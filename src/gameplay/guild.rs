@@ -0,0 +1,510 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::ids::IdGenerator;
+use crate::server::InventoryItem;
+
+/// Quórum padrão para saques do cesto da guilda
+pub const GUILD_QUORUM: u32 = 2;
+
+/// Pedido de saque do cesto da guilda aguardando os votos dos membros
+#[derive(Debug, Clone, PartialEq)]
+pub struct Withdrawal {
+    pub id: String,
+    pub requester: String,
+    pub item: InventoryItem,
+    pub approvals: BTreeSet<String>,
+    pub refusals: BTreeSet<String>,
+}
+
+/// O que um membro pede ao líder da guilda
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuildAction {
+    Entrar,
+    Sair,
+    Depositar(InventoryItem),
+    Sacar(InventoryItem),
+    Votar {
+        id: String,
+        approve: bool,
+    },
+    /// Só o líder muda o quórum
+    Quorum(u32),
+}
+
+/// Resposta do líder a um pedido, enviada a quem pediu
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuildReply {
+    pub id: String,
+    pub requester: String,
+    pub accepted: bool,
+}
+
+/// Guilda de pescadores com um cesto compartilhado. O líder ordena todas as mudanças e
+/// anuncia o estado completo a cada uma, com a versão. Os membros guardam a réplica mais
+/// nova, e se o líder sai da rede o próximo membro online assume com ela
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guild {
+    pub name: String,
+    pub leader: String,
+    pub members: BTreeSet<String>,
+    /// Aprovações necessárias para um saque, contando quem pediu
+    pub quorum: u32,
+    pub basket: BTreeMap<String, u32>,
+    pub pending: Vec<Withdrawal>,
+    pub version: u64,
+}
+
+/// Guildas conhecidas na rede e os nossos pedidos ainda sem resposta
+#[derive(Debug, Default)]
+pub struct GuildBook {
+    pub guilds: HashMap<String, Guild>,
+    /// Pedidos feitos ao líder, pelo id: nome da guilda e a ação
+    pub requests: HashMap<String, (String, GuildAction)>,
+    pub ids: IdGenerator,
+}
+
+impl Guild {
+    pub fn new(name: String, founder: String, quorum: u32) -> Self {
+        Self {
+            name,
+            leader: founder.clone(),
+            members: BTreeSet::from([founder]),
+            quorum,
+            basket: BTreeMap::new(),
+            pending: Vec::new(),
+            version: 1,
+        }
+    }
+
+    /// Quórum que pode ser alcançado com os membros atuais
+    pub fn effective_quorum(&self) -> u32 {
+        self.quorum.clamp(1, self.members.len().max(1) as u32)
+    }
+
+    /// Quantidade de um peixe no cesto que não está em saques pendentes
+    pub fn available(&self, fish: &str) -> u32 {
+        let pending: u32 = self
+            .pending
+            .iter()
+            .filter(|w| w.item.fish_type == fish)
+            .map(|w| w.item.quantity)
+            .fold(0, u32::saturating_add);
+        self.basket
+            .get(fish)
+            .copied()
+            .unwrap_or(0)
+            .saturating_sub(pending)
+    }
+
+    /// Aplica o pedido de um peer. Só o líder chama, e cada mudança aumenta a versão.
+    /// Retorna as respostas a enviar: saques só são respondidos quando os votos decidem
+    pub fn apply(
+        &mut self,
+        requester: &str,
+        id: &str,
+        action: &GuildAction,
+    ) -> Result<Vec<GuildReply>, String> {
+        let member = self.members.contains(requester);
+        let reply = |accepted| GuildReply {
+            id: id.to_string(),
+            requester: requester.to_string(),
+            accepted,
+        };
+        let replies = match action {
+            GuildAction::Entrar if member => return Err("já é membro".to_string()),
+            _ if !member && *action != GuildAction::Entrar => {
+                return Err("não é membro".to_string());
+            }
+            GuildAction::Entrar => {
+                self.members.insert(requester.to_string());
+                vec![reply(true)]
+            }
+            GuildAction::Sair => {
+                if self.members.len() == 1 && !self.basket.is_empty() {
+                    return Err("o cesto precisa ser esvaziado antes".to_string());
+                }
+                self.members.remove(requester);
+                if self.leader == requester {
+                    self.leader = self.members.first().cloned().unwrap_or_default();
+                }
+                // Saques de quem saiu são recusados, e os votos dele deixam de contar
+                let mut replies: Vec<GuildReply> = self
+                    .pending
+                    .iter()
+                    .filter(|w| w.requester == requester)
+                    .map(|w| GuildReply {
+                        id: w.id.clone(),
+                        requester: w.requester.clone(),
+                        accepted: false,
+                    })
+                    .collect();
+                self.pending.retain(|w| w.requester != requester);
+                for w in &mut self.pending {
+                    w.approvals.remove(requester);
+                    w.refusals.remove(requester);
+                }
+                replies.push(reply(true));
+                replies.extend(self.settle());
+                replies
+            }
+            GuildAction::Depositar(item) if item.quantity == 0 => {
+                return Err("o depósito precisa ter ao menos um peixe".to_string());
+            }
+            GuildAction::Depositar(item) => {
+                let count = self.basket.entry(item.fish_type.clone()).or_insert(0);
+                *count = count.saturating_add(item.quantity);
+                vec![reply(true)]
+            }
+            GuildAction::Sacar(item) => {
+                if item.quantity == 0 || self.available(&item.fish_type) < item.quantity {
+                    return Err(format!("o cesto não tem {}", item));
+                }
+                self.pending.push(Withdrawal {
+                    id: id.to_string(),
+                    requester: requester.to_string(),
+                    item: item.clone(),
+                    approvals: BTreeSet::from([requester.to_string()]),
+                    refusals: BTreeSet::new(),
+                });
+                self.settle()
+            }
+            GuildAction::Votar { id, approve } => {
+                let Some(w) = self.pending.iter_mut().find(|w| w.id == *id) else {
+                    return Err(format!("saque {} não encontrado", id));
+                };
+                if *approve {
+                    w.refusals.remove(requester);
+                    w.approvals.insert(requester.to_string());
+                } else {
+                    w.approvals.remove(requester);
+                    w.refusals.insert(requester.to_string());
+                }
+                self.settle()
+            }
+            GuildAction::Quorum(_) if requester != self.leader => {
+                return Err("só o líder muda o quórum".to_string());
+            }
+            GuildAction::Quorum(0) => return Err("o quórum precisa ser maior que 0".to_string()),
+            // Um quórum novo pode decidir saques pendentes
+            GuildAction::Quorum(quorum) => {
+                self.quorum = *quorum;
+                let mut replies = self.settle();
+                replies.push(reply(true));
+                replies
+            }
+        };
+        self.version += 1;
+        Ok(replies)
+    }
+
+    /// Quem assume se o líder sair da rede: o primeiro outro membro online, em ordem
+    /// alfabética
+    pub fn successor(&self, online: &HashSet<String>) -> Option<&String> {
+        self.members
+            .iter()
+            .find(|m| **m != self.leader && online.contains(*m))
+    }
+
+    /// Assume a liderança no lugar de um líder que saiu da rede
+    pub fn take_leadership(&mut self, username: &str) {
+        self.leader = username.to_string();
+        self.version += 1;
+    }
+
+    /// Conclui os saques que alcançaram o quórum e recusa os que não podem mais alcançar
+    fn settle(&mut self) -> Vec<GuildReply> {
+        let quorum = self.effective_quorum() as usize;
+        let members = self.members.len();
+        let mut replies = Vec::new();
+        let mut pending = Vec::new();
+        for w in std::mem::take(&mut self.pending) {
+            if w.approvals.len() >= quorum {
+                if let Some(count) = self.basket.get_mut(&w.item.fish_type) {
+                    *count = count.saturating_sub(w.item.quantity);
+                    if *count == 0 {
+                        self.basket.remove(&w.item.fish_type);
+                    }
+                }
+            } else if w.refusals.len() <= members.saturating_sub(quorum) {
+                pending.push(w);
+                continue;
+            }
+            replies.push(GuildReply {
+                accepted: w.approvals.len() >= quorum,
+                id: w.id,
+                requester: w.requester,
+            });
+        }
+        self.pending = pending;
+        replies
+    }
+}
+
+impl GuildBook {
+    /// Guilda da qual o peer é membro, se houver
+    pub fn of_member(&self, username: &str) -> Option<&Guild> {
+        self.guilds.values().find(|g| g.members.contains(username))
+    }
+
+    /// Etiqueta mostrada ao lado do nome do peer no chat e na lista de peers
+    pub fn tag(&self, username: &str) -> String {
+        self.of_member(username)
+            .map(|g| format!(" [{}]", g.name))
+            .unwrap_or_default()
+    }
+
+    /// Guarda o estado anunciado de uma guilda se ele for mais novo que o conhecido. Só o
+    /// líder anuncia, ou quem assumiria no lugar dele; uma guilda desconhecida só vale se
+    /// vier do próprio líder. Guildas sem membros são desfeitas. Retorna o estado anterior
+    /// se houve mudança
+    pub fn update(
+        &mut self,
+        guild: Guild,
+        sender: &str,
+        online: &HashSet<String>,
+    ) -> Option<Option<Guild>> {
+        let trusted = match self.guilds.get(&guild.name) {
+            Some(known) => {
+                known.version < guild.version
+                    && (known.leader == sender
+                        || known.successor(online).is_some_and(|s| s == sender))
+            }
+            None => guild.leader == sender,
+        };
+        if !trusted {
+            return None;
+        }
+        if guild.members.is_empty() {
+            return Some(self.guilds.remove(&guild.name));
+        }
+        Some(self.guilds.insert(guild.name.clone(), guild))
+    }
+
+    /// Quantidade de um item reservada em depósitos ainda não confirmados pelo líder
+    pub fn reserved(&self, item: &str) -> u32 {
+        self.requests
+            .values()
+            .filter_map(|(_, action)| match action {
+                GuildAction::Depositar(i) if i.fish_type == item => Some(i.quantity),
+                _ => None,
+            })
+            .fold(0, u32::saturating_add)
+    }
+}
+
+impl Display for GuildAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuildAction::Entrar => write!(f, "entrar"),
+            GuildAction::Sair => write!(f, "sair"),
+            GuildAction::Depositar(item) => write!(f, "depositar {}", item),
+            GuildAction::Sacar(item) => write!(f, "sacar {}", item),
+            GuildAction::Votar { id, approve: true } => write!(f, "aprovar {}", id),
+            GuildAction::Votar { id, approve: false } => write!(f, "recusar {}", id),
+            GuildAction::Quorum(quorum) => write!(f, "quorum {}", quorum),
+        }
+    }
+}
+
+impl FromStr for GuildAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, arg) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let arg = arg.trim();
+        match kind {
+            "entrar" => Ok(GuildAction::Entrar),
+            "sair" => Ok(GuildAction::Sair),
+            "depositar" => Ok(GuildAction::Depositar(InventoryItem::from_str(arg)?)),
+            "sacar" => Ok(GuildAction::Sacar(InventoryItem::from_str(arg)?)),
+            "quorum" => Ok(GuildAction::Quorum(
+                arg.parse()
+                    .map_err(|_| format!("Invalid quorum: {}", arg))?,
+            )),
+            "aprovar" | "recusar" if !arg.is_empty() => Ok(GuildAction::Votar {
+                id: arg.to_string(),
+                approve: kind == "aprovar",
+            }),
+            _ => Err(format!("Invalid guild action: {}", s)),
+        }
+    }
+}
+
+/// Formato: `id>pedinte:peixe|n:aprovou1+aprovou2:recusou1`
+impl Display for Withdrawal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |names: &BTreeSet<String>| names.iter().cloned().collect::<Vec<_>>().join("+");
+        write!(
+            f,
+            "{}>{}:{}:{}:{}",
+            self.id,
+            self.requester,
+            self.item,
+            join(&self.approvals),
+            join(&self.refusals)
+        )
+    }
+}
+
+impl FromStr for Withdrawal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid withdrawal: {}", s);
+        let (id, rest) = s.trim().split_once('>').ok_or_else(invalid)?;
+        let parts: Vec<&str> = rest.split(':').collect();
+        let [requester, item, approvals, refusals] = parts[..] else {
+            return Err(invalid());
+        };
+        let names = |s: &str| {
+            s.split('+')
+                .filter(|n| !n.is_empty())
+                .map(|n| n.to_string())
+                .collect()
+        };
+        Ok(Withdrawal {
+            id: id.to_string(),
+            requester: requester.to_string(),
+            item: InventoryItem::from_str(item)?,
+            approvals: names(approvals),
+            refusals: names(refusals),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(fish: &str, quantity: u32) -> InventoryItem {
+        InventoryItem::new(fish.to_string(), quantity)
+    }
+
+    #[test]
+    fn withdrawal_needs_quorum() {
+        let mut guild = Guild::new("tubarões".to_string(), "alice".to_string(), 2);
+        assert!(guild.apply("bob", "bob-1", &GuildAction::Sair).is_err());
+        guild.apply("bob", "bob-1", &GuildAction::Entrar).unwrap();
+        guild
+            .apply("carol", "carol-1", &GuildAction::Entrar)
+            .unwrap();
+        guild
+            .apply("bob", "bob-2", &GuildAction::Depositar(item("atum", 3)))
+            .unwrap();
+        assert!(
+            guild
+                .apply("carol", "carol-2", &GuildAction::Sacar(item("atum", 4)))
+                .is_err()
+        );
+
+        // O saque fica pendente até outro membro aprovar
+        let replies = guild
+            .apply("carol", "carol-2", &GuildAction::Sacar(item("atum", 2)))
+            .unwrap();
+        assert!(replies.is_empty());
+        assert_eq!(guild.available("atum"), 1);
+        let vote = GuildAction::Votar {
+            id: "carol-2".to_string(),
+            approve: true,
+        };
+        let replies = guild.apply("alice", "alice-1", &vote).unwrap();
+        assert_eq!(replies.len(), 1);
+        assert!(replies[0].accepted);
+        assert_eq!(replies[0].requester, "carol");
+        assert_eq!(guild.basket.get("atum"), Some(&1));
+        assert_eq!(guild.version, 6);
+        assert!(
+            guild
+                .apply("bob", "bob-3", &GuildAction::Quorum(1))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn withdrawal_refused_when_quorum_is_impossible() {
+        let mut guild = Guild::new("tubarões".to_string(), "alice".to_string(), 3);
+        guild.apply("bob", "bob-1", &GuildAction::Entrar).unwrap();
+        guild
+            .apply("carol", "carol-1", &GuildAction::Entrar)
+            .unwrap();
+        guild
+            .apply("alice", "alice-1", &GuildAction::Depositar(item("atum", 1)))
+            .unwrap();
+        guild
+            .apply("bob", "bob-2", &GuildAction::Sacar(item("atum", 1)))
+            .unwrap();
+        let vote = GuildAction::Votar {
+            id: "bob-2".to_string(),
+            approve: false,
+        };
+        let replies = guild.apply("carol", "carol-2", &vote).unwrap();
+        assert_eq!(replies.len(), 1);
+        assert!(!replies[0].accepted);
+        assert_eq!(guild.basket.get("atum"), Some(&1));
+    }
+
+    #[test]
+    fn deposits_are_checked() {
+        let mut guild = Guild::new("tubarões".to_string(), "alice".to_string(), 2);
+        assert!(
+            guild
+                .apply("alice", "alice-1", &GuildAction::Depositar(item("atum", 0)))
+                .is_err()
+        );
+        for id in ["alice-2", "alice-3"] {
+            guild
+                .apply("alice", id, &GuildAction::Depositar(item("atum", u32::MAX)))
+                .unwrap();
+        }
+        assert_eq!(guild.basket.get("atum"), Some(&u32::MAX));
+    }
+
+    #[test]
+    fn only_the_leader_or_successor_announces() {
+        let mut book = GuildBook::default();
+        let mut guild = Guild::new("tubarões".to_string(), "bob".to_string(), 2);
+        guild
+            .apply("alice", "alice-1", &GuildAction::Entrar)
+            .unwrap();
+        guild
+            .apply("carol", "carol-1", &GuildAction::Entrar)
+            .unwrap();
+        let online = HashSet::from(["alice".to_string(), "carol".to_string()]);
+        assert!(book.update(guild.clone(), "carol", &online).is_none());
+        assert!(book.update(guild.clone(), "bob", &online).is_some());
+
+        // carol não é a sucessora, alice vem antes em ordem alfabética
+        let mut forged = guild.clone();
+        forged.take_leadership("carol");
+        assert!(book.update(forged, "carol", &online).is_none());
+        let mut next = guild.clone();
+        next.take_leadership("alice");
+        assert!(book.update(next, "alice", &online).is_some());
+        assert_eq!(book.guilds["tubarões"].leader, "alice");
+    }
+
+    #[test]
+    fn text_formats_round_trip() {
+        let w = Withdrawal {
+            id: "bob-2".to_string(),
+            requester: "bob".to_string(),
+            item: item("atum", 1),
+            approvals: BTreeSet::from(["alice".to_string(), "bob".to_string()]),
+            refusals: BTreeSet::new(),
+        };
+        assert_eq!(Withdrawal::from_str(&w.to_string()), Ok(w));
+        for action in [
+            GuildAction::Entrar,
+            GuildAction::Sacar(item("atum", 2)),
+            GuildAction::Quorum(3),
+            GuildAction::Votar {
+                id: "bob-2".to_string(),
+                approve: false,
+            },
+        ] {
+            assert_eq!(GuildAction::from_str(&action.to_string()), Ok(action));
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::server::InventoryItem;
+
+/// Cabeçalho do CSV do histórico, usado tanto no arquivo salvo quanto na exportação
+const CSV_HEADER: &str = "data,resultado,peer,entregue,recebido";
+
+/// Como uma troca terminou
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeOutcome {
+    Concluida,
+    Recusada,
+    /// A oferta foi descartada sem resposta, ex: o peer saiu da rede
+    Expirada,
+}
+
+/// Registro de uma troca, do nosso ponto de vista
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeRecord {
+    pub time: u64,
+    pub outcome: TradeOutcome,
+    pub peer: String,
+    pub given: Vec<InventoryItem>,
+    pub received: Vec<InventoryItem>,
+}
+
+/// Histórico de trocas, salvo num arquivo CSV a cada novo registro
+#[derive(Debug, Default)]
+pub struct TradeHistory {
+    pub records: Vec<TradeRecord>,
+    path: Option<PathBuf>,
+}
+
+impl TradeRecord {
+    /// Se a troca envolve o peer e o peixe dados, quando informados
+    pub fn matches(&self, peer: Option<&str>, fish: Option<&str>) -> bool {
+        peer.is_none_or(|p| self.peer == p)
+            && fish.is_none_or(|f| {
+                self.given
+                    .iter()
+                    .chain(&self.received)
+                    .any(|i| i.fish_type == f)
+            })
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{}",
+            self.time,
+            self.outcome,
+            csv_escape(&self.peer),
+            csv_escape(&items_to_string(&self.given)),
+            csv_escape(&items_to_string(&self.received))
+        )
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"data\": {}, \"resultado\": \"{}\", \"peer\": \"{}\", \"entregue\": {}, \"recebido\": {}}}",
+            self.time,
+            self.outcome,
+            json_escape(&self.peer),
+            items_to_json(&self.given),
+            items_to_json(&self.received)
+        )
+    }
+}
+
+impl TradeHistory {
+    /// Carrega o histórico salvo no arquivo e passa a salvar os novos registros nele
+    pub fn load(&mut self, path: &Path) {
+        if let Ok(content) = fs::read_to_string(path) {
+            self.records = content
+                .lines()
+                .skip(1)
+                .filter_map(|line| TradeRecord::from_str(line).ok())
+                .collect();
+        }
+        self.path = Some(path.to_path_buf());
+    }
+
+    /// Guarda um registro no histórico e no arquivo
+    pub fn record(&mut self, record: TradeRecord) {
+        if let Some(path) = &self.path {
+            let is_new = !path.exists();
+            let file = OpenOptions::new().create(true).append(true).open(path);
+            let written = file.and_then(|mut f| {
+                if is_new {
+                    writeln!(f, "{}", CSV_HEADER)?;
+                }
+                writeln!(f, "{}", record.to_csv())
+            });
+            if written.is_err() {
+                crate::tui::err("* Não foi possível salvar o histórico de trocas.");
+            }
+        }
+        self.records.push(record);
+    }
+
+    /// Registros que envolvem o peer e o peixe dados, quando informados
+    pub fn filter(&self, peer: Option<&str>, fish: Option<&str>) -> Vec<&TradeRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.matches(peer, fish))
+            .collect()
+    }
+}
+
+/// Exporta registros para CSV, com cabeçalho
+pub fn history_to_csv(records: &[&TradeRecord]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    for record in records {
+        csv.push('\n');
+        csv.push_str(&record.to_csv());
+    }
+    csv.push('\n');
+    csv
+}
+
+/// Exporta registros para uma lista JSON
+pub fn history_to_json(records: &[&TradeRecord]) -> String {
+    let entries: Vec<String> = records
+        .iter()
+        .map(|r| format!("  {}", r.to_json()))
+        .collect();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+/// Itens separados por espaço, ex: `atum|2 moedas|10`. Evita vírgulas dentro do CSV
+fn items_to_string(items: &[InventoryItem]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn items_to_json(items: &[InventoryItem]) -> String {
+    let items: Vec<String> = items
+        .iter()
+        .map(|i| {
+            format!(
+                "{{\"peixe\": \"{}\", \"quantidade\": {}}}",
+                json_escape(&i.fish_type),
+                i.quantity
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(", "))
+}
+
+pub(super) fn json_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Coloca um campo entre aspas se ele tiver vírgula, aspas ou quebra de linha, dobrando as
+/// aspas internas
+pub(super) fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Separa uma linha de CSV em campos, desfazendo o `csv_escape`
+pub(super) fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+impl FromStr for TradeRecord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = csv_fields(s.trim());
+        let [time, outcome, peer, given, received] = &fields[..] else {
+            return Err("Invalid trade record".to_string());
+        };
+        let parse_items = |s: &str| -> Result<Vec<InventoryItem>, String> {
+            s.split_whitespace().map(InventoryItem::from_str).collect()
+        };
+        Ok(TradeRecord {
+            time: time.parse().map_err(|_| "Invalid time".to_string())?,
+            outcome: TradeOutcome::from_str(outcome)?,
+            peer: peer.to_string(),
+            given: parse_items(given)?,
+            received: parse_items(received)?,
+        })
+    }
+}
+
+impl FromStr for TradeOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "concluida" => Ok(TradeOutcome::Concluida),
+            "recusada" => Ok(TradeOutcome::Recusada),
+            "expirada" => Ok(TradeOutcome::Expirada),
+            _ => Err("Invalid trade outcome".to_string()),
+        }
+    }
+}
+
+impl Display for TradeOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeOutcome::Concluida => write!(f, "concluida"),
+            TradeOutcome::Recusada => write!(f, "recusada"),
+            TradeOutcome::Expirada => write!(f, "expirada"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> TradeRecord {
+        TradeRecord {
+            time: 1760000000,
+            outcome: TradeOutcome::Concluida,
+            peer: "bob".to_string(),
+            given: vec![
+                InventoryItem::new("atum".to_string(), 2),
+                InventoryItem::new("moedas".to_string(), 10),
+            ],
+            received: vec![InventoryItem::new("peixe-lua".to_string(), 1)],
+        }
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let record = record();
+        let csv = history_to_csv(&[&record]);
+        assert_eq!(
+            csv,
+            "data,resultado,peer,entregue,recebido\n1760000000,concluida,bob,atum|2 moedas|10,peixe-lua|1\n"
+        );
+        let line = csv.lines().nth(1).unwrap();
+        assert_eq!(TradeRecord::from_str(line), Ok(record));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted() {
+        let mut record = record();
+        record.peer = "bo,b \"o grande\"".to_string();
+        record.received = vec![InventoryItem::new("peixe,lua".to_string(), 1)];
+        let line = record.to_csv();
+        assert_eq!(
+            line,
+            "1760000000,concluida,\"bo,b \"\"o grande\"\"\",atum|2 moedas|10,\"peixe,lua|1\""
+        );
+        assert_eq!(TradeRecord::from_str(&line), Ok(record));
+    }
+
+    #[test]
+    fn filters_by_peer_and_fish() {
+        let record = record();
+        assert!(record.matches(None, None));
+        assert!(record.matches(Some("bob"), Some("peixe-lua")));
+        assert!(!record.matches(Some("alice"), None));
+        assert!(!record.matches(None, Some("sardinha")));
+    }
+}
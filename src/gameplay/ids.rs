@@ -0,0 +1,35 @@
+/// Gera ids curtos para as coisas que criamos na rede, ex: `alice-1`. Cada livro tem o
+/// seu contador, então ids de tipos diferentes podem se repetir
+#[derive(Debug, Default)]
+pub struct IdGenerator {
+    next: u32,
+}
+
+impl IdGenerator {
+    /// Próximo id, com o nome de quem cria na frente e um número que só cresce
+    pub fn next(&mut self, username: &str) -> String {
+        self.next += 1;
+        format!("{}-{}", username, self.next)
+    }
+}
+
+/// Checa se o id foi gerado por `username`, ou seja, se é o nome dele seguido de um número
+pub fn made_by(id: &str, username: &str) -> bool {
+    id.rsplit_once('-')
+        .is_some_and(|(name, n)| name == username && n.parse::<u32>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_carry_their_creator() {
+        let mut ids = IdGenerator::default();
+        let id = ids.next("ana-maria");
+        assert_eq!(id, "ana-maria-1");
+        assert!(made_by(&id, "ana-maria"));
+        assert!(!made_by(&id, "ana"));
+        assert!(!made_by("ana-maria-x", "ana-maria"));
+    }
+}
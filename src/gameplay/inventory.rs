@@ -1,4 +1,22 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::fisher::FishCatalog;
+use crate::server::InventoryItem;
+
+/// Quantos peixes cabem na cesta sem aquários
+pub const BASKET_CAPACITY: u32 = 50;
+
+/// Espaço extra que cada aquário dá
+pub const AQUARIUM_CAPACITY: u32 = 25;
+
+/// Preço de um aquário em moedas
+pub const AQUARIUM_PRICE: u32 = 250;
+
+/// Capacidade total da cesta com os aquários dados
+pub fn basket_capacity(aquariums: u32) -> u32 {
+    BASKET_CAPACITY + aquariums * AQUARIUM_CAPACITY
+}
 
 /// Uma cesta de peixes, serve para armazenar todos os peixes do usuário
 #[derive(Debug, PartialEq)]
@@ -19,6 +37,25 @@ impl FishBasket {
     pub fn map_mut(&mut self) -> &mut HashMap<String, u32> {
         &mut self.0
     }
+
+    /// Quantidade total de peixes na cesta
+    pub fn total(&self) -> u32 {
+        self.0.values().copied().fold(0, u32::saturating_add)
+    }
+
+    /// Retira `quantity` unidades de um peixe, só se houver o suficiente
+    pub fn take(&mut self, fish: &str, quantity: u32) -> bool {
+        match self.0.get_mut(fish) {
+            Some(count) if *count >= quantity => {
+                *count -= quantity;
+                if *count == 0 {
+                    self.0.remove(fish);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 impl Default for FishBasket {
@@ -26,3 +63,137 @@ impl Default for FishBasket {
         Self::new()
     }
 }
+
+/// Ordem em que o inventário é mostrado
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InventoryOrder {
+    #[default]
+    Raridade,
+    Quantidade,
+    Nome,
+}
+
+/// Filtros e ordem pedidos em `$i`, usados tanto na nossa cesta quanto no inventário
+/// recebido de um peer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InventoryView {
+    pub tier: Option<u8>,
+    pub order: InventoryOrder,
+    /// Trecho do nome do peixe, sem diferenciar maiúsculas
+    pub search: Option<String>,
+}
+
+impl InventoryView {
+    /// Checa se algum filtro está ativo
+    pub fn is_filtered(&self) -> bool {
+        self.tier.is_some() || self.search.is_some()
+    }
+
+    /// Filtra e ordena os itens. Empates são desfeitos pelo nome, para a lista não mudar
+    /// de ordem entre uma chamada e outra
+    pub fn apply(&self, catalog: &FishCatalog, items: &[InventoryItem]) -> Vec<InventoryItem> {
+        let search = self.search.as_ref().map(|s| s.to_lowercase());
+        let mut items: Vec<InventoryItem> = items
+            .iter()
+            .filter(|i| {
+                self.tier
+                    .is_none_or(|tier| catalog.get_rarity_rank(&i.fish_type) == tier)
+            })
+            .filter(|i| {
+                search
+                    .as_ref()
+                    .is_none_or(|s| i.fish_type.to_lowercase().contains(s))
+            })
+            .cloned()
+            .collect();
+        match self.order {
+            InventoryOrder::Raridade => {
+                items.sort_by_key(|i| (catalog.get_rarity_rank(&i.fish_type), i.fish_type.clone()))
+            }
+            InventoryOrder::Quantidade => {
+                items.sort_by_key(|i| (std::cmp::Reverse(i.quantity), i.fish_type.clone()))
+            }
+            InventoryOrder::Nome => items.sort_by(|a, b| a.fish_type.cmp(&b.fish_type)),
+        }
+        items
+    }
+}
+
+/// Quantidade de peixes de cada raridade, indexada pelo rank. As vitrines dos outros
+/// peers chegam com qualquer quantidade, então a soma satura
+pub fn tier_totals(catalog: &FishCatalog, items: &[InventoryItem]) -> [u32; 7] {
+    let mut totals = [0u32; 7];
+    for item in items {
+        let total = &mut totals[catalog.get_rarity_rank(&item.fish_type) as usize];
+        *total = total.saturating_add(item.quantity);
+    }
+    totals
+}
+
+impl FromStr for InventoryOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "raridade" => Ok(InventoryOrder::Raridade),
+            "quantidade" => Ok(InventoryOrder::Quantidade),
+            "nome" => Ok(InventoryOrder::Nome),
+            _ => Err(format!("Ordem desconhecida: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_only_what_exists() {
+        let mut basket = FishBasket::new();
+        basket.map_mut().insert("atum".to_string(), 3);
+        basket.map_mut().insert("salmão".to_string(), 1);
+        assert_eq!(basket.total(), 4);
+        assert!(!basket.take("atum", 4));
+        assert!(basket.take("atum", 3));
+        assert!(!basket.map().contains_key("atum"));
+        assert_eq!(basket.total(), 1);
+        assert_eq!(basket_capacity(2), BASKET_CAPACITY + 2 * AQUARIUM_CAPACITY);
+    }
+
+    #[test]
+    fn view_filters_and_sorts() {
+        let catalog = FishCatalog::new();
+        let items = vec![
+            InventoryItem::new("salmão".to_string(), 2),
+            InventoryItem::new("sardinha".to_string(), 5),
+            InventoryItem::new("atum".to_string(), 1),
+        ];
+        let view = InventoryView {
+            order: InventoryOrder::Quantidade,
+            search: Some("SA".to_string()),
+            ..Default::default()
+        };
+        let shown: Vec<String> = view
+            .apply(&catalog, &items)
+            .into_iter()
+            .map(|i| i.fish_type)
+            .collect();
+        assert_eq!(shown, vec!["sardinha", "salmão"]);
+        let rank = catalog.get_rarity_rank("atum");
+        let view = InventoryView {
+            tier: Some(rank),
+            ..Default::default()
+        };
+        assert!(
+            view.apply(&catalog, &items)
+                .iter()
+                .all(|i| catalog.get_rarity_rank(&i.fish_type) == rank)
+        );
+        assert_eq!(tier_totals(&catalog, &items).iter().sum::<u32>(), 8);
+        let huge = vec![
+            InventoryItem::new("atum".to_string(), u32::MAX),
+            InventoryItem::new("atum".to_string(), 1),
+        ];
+        assert_eq!(tier_totals(&catalog, &huge)[rank as usize], u32::MAX);
+    }
+}
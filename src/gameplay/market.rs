@@ -0,0 +1,17 @@
+use super::fisher::FishCatalog;
+
+/// Nome do item que representa moedas dentro de uma oferta, ex: `moedas|50`
+pub const COIN_ITEM: &str = "moedas";
+
+/// Preço que o mercado paga por um peixe, indexado pelo rank de raridade
+pub const RARITY_PRICES: [u32; 7] = [5, 12, 30, 75, 200, 500, 1500];
+
+/// Checa se um item de oferta representa moedas ao invés de um peixe
+pub fn is_coin(item: &str) -> bool {
+    item == COIN_ITEM
+}
+
+/// Preço de venda de um peixe para o mercado
+pub fn sell_price(catalog: &FishCatalog, fish: &str) -> u32 {
+    RARITY_PRICES[catalog.get_rarity_rank(fish) as usize]
+}
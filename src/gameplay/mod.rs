@@ -1,6 +1,144 @@
+mod auction;
+mod board;
+mod clock;
+mod duel;
+mod environment;
+mod export;
 mod fisher;
+mod gift;
+mod guild;
+mod history;
+mod ids;
 mod inventory;
+mod market;
+mod multitrade;
+mod orders;
+mod party;
+mod privacy;
+mod quests;
+mod recipes;
+mod rules;
+mod scarcity;
+mod search;
+mod shoal;
+mod suggest;
+mod tournament;
+mod valuation;
+mod wishlist;
 
+pub use auction::Auction;
+pub use auction::AuctionHouse;
+pub use board::OfferBoard;
+pub use board::PublicOffer;
+pub use clock::format_elapsed;
+pub use clock::now_millis;
+pub use clock::now_secs;
+pub use clock::parse_duration;
+pub use clock::today;
+pub use duel::DUEL_CASTS;
+pub use duel::DUEL_TIMEOUT;
+pub use duel::Duel;
+pub use duel::DuelBook;
+pub use duel::DuelResult;
+pub use duel::commitment;
+pub use environment::Condition;
+pub use environment::EXCLUSIVE_SPECIES;
+pub use environment::Environment;
+pub use environment::TimeOfDay;
+pub use environment::WEATHER_SLOT;
+pub use environment::Weather;
+pub use export::basket_from_csv;
+pub use export::basket_from_json;
+pub use export::basket_path;
+pub use export::export_basket;
+pub use export::split_coins;
 pub use fisher::FishCatalog;
+pub use fisher::FishingOdds;
+pub use fisher::RARITY_NAMES;
+pub use fisher::RARITY_POINTS;
+pub use fisher::TIER_WEIGHTS;
 pub use fisher::fishing;
+pub use fisher::fishing_best_of;
+pub use fisher::fishing_from_rank;
+pub use fisher::fishing_with_bait;
+pub use fisher::fishing_with_odds;
+pub use fisher::rarity_rank_from_name;
+pub use gift::Gift;
+pub use gift::GiftBook;
+pub use guild::GUILD_QUORUM;
+pub use guild::Guild;
+pub use guild::GuildAction;
+pub use guild::GuildBook;
+pub use guild::GuildReply;
+pub use guild::Withdrawal;
+pub use history::TradeHistory;
+pub use history::TradeOutcome;
+pub use history::TradeRecord;
+pub use history::history_to_csv;
+pub use history::history_to_json;
+pub use ids::IdGenerator;
+pub use ids::made_by;
+pub use inventory::AQUARIUM_CAPACITY;
+pub use inventory::AQUARIUM_PRICE;
+pub use inventory::BASKET_CAPACITY;
 pub use inventory::FishBasket;
+pub use inventory::InventoryOrder;
+pub use inventory::InventoryView;
+pub use inventory::basket_capacity;
+pub use inventory::tier_totals;
+pub use market::COIN_ITEM;
+pub use market::RARITY_PRICES;
+pub use market::is_coin;
+pub use market::sell_price;
+pub use multitrade::MULTI_TRADE_TIMEOUT;
+pub use multitrade::MultiTrade;
+pub use multitrade::MultiTradeBook;
+pub use multitrade::TradeLeg;
+pub use multitrade::parse_legs;
+pub use orders::Order;
+pub use orders::OrderBook;
+pub use orders::OrderSide;
+pub use party::PARTY_BONUS;
+pub use party::PARTY_RARE_RANK;
+pub use party::PARTY_SIZE;
+pub use party::Party;
+pub use party::PartyBook;
+pub use privacy::InspectionDecision;
+pub use privacy::InventoryAccess;
+pub use privacy::Privacy;
+pub use quests::QuestBook;
+pub use quests::Reward;
+pub use recipes::ActiveBonus;
+pub use recipes::Consumables;
+pub use recipes::Product;
+pub use recipes::RECIPES;
+pub use recipes::Recipe;
+pub use recipes::RecipeKind;
+pub use rules::RuleDecision;
+pub use rules::TRADE_RULES;
+pub use rules::TradeRule;
+pub use rules::TradeRules;
+pub use scarcity::MAX_REPORTED_CATCHES;
+pub use scarcity::MIN_SCARCITY_WEIGHT;
+pub use scarcity::SCARCITY_WINDOW;
+pub use scarcity::Scarcity;
+pub use search::MAX_SEARCH_HOPS;
+pub use search::MAX_SEARCH_QUANTITY;
+pub use search::SEARCH_TIMEOUT;
+pub use search::Search;
+pub use search::SearchBook;
+pub use shoal::SHOAL_CHANCE;
+pub use shoal::SHOAL_GRACE;
+pub use shoal::SHOAL_SLOTS;
+pub use shoal::SHOAL_WINDOW;
+pub use shoal::Shoal;
+pub use shoal::ShoalBook;
+pub use suggest::MAX_SUGGESTIONS;
+pub use suggest::suggest_trades;
+pub use tournament::TOURNAMENT_COUNTDOWN;
+pub use tournament::TOURNAMENT_GRACE;
+pub use tournament::Tournament;
+pub use valuation::LOPSIDED_RATIO;
+pub use valuation::PriceHistory;
+pub use valuation::Valuation;
+pub use wishlist::Wishlist;
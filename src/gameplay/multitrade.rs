@@ -0,0 +1,181 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::ids::IdGenerator;
+use crate::server::{InventoryItem, Peer};
+
+/// Tempo que o coordenador espera as aprovações antes de abortar a multitroca
+pub const MULTI_TRADE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Perna de uma multitroca: quem entrega, quem recebe e o quê. Ex: `alice>bob:atum|1+salmão|2`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeLeg {
+    pub from: String,
+    pub to: String,
+    pub items: Vec<InventoryItem>,
+}
+
+/// Troca entre três ou mais peers, coordenada por quem a propôs em duas fases: cada
+/// participante reserva o que vai entregar e vota, e só com todos aprovando o coordenador
+/// manda efetivar. Qualquer recusa aborta a troca para todos
+#[derive(Debug, Clone)]
+pub struct MultiTrade {
+    pub id: String,
+    pub coordinator: Peer,
+    pub legs: Vec<TradeLeg>,
+    /// Participantes que já aprovaram
+    pub approvals: BTreeSet<String>,
+    /// O que reservamos ao aprovar, vazio enquanto não aprovamos
+    pub reserved: Vec<InventoryItem>,
+    /// Os outros participantes, só conhecidos pelo coordenador
+    pub peers: Vec<Peer>,
+    pub mine: bool,
+}
+
+/// Multitrocas em andamento das quais participamos
+#[derive(Debug, Default)]
+pub struct MultiTradeBook {
+    pub trades: HashMap<String, MultiTrade>,
+    pub ids: IdGenerator,
+}
+
+impl MultiTrade {
+    /// Nomes de todos os participantes
+    pub fn participants(&self) -> BTreeSet<String> {
+        self.legs
+            .iter()
+            .flat_map(|l| [l.from.clone(), l.to.clone()])
+            .collect()
+    }
+
+    /// Tudo o que o participante entrega
+    pub fn gives(&self, username: &str) -> Vec<InventoryItem> {
+        merge_items(self.legs.iter().filter(|l| l.from == username))
+    }
+
+    /// Tudo o que o participante recebe
+    pub fn receives(&self, username: &str) -> Vec<InventoryItem> {
+        merge_items(self.legs.iter().filter(|l| l.to == username))
+    }
+
+    pub fn all_approved(&self) -> bool {
+        self.participants().is_subset(&self.approvals)
+    }
+}
+
+impl MultiTradeBook {
+    /// Quantidade de um item reservada nas multitrocas que aprovamos
+    pub fn reserved(&self, item: &str) -> u32 {
+        self.trades
+            .values()
+            .flat_map(|t| &t.reserved)
+            .filter(|i| i.fish_type == item)
+            .map(|i| i.quantity)
+            .sum()
+    }
+}
+
+/// Junta os itens de várias pernas somando as quantidades do mesmo peixe
+fn merge_items<'a>(legs: impl Iterator<Item = &'a TradeLeg>) -> Vec<InventoryItem> {
+    let mut merged: Vec<InventoryItem> = Vec::new();
+    for item in legs.flat_map(|l| &l.items) {
+        match merged.iter_mut().find(|i| i.fish_type == item.fish_type) {
+            Some(m) => m.quantity += item.quantity,
+            None => merged.push(item.clone()),
+        }
+    }
+    merged
+}
+
+/// Lê as pernas de uma multitroca separadas por vírgula ou espaço
+pub fn parse_legs(s: &str) -> Result<Vec<TradeLeg>, String> {
+    s.split([',', ' '])
+        .map(|leg| leg.trim())
+        .filter(|leg| !leg.is_empty())
+        .map(TradeLeg::from_str)
+        .collect()
+}
+
+impl FromStr for TradeLeg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (route, items) = s
+            .trim()
+            .split_once(':')
+            .ok_or("Invalid trade leg format".to_string())?;
+        let (from, to) = route
+            .split_once('>')
+            .ok_or("Invalid trade leg route".to_string())?;
+        let items: Vec<InventoryItem> = items
+            .split('+')
+            .map(InventoryItem::from_str)
+            .collect::<Result<_, _>>()?;
+        let (from, to) = (
+            from.trim().trim_start_matches('@'),
+            to.trim().trim_start_matches('@'),
+        );
+        if from.is_empty() || to.is_empty() || from == to || items.is_empty() {
+            return Err("Invalid trade leg".to_string());
+        }
+        Ok(TradeLeg {
+            from: from.to_string(),
+            to: to.to_string(),
+            items,
+        })
+    }
+}
+
+impl Display for TradeLeg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items: Vec<String> = self.items.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}>{}:{}", self.from, self.to, items.join("+"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn three_way_trade() {
+        let legs =
+            parse_legs("alice>bob:atum|2, bob>carol:salmão|1 carol>alice:peixe-lua|1+atum|1")
+                .unwrap();
+        assert_eq!(legs.len(), 3);
+        assert_eq!(legs[2].to_string(), "carol>alice:peixe-lua|1+atum|1");
+
+        let mut trade = MultiTrade {
+            id: "alice-1".to_string(),
+            coordinator: Peer::new("alice".to_string(), "127.0.0.1:6000".parse().unwrap()),
+            legs,
+            approvals: BTreeSet::from(["alice".to_string(), "bob".to_string()]),
+            reserved: Vec::new(),
+            peers: Vec::new(),
+            mine: true,
+        };
+        assert_eq!(
+            trade.receives("alice"),
+            vec![
+                InventoryItem::new("peixe-lua".to_string(), 1),
+                InventoryItem::new("atum".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            trade.gives("bob"),
+            vec![InventoryItem::new("salmão".to_string(), 1)]
+        );
+        assert!(!trade.all_approved());
+        trade.approvals.insert("carol".to_string());
+        assert!(trade.all_approved());
+    }
+
+    #[test]
+    fn invalid_legs() {
+        assert!(parse_legs("alice>alice:atum|1").is_err());
+        assert!(parse_legs("alice:atum|1").is_err());
+        assert!(parse_legs("alice>bob:").is_err());
+    }
+}
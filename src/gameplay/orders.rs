@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::ids::IdGenerator;
+use crate::server::{InventoryItem, Peer};
+
+/// Lado de uma ordem: quem quer comprar ou quem quer vender um peixe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Compra,
+    Venda,
+}
+
+/// Ordem permanente de compra ou venda. Fica no livro de ordens de todos os peers até ser
+/// cancelada ou completada
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub id: String,
+    pub owner: Peer,
+    pub side: OrderSide,
+    /// Peixe e quantidade desejada
+    pub item: InventoryItem,
+    /// Preço por unidade: o máximo que se paga numa compra, o mínimo que se aceita numa venda
+    pub price: InventoryItem,
+    pub mine: bool,
+}
+
+/// Livro de ordens conhecido pelo peer
+#[derive(Debug, Default)]
+pub struct OrderBook {
+    pub orders: HashMap<String, Order>,
+    pub ids: IdGenerator,
+}
+
+impl Order {
+    /// Checa se duas ordens podem virar uma troca: lados opostos, mesmo peixe, mesmo item de
+    /// pagamento e o comprador paga pelo menos o que o vendedor pede
+    pub fn matches(&self, other: &Order) -> bool {
+        let (buy, sell) = match (self.side, other.side) {
+            (OrderSide::Compra, OrderSide::Venda) => (self, other),
+            (OrderSide::Venda, OrderSide::Compra) => (other, self),
+            _ => return false,
+        };
+        buy.owner.address() != sell.owner.address()
+            && buy.item.fish_type == sell.item.fish_type
+            && buy.price.fish_type == sell.price.fish_type
+            && buy.price.quantity >= sell.price.quantity
+            && buy.item.quantity > 0
+            && sell.item.quantity > 0
+    }
+}
+
+impl OrderBook {
+    /// Melhor ordem do livro que casa com a ordem dada: o menor preço para quem compra, o
+    /// maior para quem vende
+    pub fn find_match(&self, order: &Order) -> Option<Order> {
+        let candidates = self.orders.values().filter(|o| o.matches(order));
+        match order.side {
+            OrderSide::Compra => candidates.min_by_key(|o| o.price.quantity),
+            OrderSide::Venda => candidates.max_by_key(|o| o.price.quantity),
+        }
+        .cloned()
+    }
+
+    /// Desconta das nossas ordens os peixes trocados: o que demos abate ordens de venda e o
+    /// que recebemos abate ordens de compra. Retorna as ordens alteradas, as zeradas são
+    /// removidas do livro
+    pub fn record_trade(
+        &mut self,
+        given: &[InventoryItem],
+        received: &[InventoryItem],
+    ) -> Vec<Order> {
+        let mut changed = Vec::new();
+        for order in self.orders.values_mut().filter(|o| o.mine) {
+            let items = match order.side {
+                OrderSide::Venda => given,
+                OrderSide::Compra => received,
+            };
+            let traded: u32 = items
+                .iter()
+                .filter(|i| i.fish_type == order.item.fish_type)
+                .map(|i| i.quantity)
+                .sum();
+            if traded > 0 {
+                order.item.quantity = order.item.quantity.saturating_sub(traded);
+                changed.push(order.clone());
+            }
+        }
+        self.orders.retain(|_, o| o.item.quantity > 0);
+        changed
+    }
+}
+
+impl FromStr for OrderSide {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "compra" | "comprar" => Ok(OrderSide::Compra),
+            "venda" | "vender" => Ok(OrderSide::Venda),
+            _ => Err("Invalid order side".to_string()),
+        }
+    }
+}
+
+impl Display for OrderSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderSide::Compra => write!(f, "compra"),
+            OrderSide::Venda => write!(f, "venda"),
+        }
+    }
+}
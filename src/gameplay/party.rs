@@ -0,0 +1,184 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::fisher::FishingOdds;
+use super::ids::IdGenerator;
+
+/// Quantos pescadores cabem num grupo
+pub const PARTY_SIZE: usize = 4;
+/// Bônus, em porcentagem, nas chances de cada raridade acima de comum por companheiro
+pub const PARTY_BONUS: u32 = 10;
+/// Pescas a partir desta raridade (épico) são anunciadas ao grupo
+pub const PARTY_RARE_RANK: u8 = 2;
+
+/// Grupo temporário de pescadores. O líder convida e anuncia a lista de membros, com a
+/// versão, a cada entrada. Saídas e quedas são aplicadas por cada membro do mesmo jeito,
+/// e o líder, que pode ser outro depois da saída, anuncia a lista nova
+#[derive(Debug, Clone, PartialEq)]
+pub struct Party {
+    pub id: String,
+    pub leader: String,
+    pub members: BTreeSet<String>,
+    pub version: u64,
+}
+
+/// Nosso grupo e os convites pendentes
+#[derive(Debug, Default)]
+pub struct PartyBook {
+    pub party: Option<Party>,
+    /// Convites recebidos, pelo nome de quem convidou
+    pub invites: HashMap<String, String>,
+    /// Convites enviados, pelo nome do convidado
+    pub sent: HashMap<String, String>,
+    /// Convite que aceitamos e ainda espera a lista do líder: quem convidou e o id
+    pub accepted: Option<(String, String)>,
+    pub ids: IdGenerator,
+}
+
+impl Party {
+    pub fn new(id: String, leader: String, member: String) -> Self {
+        Self {
+            id,
+            members: BTreeSet::from([leader.clone(), member]),
+            leader,
+            version: 1,
+        }
+    }
+
+    /// Bônus atual em porcentagem, cresce com o tamanho do grupo
+    pub fn bonus(&self) -> u32 {
+        PARTY_BONUS * self.members.len().saturating_sub(1) as u32
+    }
+
+    /// Aumenta as chances das raridades acima de comum
+    pub fn apply_bonus(&self, odds: &mut FishingOdds) {
+        for tier in odds.tiers.iter_mut().skip(1) {
+            *tier = *tier * (100 + self.bonus()) / 100;
+        }
+    }
+
+    /// Os outros membros, para quem as mensagens do grupo são enviadas
+    pub fn others<'a>(&'a self, me: &'a str) -> impl Iterator<Item = &'a String> {
+        self.members.iter().filter(move |m| *m != me)
+    }
+
+    /// Tira um membro do grupo. Se era o líder, o primeiro em ordem alfabética assume,
+    /// então todos os membros chegam ao mesmo líder sem trocar mensagens
+    pub fn remove(&mut self, username: &str) -> bool {
+        if !self.members.remove(username) {
+            return false;
+        }
+        if self.leader == username {
+            self.leader = self.members.first().cloned().unwrap_or_default();
+        }
+        true
+    }
+
+    /// Um grupo de um só é desfeito
+    pub fn is_dissolved(&self) -> bool {
+        self.members.len() < 2
+    }
+}
+
+impl PartyBook {
+    /// Guarda a lista anunciada pelo líder se ela for do nosso grupo e mais nova, ou do
+    /// grupo cujo convite aceitamos. Listas maiores que um grupo são ignoradas. Retorna o
+    /// grupo anterior se houve mudança
+    pub fn update(&mut self, party: Party, sender: &str, me: &str) -> Option<Option<Party>> {
+        if party.members.len() > PARTY_SIZE {
+            return None;
+        }
+        let trusted = match &self.party {
+            Some(current) => {
+                current.id == party.id
+                    && current.leader == sender
+                    && current.version < party.version
+            }
+            None => self
+                .accepted
+                .as_ref()
+                .is_some_and(|(leader, id)| *leader == sender && *id == party.id),
+        };
+        if !trusted {
+            return None;
+        }
+        self.accepted = None;
+        let old = self.party.take();
+        if party.members.contains(me) && !party.is_dissolved() {
+            self.party = Some(party);
+        }
+        Some(old)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_agree_on_new_leader() {
+        let mut party = Party::new(
+            "carol-1".to_string(),
+            "carol".to_string(),
+            "bob".to_string(),
+        );
+        party.members.insert("alice".to_string());
+        let mut copy = party.clone();
+        assert!(party.remove("carol"));
+        assert!(copy.remove("carol"));
+        assert_eq!(party.leader, "alice");
+        assert_eq!(party, copy);
+        assert!(!party.remove("carol"));
+        assert!(party.remove("bob"));
+        assert!(party.is_dissolved());
+    }
+
+    #[test]
+    fn old_or_foreign_states_are_ignored() {
+        let mut book = PartyBook::default();
+        let mut party = Party::new(
+            "alice-1".to_string(),
+            "alice".to_string(),
+            "bob".to_string(),
+        );
+        // Sem convite aceito, ninguém põe a gente num grupo
+        assert_eq!(book.update(party.clone(), "alice", "bob"), None);
+        book.accepted = Some(("alice".to_string(), "alice-1".to_string()));
+        assert_eq!(book.update(party.clone(), "alice", "bob"), Some(None));
+        let other = Party::new(
+            "carol-1".to_string(),
+            "carol".to_string(),
+            "bob".to_string(),
+        );
+        assert_eq!(book.update(other, "carol", "bob"), None);
+        assert_eq!(book.update(party.clone(), "alice", "bob"), None);
+        party.members.insert("carol".to_string());
+        party.version += 1;
+        assert_eq!(book.update(party.clone(), "carol", "bob"), None);
+        assert!(book.update(party.clone(), "alice", "bob").is_some());
+        assert_eq!(book.party.as_ref().unwrap().members.len(), 3);
+        let mut crowded = party.clone();
+        crowded.members.extend(["dave", "eve"].map(String::from));
+        crowded.version += 1;
+        assert_eq!(book.update(crowded, "alice", "bob"), None);
+        // Uma lista nova sem nós desfaz o grupo aqui
+        party.members.remove("bob");
+        party.version += 1;
+        assert!(book.update(party, "alice", "bob").is_some());
+        assert!(book.party.is_none());
+    }
+
+    #[test]
+    fn bonus_grows_with_members() {
+        let mut party = Party::new(
+            "alice-1".to_string(),
+            "alice".to_string(),
+            "bob".to_string(),
+        );
+        let mut odds = FishingOdds::default();
+        party.apply_bonus(&mut odds);
+        assert_eq!(odds.tiers[0], 100);
+        assert_eq!(odds.tiers[1], 55);
+        party.members.insert("carol".to_string());
+        assert_eq!(party.bonus(), 2 * PARTY_BONUS);
+    }
+}
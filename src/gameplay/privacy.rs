@@ -0,0 +1,132 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::fisher::FishCatalog;
+use crate::server::{InventoryItem, Peer};
+
+/// Quem pode ver o nosso inventário
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InventoryAccess {
+    #[default]
+    Publico,
+    /// Só amigos, membros do grupo e da guilda
+    Amigos,
+    /// Amigos veem direto, os outros esperam o usuário permitir
+    Perguntar,
+}
+
+/// O que fazer com um pedido de inventário
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InspectionDecision {
+    Mostrar,
+    Perguntar,
+    /// Recusa, com o motivo enviado ao peer
+    Negar(String),
+}
+
+/// Configurações de privacidade do inventário
+#[derive(Debug, Default)]
+pub struct Privacy {
+    pub access: InventoryAccess,
+    pub friends: BTreeSet<String>,
+    /// Raridades que nunca aparecem para os outros peers
+    pub hidden_tiers: BTreeSet<u8>,
+    /// Pedidos esperando a resposta do usuário, pelo nome de quem pediu
+    pub pending: HashMap<String, Peer>,
+}
+
+impl Privacy {
+    /// Decide um pedido de inventário. `friend` diz se o peer é amigo, do grupo ou da
+    /// guilda
+    pub fn decide(&self, friend: bool) -> InspectionDecision {
+        match self.access {
+            InventoryAccess::Publico => InspectionDecision::Mostrar,
+            _ if friend => InspectionDecision::Mostrar,
+            InventoryAccess::Amigos => {
+                InspectionDecision::Negar("o inventário é só para amigos".to_string())
+            }
+            InventoryAccess::Perguntar => InspectionDecision::Perguntar,
+        }
+    }
+
+    /// Itens que podem ser mostrados, sem as raridades escondidas
+    pub fn visible(&self, catalog: &FishCatalog, items: Vec<InventoryItem>) -> Vec<InventoryItem> {
+        items
+            .into_iter()
+            .filter(|i| {
+                !self
+                    .hidden_tiers
+                    .contains(&catalog.get_rarity_rank(&i.fish_type))
+            })
+            .collect()
+    }
+}
+
+impl InventoryAccess {
+    pub fn description(&self) -> &'static str {
+        match self {
+            InventoryAccess::Publico => "qualquer peer vê o inventário",
+            InventoryAccess::Amigos => "só amigos, o grupo e a guilda veem o inventário",
+            InventoryAccess::Perguntar => "amigos veem direto, os outros esperam a sua permissão",
+        }
+    }
+}
+
+impl Display for InventoryAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InventoryAccess::Publico => write!(f, "público"),
+            InventoryAccess::Amigos => write!(f, "amigos"),
+            InventoryAccess::Perguntar => write!(f, "perguntar"),
+        }
+    }
+}
+
+impl FromStr for InventoryAccess {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "público" | "publico" => Ok(InventoryAccess::Publico),
+            "amigos" => Ok(InventoryAccess::Amigos),
+            "perguntar" => Ok(InventoryAccess::Perguntar),
+            _ => Err(format!("Modo de privacidade desconhecido: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn friends_skip_the_question() {
+        let mut privacy = Privacy::default();
+        assert_eq!(privacy.decide(false), InspectionDecision::Mostrar);
+        privacy.access = InventoryAccess::Amigos;
+        assert!(matches!(
+            privacy.decide(false),
+            InspectionDecision::Negar(_)
+        ));
+        assert_eq!(privacy.decide(true), InspectionDecision::Mostrar);
+        privacy.access = InventoryAccess::Perguntar;
+        assert_eq!(privacy.decide(false), InspectionDecision::Perguntar);
+        assert_eq!(privacy.decide(true), InspectionDecision::Mostrar);
+    }
+
+    #[test]
+    fn hidden_tiers_are_not_shown() {
+        let catalog = FishCatalog::new();
+        let mut privacy = Privacy::default();
+        let items = vec![
+            InventoryItem::new("sardinha".to_string(), 3),
+            InventoryItem::new("salmão".to_string(), 1),
+        ];
+        privacy
+            .hidden_tiers
+            .insert(catalog.get_rarity_rank("sardinha"));
+        let visible = privacy.visible(&catalog, items);
+        assert!(visible.iter().all(|i| i.fish_type != "sardinha"));
+    }
+}
@@ -0,0 +1,247 @@
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use super::fisher::{FishCatalog, RARITY_NAMES};
+
+/// Quantidade de missões sorteadas por dia
+pub const QUESTS_PER_DAY: usize = 3;
+
+/// Objetivo de uma missão diária
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuestGoal {
+    /// Pescar peixes de uma raridade mínima
+    CatchTier { tier: u8, count: u32 },
+    /// Pescar um peixe específico
+    CatchFish { fish: String, count: u32 },
+    /// Trocar (dar ou receber) peixes de uma raridade mínima
+    TradeTier { tier: u8, count: u32 },
+    /// Completar trocas com outros pescadores
+    Trades { count: u32 },
+}
+
+/// Recompensa entregue ao completar uma missão
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reward {
+    /// Peixes aleatórios de uma raridade
+    Fish { tier: u8, quantity: u32 },
+    /// Iscas, que melhoram as próximas pescas
+    Baits(u32),
+}
+
+/// Missão diária e o progresso do usuário nela
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quest {
+    pub goal: QuestGoal,
+    pub reward: Reward,
+    pub progress: u32,
+}
+
+/// Conjunto de missões do dia. Como as missões são geradas a partir da data, todos os
+/// peers veem as mesmas missões sem precisar trocar mensagens
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestBook {
+    pub day: u64,
+    pub quests: Vec<Quest>,
+}
+
+impl QuestGoal {
+    pub fn target(&self) -> u32 {
+        match self {
+            QuestGoal::CatchTier { count, .. }
+            | QuestGoal::CatchFish { count, .. }
+            | QuestGoal::TradeTier { count, .. }
+            | QuestGoal::Trades { count } => *count,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            QuestGoal::CatchTier { tier, count } => format!(
+                "Pesque {} peixe(s) {} ou mais raro(s)",
+                count, RARITY_NAMES[*tier as usize]
+            ),
+            QuestGoal::CatchFish { fish, count } => format!("Pesque {} {}(s)", count, fish),
+            QuestGoal::TradeTier { tier, count } => format!(
+                "Troque {} peixe(s) {} ou mais raro(s)",
+                count, RARITY_NAMES[*tier as usize]
+            ),
+            QuestGoal::Trades { count } => format!("Complete {} troca(s)", count),
+        }
+    }
+}
+
+impl Reward {
+    pub fn description(&self) -> String {
+        match self {
+            Reward::Fish { tier, quantity } => {
+                format!("{} peixe(s) {}", quantity, RARITY_NAMES[*tier as usize])
+            }
+            Reward::Baits(n) => format!("{} isca(s)", n),
+        }
+    }
+}
+
+impl Quest {
+    pub fn is_done(&self) -> bool {
+        self.progress >= self.goal.target()
+    }
+
+    /// Avança o progresso da missão, retorna true se ela acabou de ser completada
+    fn advance(&mut self, amount: u32) -> bool {
+        if self.is_done() || amount == 0 {
+            return false;
+        }
+        self.progress = self.progress.saturating_add(amount).min(self.goal.target());
+        self.is_done()
+    }
+}
+
+impl QuestBook {
+    /// Gera as missões de um dia. A mesma data sempre gera as mesmas missões
+    pub fn for_day(day: u64, catalog: &FishCatalog) -> Self {
+        let mut rng = StdRng::seed_from_u64(day);
+        let mut quests = Vec::with_capacity(QUESTS_PER_DAY);
+
+        // Uma missão de pesca por raridade
+        let tier = rng.random_range(1..=3);
+        quests.push(Quest {
+            goal: QuestGoal::CatchTier {
+                tier,
+                count: [0, 5, 3, 1][tier as usize],
+            },
+            reward: Reward::Baits(tier as u32),
+            progress: 0,
+        });
+
+        // Uma missão de pesca de um peixe específico, comum ou raro
+        let fish_tier = rng.random_range(0..=1);
+        let fish = catalog
+            .get_fish_by_rank(fish_tier)
+            .choose(&mut rng)
+            .cloned()
+            .unwrap_or_default();
+        quests.push(Quest {
+            goal: QuestGoal::CatchFish {
+                fish,
+                count: 2 - fish_tier as u32,
+            },
+            reward: Reward::Fish {
+                tier: fish_tier + 1,
+                quantity: 1,
+            },
+            progress: 0,
+        });
+
+        // Uma missão de troca
+        let goal = if rng.random_bool(0.5) {
+            QuestGoal::Trades {
+                count: rng.random_range(1..=2),
+            }
+        } else {
+            QuestGoal::TradeTier {
+                tier: rng.random_range(2..=3),
+                count: 1,
+            }
+        };
+        quests.push(Quest {
+            goal,
+            reward: Reward::Fish {
+                tier: 2,
+                quantity: 1,
+            },
+            progress: 0,
+        });
+
+        Self { day, quests }
+    }
+
+    /// Troca as missões se o dia mudou
+    pub fn refresh(&mut self, day: u64, catalog: &FishCatalog) {
+        if self.day != day {
+            *self = Self::for_day(day, catalog);
+        }
+    }
+
+    /// Registra um peixe pescado, retorna as recompensas das missões completadas
+    pub fn record_catch(&mut self, fish: &str, rank: u8) -> Vec<Reward> {
+        self.quests
+            .iter_mut()
+            .filter_map(|q| {
+                let amount = match &q.goal {
+                    QuestGoal::CatchTier { tier, .. } if rank >= *tier => 1,
+                    QuestGoal::CatchFish { fish: f, .. } if f == fish => 1,
+                    _ => 0,
+                };
+                q.advance(amount).then(|| q.reward.clone())
+            })
+            .collect()
+    }
+
+    /// Registra uma troca concluída a partir dos ranks e quantidades dos peixes trocados,
+    /// retorna as recompensas das missões completadas
+    pub fn record_trade(&mut self, traded: &[(u8, u32)]) -> Vec<Reward> {
+        self.quests
+            .iter_mut()
+            .filter_map(|q| {
+                let amount = match &q.goal {
+                    QuestGoal::Trades { .. } => 1,
+                    QuestGoal::TradeTier { tier, .. } => traded
+                        .iter()
+                        .filter(|(rank, _)| rank >= tier)
+                        .map(|(_, quantity)| *quantity)
+                        .fold(0, u32::saturating_add),
+                    _ => 0,
+                };
+                q.advance(amount).then(|| q.reward.clone())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_day_same_quests() {
+        let catalog = FishCatalog::new();
+        assert_eq!(
+            QuestBook::for_day(20000, &catalog),
+            QuestBook::for_day(20000, &catalog)
+        );
+        assert_eq!(
+            QuestBook::for_day(20000, &catalog).quests.len(),
+            QUESTS_PER_DAY
+        );
+    }
+
+    #[test]
+    fn quest_completes_once() {
+        let mut book = QuestBook {
+            day: 0,
+            quests: vec![Quest {
+                goal: QuestGoal::CatchTier { tier: 2, count: 2 },
+                reward: Reward::Baits(1),
+                progress: 0,
+            }],
+        };
+        assert!(book.record_catch("atum", 0).is_empty());
+        assert!(book.record_catch("carpa", 2).is_empty());
+        assert_eq!(book.record_catch("carpa", 2), vec![Reward::Baits(1)]);
+        assert!(book.record_catch("carpa", 2).is_empty());
+    }
+
+    #[test]
+    fn huge_trades_do_not_overflow() {
+        let mut book = QuestBook {
+            day: 0,
+            quests: vec![Quest {
+                goal: QuestGoal::TradeTier { tier: 1, count: 5 },
+                reward: Reward::Baits(1),
+                progress: 4,
+            }],
+        };
+        let traded = [(1, u32::MAX), (2, u32::MAX)];
+        assert_eq!(book.record_trade(&traded), vec![Reward::Baits(1)]);
+        assert_eq!(book.quests[0].progress, 5);
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use super::fisher::{FishCatalog, RARITY_NAMES};
+use super::inventory::AQUARIUM_CAPACITY;
+
+/// Se a receita é feita na bancada ou na cozinha
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeKind {
+    /// Itens feitos com peixes repetidos (`$craft`)
+    Craft,
+    /// Pratos que dão bônus temporários de pesca ao serem comidos (`$cozinhar`)
+    Cook,
+}
+
+/// O que uma receita produz
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Product {
+    /// Iscas comuns, iguais às das missões
+    Baits(u32),
+    /// Iscas raras: a próxima pesca nunca vem abaixo de raro
+    RareBaits(u32),
+    /// Chance, em porcentagem, de ganhar um peixe da raridade dada
+    FishChance { rank: u8, percent: u32 },
+    /// Lançamentos extras em cada pesca durante um tempo, fica o peixe mais raro
+    Bonus { rolls: u32, duration: Duration },
+    /// Aquários, que aumentam a capacidade da cesta
+    Aquariums(u32),
+}
+
+/// Receita: `quantity` unidades de um mesmo peixe da raridade `rank` viram o produto
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recipe {
+    pub name: &'static str,
+    pub kind: RecipeKind,
+    pub rank: u8,
+    pub quantity: u32,
+    pub product: Product,
+}
+
+/// Todas as receitas conhecidas
+pub const RECIPES: [Recipe; 7] = [
+    Recipe {
+        name: "isca",
+        kind: RecipeKind::Craft,
+        rank: 0,
+        quantity: 5,
+        product: Product::Baits(1),
+    },
+    Recipe {
+        name: "isca-rara",
+        kind: RecipeKind::Craft,
+        rank: 0,
+        quantity: 10,
+        product: Product::RareBaits(1),
+    },
+    Recipe {
+        name: "amuleto-shiny",
+        kind: RecipeKind::Craft,
+        rank: 2,
+        quantity: 3,
+        product: Product::FishChance {
+            rank: 3,
+            percent: 40,
+        },
+    },
+    Recipe {
+        name: "aquario",
+        kind: RecipeKind::Craft,
+        rank: 1,
+        quantity: 8,
+        product: Product::Aquariums(1),
+    },
+    Recipe {
+        name: "ensopado",
+        kind: RecipeKind::Cook,
+        rank: 0,
+        quantity: 4,
+        product: Product::Bonus {
+            rolls: 1,
+            duration: Duration::from_secs(5 * 60),
+        },
+    },
+    Recipe {
+        name: "moqueca",
+        kind: RecipeKind::Cook,
+        rank: 1,
+        quantity: 3,
+        product: Product::Bonus {
+            rolls: 2,
+            duration: Duration::from_secs(5 * 60),
+        },
+    },
+    Recipe {
+        name: "sashimi",
+        kind: RecipeKind::Cook,
+        rank: 2,
+        quantity: 2,
+        product: Product::Bonus {
+            rolls: 1,
+            duration: Duration::from_secs(20 * 60),
+        },
+    },
+];
+
+/// Bônus de um prato comido, válido até `until` (segundos desde a época)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveBonus {
+    pub dish: String,
+    pub rolls: u32,
+    pub until: u64,
+}
+
+/// Consumíveis feitos com receitas que ainda não foram usados
+#[derive(Debug, Default)]
+pub struct Consumables {
+    pub rare_baits: u32,
+    pub bonus: Option<ActiveBonus>,
+}
+
+impl Recipe {
+    /// Procura uma receita do tipo dado pelo nome
+    pub fn find(kind: RecipeKind, name: &str) -> Option<&'static Recipe> {
+        RECIPES
+            .iter()
+            .find(|r| r.kind == kind && r.name == name.to_lowercase())
+    }
+
+    /// Checa se o peixe serve de ingrediente para a receita
+    pub fn accepts(&self, catalog: &FishCatalog, fish: &str) -> bool {
+        catalog.contains(fish) && catalog.get_rarity_rank(fish) == self.rank
+    }
+
+    pub fn ingredients(&self) -> String {
+        format!(
+            "{} unidades de um mesmo peixe {}",
+            self.quantity, RARITY_NAMES[self.rank as usize]
+        )
+    }
+}
+
+impl Product {
+    pub fn description(&self) -> String {
+        match self {
+            Product::Baits(n) => format!("{} isca(s)", n),
+            Product::RareBaits(n) => format!("{} isca(s) rara(s), pesca no mínimo um raro", n),
+            Product::FishChance { rank, percent } => format!(
+                "{}% de chance de um peixe {}",
+                percent, RARITY_NAMES[*rank as usize]
+            ),
+            Product::Bonus { rolls, duration } => format!(
+                "+{} lançamento(s) por pesca durante {} min",
+                rolls,
+                duration.as_secs() / 60
+            ),
+            Product::Aquariums(n) => {
+                format!("{} aquário(s), +{} de espaço cada", n, AQUARIUM_CAPACITY)
+            }
+        }
+    }
+}
+
+impl Consumables {
+    /// Lançamentos extras do prato ativo, descartando o bônus se já expirou
+    pub fn bonus_rolls(&mut self, now: u64) -> u32 {
+        match &self.bonus {
+            Some(bonus) if bonus.until > now => bonus.rolls,
+            Some(_) => {
+                self.bonus = None;
+                0
+            }
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipes_by_kind() {
+        let catalog = FishCatalog::new();
+        let recipe = Recipe::find(RecipeKind::Craft, "Isca-Rara").unwrap();
+        assert_eq!(recipe.product, Product::RareBaits(1));
+        assert!(recipe.accepts(&catalog, "sardinha"));
+        assert!(!recipe.accepts(&catalog, "salmão"));
+        // Pratos só são feitos na cozinha
+        assert!(Recipe::find(RecipeKind::Craft, "moqueca").is_none());
+        assert!(Recipe::find(RecipeKind::Cook, "moqueca").is_some());
+    }
+
+    #[test]
+    fn bonus_expires() {
+        let mut consumables = Consumables {
+            rare_baits: 0,
+            bonus: Some(ActiveBonus {
+                dish: "moqueca".to_string(),
+                rolls: 2,
+                until: 1000,
+            }),
+        };
+        assert_eq!(consumables.bonus_rolls(999), 2);
+        assert_eq!(consumables.bonus_rolls(1000), 0);
+        assert!(consumables.bonus.is_none());
+    }
+}
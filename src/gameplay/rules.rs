@@ -0,0 +1,178 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::fisher::FishCatalog;
+use super::market::is_coin;
+use crate::server::{InventoryItem, protocol::Offer};
+
+/// Quantas decisões automáticas guardamos no registro
+pub const RULE_LOG_SIZE: usize = 20;
+
+/// Rank da raridade abissal
+const ABYSSAL_RANK: u8 = 6;
+
+/// Regras para responder ofertas de troca sem esperar o usuário
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TradeRule {
+    /// Recusa ofertas de peers silenciados
+    RecusarSilenciados,
+    /// Recusa ofertas que pedem peixes abissais
+    RecusarAbissal,
+    /// Aceita ofertas em que todo peixe dado é de raridade maior que os pedidos
+    AceitarMelhor,
+}
+
+/// Todas as regras, na ordem em que são avaliadas
+pub const TRADE_RULES: [TradeRule; 3] = [
+    TradeRule::RecusarSilenciados,
+    TradeRule::RecusarAbissal,
+    TradeRule::AceitarMelhor,
+];
+
+/// Decisão tomada por uma regra sobre uma oferta recebida
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleDecision {
+    pub time: u64,
+    pub peer: String,
+    pub offer: Offer,
+    pub rule: TradeRule,
+    pub accepted: bool,
+}
+
+/// Regras ativas, peers silenciados e o registro das últimas decisões
+#[derive(Debug)]
+pub struct TradeRules {
+    pub enabled: BTreeSet<TradeRule>,
+    pub muted: BTreeSet<String>,
+    pub log: VecDeque<RuleDecision>,
+}
+
+impl TradeRule {
+    /// Se a regra aceita ou recusa as ofertas em que se aplica
+    pub fn accepts(&self) -> bool {
+        matches!(self, TradeRule::AceitarMelhor)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TradeRule::RecusarSilenciados => "recusa ofertas de peers silenciados",
+            TradeRule::RecusarAbissal => "recusa ofertas que pedem peixes abissais",
+            TradeRule::AceitarMelhor => {
+                "aceita ofertas que dão peixes mais raros do que os pedidos"
+            }
+        }
+    }
+}
+
+impl TradeRules {
+    /// Primeira regra ativa que se aplica à oferta. As recusas têm prioridade sobre o aceite
+    pub fn evaluate(&self, catalog: &FishCatalog, peer: &str, offer: &Offer) -> Option<TradeRule> {
+        let ranks = |items: &[InventoryItem]| -> Option<Vec<u8>> {
+            items
+                .iter()
+                .map(|i| (!is_coin(&i.fish_type)).then(|| catalog.get_rarity_rank(&i.fish_type)))
+                .collect()
+        };
+        let applies = |rule: &TradeRule| match rule {
+            TradeRule::RecusarSilenciados => self.muted.contains(peer),
+            TradeRule::RecusarAbissal => offer.requested.iter().any(|i| {
+                !is_coin(&i.fish_type) && catalog.get_rarity_rank(&i.fish_type) == ABYSSAL_RANK
+            }),
+            // Ofertas com moedas não têm raridade para comparar
+            TradeRule::AceitarMelhor => match (ranks(&offer.offered), ranks(&offer.requested)) {
+                (Some(offered), Some(requested)) => {
+                    !offered.is_empty() && offered.iter().min() > requested.iter().max()
+                }
+                _ => false,
+            },
+        };
+        TRADE_RULES
+            .into_iter()
+            .filter(|rule| self.enabled.contains(rule))
+            .find(applies)
+    }
+
+    /// Guarda uma decisão no registro, descartando as mais antigas
+    pub fn record(&mut self, decision: RuleDecision) {
+        if self.log.len() == RULE_LOG_SIZE {
+            self.log.pop_front();
+        }
+        self.log.push_back(decision);
+    }
+}
+
+impl Default for TradeRules {
+    fn default() -> Self {
+        Self {
+            enabled: BTreeSet::from([TradeRule::RecusarSilenciados]),
+            muted: BTreeSet::new(),
+            log: VecDeque::new(),
+        }
+    }
+}
+
+impl FromStr for TradeRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "recusar-silenciados" => Ok(TradeRule::RecusarSilenciados),
+            "recusar-abissal" | "recusar-abissais" => Ok(TradeRule::RecusarAbissal),
+            "aceitar-melhor" => Ok(TradeRule::AceitarMelhor),
+            _ => Err("Invalid trade rule".to_string()),
+        }
+    }
+}
+
+impl Display for TradeRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeRule::RecusarSilenciados => write!(f, "recusar-silenciados"),
+            TradeRule::RecusarAbissal => write!(f, "recusar-abissal"),
+            TradeRule::AceitarMelhor => write!(f, "aceitar-melhor"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declines_take_priority_over_accept() {
+        let catalog = FishCatalog::new();
+        let mut rules = TradeRules::default();
+        rules.enabled.insert(TradeRule::AceitarMelhor);
+        let offer = Offer::from_str("peixe-lua|1 > sardinha|2").unwrap();
+        assert_eq!(
+            rules.evaluate(&catalog, "bob", &offer),
+            Some(TradeRule::AceitarMelhor)
+        );
+
+        rules.muted.insert("bob".to_string());
+        assert_eq!(
+            rules.evaluate(&catalog, "bob", &offer),
+            Some(TradeRule::RecusarSilenciados)
+        );
+    }
+
+    #[test]
+    fn only_enabled_rules_apply() {
+        let catalog = FishCatalog::new();
+        let mut rules = TradeRules::default();
+        let offer = Offer::from_str("sardinha|1 > peixe-bolha|1").unwrap();
+        assert_eq!(rules.evaluate(&catalog, "bob", &offer), None);
+
+        rules.enabled.insert(TradeRule::RecusarAbissal);
+        assert_eq!(
+            rules.evaluate(&catalog, "bob", &offer),
+            Some(TradeRule::RecusarAbissal)
+        );
+
+        // Moedas não contam como peixe mais raro
+        rules.enabled.insert(TradeRule::AceitarMelhor);
+        let offer = Offer::from_str("moedas|500 > sardinha|1").unwrap();
+        assert_eq!(rules.evaluate(&catalog, "bob", &offer), None);
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use super::fisher::FishCatalog;
+
+/// Por quanto tempo as pescas contam para a escassez. Relatos mais velhos são descartados
+pub const SCARCITY_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Peso mínimo, em porcentagem, de uma espécie pescada demais
+pub const MIN_SCARCITY_WEIGHT: u32 = 25;
+/// Limite de pescas de uma espécie aceito no relato de um peer, uma a cada 10s da janela.
+/// Relatos maiores são cortados, então um peer sozinho não derruba o peso de uma espécie
+/// além do que uma pesca honesta conseguiria
+pub const MAX_REPORTED_CATCHES: u32 = 60;
+
+/// Contagem de pescas por espécie, nossa e a relatada por cada peer. Quando ligada, as
+/// espécies pescadas acima da média da sua raridade ficam mais difíceis de pescar
+#[derive(Debug, Default)]
+pub struct Scarcity {
+    pub enabled: bool,
+    /// Nossas pescas na janela atual, que começou em `window_start`
+    pub mine: BTreeMap<String, u32>,
+    window_start: u64,
+    /// Última contagem relatada por cada peer e quando ela chegou
+    others: HashMap<SocketAddr, (u64, BTreeMap<String, u32>)>,
+}
+
+impl Scarcity {
+    /// Começa uma nova janela e descarta relatos velhos, se for a hora
+    pub fn refresh(&mut self, now: u64) {
+        let window = SCARCITY_WINDOW.as_secs();
+        if now >= self.window_start + window {
+            self.mine.clear();
+            self.window_start = now;
+        }
+        self.others.retain(|_, (time, _)| now < *time + window);
+    }
+
+    /// Conta uma pesca nossa
+    pub fn record_catch(&mut self, now: u64, fish: &str) {
+        self.refresh(now);
+        *self.mine.entry(fish.to_string()).or_insert(0) += 1;
+    }
+
+    /// Substitui a contagem relatada por um peer
+    pub fn record_report(&mut self, now: u64, peer: SocketAddr, counts: BTreeMap<String, u32>) {
+        if counts.is_empty() {
+            self.others.remove(&peer);
+        } else {
+            self.others.insert(peer, (now, counts));
+        }
+    }
+
+    pub fn remove_peer(&mut self, peer: &SocketAddr) {
+        self.others.remove(peer);
+    }
+
+    /// Pescas de cada espécie na rede toda
+    pub fn totals(&self) -> BTreeMap<String, u32> {
+        let mut totals = self.mine.clone();
+        for (_, counts) in self.others.values() {
+            for (fish, count) in counts {
+                let total = totals.entry(fish.clone()).or_insert(0);
+                *total = total.saturating_add(*count);
+            }
+        }
+        totals
+    }
+
+    /// Peso de cada espécie, em porcentagem, dentro da sua raridade. Espécies pescadas mais
+    /// que a média da raridade têm o peso reduzido na proporção `(média + 1) / (pescas + 1)`,
+    /// o que suaviza o efeito das primeiras pescas
+    pub fn weights(&self, catalog: &FishCatalog) -> BTreeMap<String, u32> {
+        let totals = self.totals();
+        let mut weights = BTreeMap::new();
+        for (fish, count) in &totals {
+            let count = *count as u64;
+            let tier = catalog.get_fish_by_rank(catalog.get_rarity_rank(fish));
+            let tier_total: u64 = tier
+                .iter()
+                .filter_map(|f| totals.get(f))
+                .map(|c| *c as u64)
+                .sum();
+            // Média por espécie da raridade, em centésimos para não perder precisão
+            let average = tier_total * 100 / tier.len().max(1) as u64;
+            if count * 100 > average {
+                let weight = ((average + 100) / (count + 1)).max(MIN_SCARCITY_WEIGHT as u64);
+                weights.insert(fish.clone(), weight as u32);
+            }
+        }
+        weights
+    }
+
+    /// Peso de uma espécie, 100 se ela não está escassa ou se o modo está desligado
+    pub fn weight(&self, catalog: &FishCatalog, fish: &str) -> u32 {
+        if !self.enabled {
+            return 100;
+        }
+        self.weights(catalog).get(fish).copied().unwrap_or(100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn over_caught_species_get_scarcer() {
+        let catalog = FishCatalog::new();
+        let mut scarcity = Scarcity {
+            enabled: true,
+            ..Default::default()
+        };
+        scarcity.record_catch(0, "sardinha");
+        let peer = "127.0.0.1:6001".parse().unwrap();
+        scarcity.record_report(0, peer, BTreeMap::from([("sardinha".to_string(), 7)]));
+        scarcity.record_report(0, peer, BTreeMap::from([("sardinha".to_string(), 3)]));
+
+        // 4 sardinhas entre 16 comuns: média 0.25, peso 1.25 / 5
+        assert_eq!(scarcity.totals().get("sardinha"), Some(&4));
+        assert_eq!(scarcity.weight(&catalog, "sardinha"), 25);
+        scarcity.record_catch(0, "atum");
+        assert_eq!(scarcity.weight(&catalog, "atum"), 65);
+        for _ in 0..10 {
+            scarcity.record_catch(0, "sardinha");
+        }
+        assert_eq!(scarcity.weight(&catalog, "sardinha"), MIN_SCARCITY_WEIGHT);
+        assert_eq!(scarcity.weight(&catalog, "robalo"), 100);
+
+        scarcity.enabled = false;
+        assert_eq!(scarcity.weight(&catalog, "sardinha"), 100);
+    }
+
+    #[test]
+    fn huge_reports_do_not_overflow() {
+        let catalog = FishCatalog::new();
+        let mut scarcity = Scarcity {
+            enabled: true,
+            ..Default::default()
+        };
+        for port in [6001, 6002] {
+            let peer = SocketAddr::from(([127, 0, 0, 1], port));
+            scarcity.record_report(0, peer, BTreeMap::from([("atum".to_string(), u32::MAX)]));
+        }
+        assert_eq!(scarcity.totals().get("atum"), Some(&u32::MAX));
+        assert_eq!(scarcity.weight(&catalog, "atum"), MIN_SCARCITY_WEIGHT);
+    }
+
+    #[test]
+    fn old_reports_expire() {
+        let mut scarcity = Scarcity::default();
+        let peer = "127.0.0.1:6001".parse().unwrap();
+        scarcity.record_catch(0, "atum");
+        scarcity.record_report(0, peer, BTreeMap::from([("atum".to_string(), 2)]));
+        scarcity.refresh(SCARCITY_WINDOW.as_secs());
+        assert!(scarcity.totals().is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::ids::IdGenerator;
+use crate::server::Peer;
+
+/// Tempo que esperamos as respostas de uma busca antes de mostrar o resultado
+pub const SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Máximo de vezes que uma busca pode ser repassada adiante
+pub const MAX_SEARCH_HOPS: u8 = 3;
+
+/// Maior quantidade aceita numa resposta de busca, respostas acima são cortadas
+pub const MAX_SEARCH_QUANTITY: u32 = 10_000;
+
+/// Busca de um peixe pela rede, nossa ou repassada por nós
+#[derive(Debug, Clone)]
+pub struct Search {
+    pub fish: String,
+    /// Peer de quem recebemos a busca, as respostas voltam pelo mesmo caminho.
+    /// `None` quando a busca é nossa
+    pub back: Option<Peer>,
+    /// Quem tem o peixe e quantos, só preenchido nas nossas buscas
+    pub results: HashMap<String, u32>,
+}
+
+/// Buscas em andamento conhecidas pelo peer
+#[derive(Debug, Default)]
+pub struct SearchBook {
+    pub searches: HashMap<String, Search>,
+    pub ids: IdGenerator,
+}
+
+impl Search {
+    pub fn is_mine(&self) -> bool {
+        self.back.is_none()
+    }
+}
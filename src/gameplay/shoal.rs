@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use super::ids::IdGenerator;
+
+/// Chance, em porcentagem, de uma pesca avistar um cardume
+pub const SHOAL_CHANCE: u32 = 3;
+/// Quanto tempo o cardume fica aberto para respostas
+pub const SHOAL_WINDOW: Duration = Duration::from_secs(60);
+/// Quantos peers ganham o peixe extra
+pub const SHOAL_SLOTS: u32 = 3;
+/// Segundos que cada peer espera após o fim para receber as últimas respostas
+pub const SHOAL_GRACE: u64 = 2;
+
+/// Cardume avistado por um peer. Os primeiros `slots` peers a pescar dentro da janela
+/// ganham um peixe extra. Quem avistou é o juiz: ele marca cada resposta com o próprio
+/// relógio quando ela chega e, no fim, anuncia os vencedores. Os horários informados
+/// pelos outros peers nunca são usados, então ninguém adianta a própria resposta.
+/// Continua valendo confiar em quem avistou, que poderia escolher os vencedores, e a
+/// latência até ele, que favorece os peers mais próximos
+#[derive(Debug, Clone)]
+pub struct Shoal {
+    pub id: String,
+    pub spawner: String,
+    pub fish: String,
+    pub slots: u32,
+    /// Início e fim da janela, em segundos desde a época UNIX
+    pub start: u64,
+    pub end: u64,
+    /// Horário de cada resposta em milissegundos, pelo nome do pescador. Só é preenchido
+    /// em quem avistou, nos outros peers guarda apenas a nossa resposta
+    pub claims: BTreeMap<String, u64>,
+}
+
+/// Cardumes conhecidos pelo peer
+#[derive(Debug, Default)]
+pub struct ShoalBook {
+    pub shoals: HashMap<String, Shoal>,
+    pub ids: IdGenerator,
+}
+
+impl Shoal {
+    /// Checa se um cardume anunciado pela rede segue as regras: janela de `SHOAL_WINDOW`,
+    /// `SHOAL_SLOTS` vagas e início perto do instante atual, em segundos
+    pub fn is_valid(&self, now: u64) -> bool {
+        let window = SHOAL_WINDOW.as_secs();
+        self.end.checked_sub(self.start) == Some(window)
+            && self.slots == SHOAL_SLOTS
+            && self.start.abs_diff(now) <= window
+    }
+
+    /// Checa se um instante, em milissegundos, está dentro da janela
+    pub fn is_open(&self, now_ms: u64) -> bool {
+        now_ms >= self.start.saturating_mul(1000) && now_ms < self.end.saturating_mul(1000)
+    }
+
+    /// Registra uma resposta no horário `at` de quem avistou. Respostas fora da janela
+    /// são ignoradas e cada pescador fica com a mais cedo, então mensagens repetidas não
+    /// mudam a ordem
+    pub fn record_claim(&mut self, username: &str, at: u64) -> bool {
+        if !self.is_open(at) {
+            return false;
+        }
+        let entry = self.claims.entry(username.to_string()).or_insert(at);
+        *entry = (*entry).min(at);
+        true
+    }
+
+    /// Respostas em ordem de chegada. Empates no mesmo milissegundo são desfeitos por um
+    /// hash do cardume e do nome, para que nenhum nome leve vantagem sempre
+    pub fn ranking(&self) -> Vec<(String, u64)> {
+        let mut ranking: Vec<(String, u64)> =
+            self.claims.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        ranking.sort_by_key(|(name, at)| (*at, tiebreak(&self.id, name)));
+        ranking
+    }
+
+    pub fn winners(&self) -> Vec<String> {
+        self.ranking()
+            .into_iter()
+            .take(self.slots as usize)
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+impl ShoalBook {
+    /// Cardumes abertos no instante dado, em milissegundos
+    pub fn open(&self, now_ms: u64) -> impl Iterator<Item = &Shoal> {
+        self.shoals.values().filter(move |s| s.is_open(now_ms))
+    }
+
+    /// Guarda um cardume anunciado. Retorna falso se ele já era conhecido. Cardumes cujo
+    /// resultado nunca chegou, porque quem avistou saiu da rede, são descartados aqui
+    pub fn insert(&mut self, shoal: Shoal, now_ms: u64) -> bool {
+        let window = SHOAL_WINDOW.as_millis() as u64;
+        self.shoals
+            .retain(|_, s| s.end.saturating_mul(1000) + window > now_ms);
+        if self.shoals.contains_key(&shoal.id) {
+            return false;
+        }
+        self.shoals.insert(shoal.id.clone(), shoal);
+        true
+    }
+
+    /// Registra, no nosso relógio, a resposta de um peer a um cardume que avistamos
+    pub fn record_claim(&mut self, id: &str, me: &str, username: &str, now_ms: u64) -> bool {
+        self.shoals
+            .get_mut(id)
+            .filter(|s| s.spawner == me)
+            .is_some_and(|s| s.record_claim(username, now_ms))
+    }
+
+    /// Encerra um cardume. Quem não avistou só encerra com o resultado de quem avistou
+    pub fn finish(&mut self, id: &str, spawner: &str) -> Option<Shoal> {
+        if self.shoals.get(id)?.spawner != spawner {
+            return None;
+        }
+        self.shoals.remove(id)
+    }
+}
+
+/// Hash FNV-1a, igual em todos os peers
+fn tiebreak(id: &str, username: &str) -> u64 {
+    id.bytes()
+        .chain(std::iter::once(b'|'))
+        .chain(username.bytes())
+        .fold(0xcbf29ce484222325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shoal() -> Shoal {
+        Shoal {
+            id: "alice-1".to_string(),
+            spawner: "alice".to_string(),
+            fish: "salmão".to_string(),
+            slots: 2,
+            start: 100,
+            end: 160,
+            claims: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn first_claims_win() {
+        let mut shoal = shoal();
+        assert!(shoal.record_claim("carol", 100_500));
+        assert!(shoal.record_claim("bob", 100_200));
+        assert!(shoal.record_claim("alice", 101_000));
+        // Respostas repetidas ou fora da janela não mudam nada
+        assert!(shoal.record_claim("carol", 150_000));
+        assert!(!shoal.record_claim("dave", 160_000));
+        assert!(!shoal.record_claim("dave", 99_999));
+        assert_eq!(shoal.winners(), vec!["bob", "carol"]);
+    }
+
+    #[test]
+    fn ties_are_ordered_the_same_everywhere() {
+        let mut a = shoal();
+        let mut b = shoal();
+        for name in ["bob", "carol", "dave"] {
+            a.record_claim(name, 120_000);
+        }
+        for name in ["dave", "carol", "bob"] {
+            b.record_claim(name, 120_000);
+        }
+        assert_eq!(a.winners(), b.winners());
+    }
+
+    #[test]
+    fn only_the_spawner_judges() {
+        let mut book = ShoalBook::default();
+        assert!(book.insert(shoal(), 100_000));
+        assert!(!book.insert(shoal(), 100_000));
+        // Só quem avistou registra respostas e encerra o cardume
+        assert!(!book.record_claim("alice-1", "bob", "carol", 110_000));
+        assert!(book.record_claim("alice-1", "alice", "carol", 110_000));
+        assert!(!book.record_claim("alice-1", "alice", "bob", 170_000));
+        assert!(book.finish("alice-1", "bob").is_none());
+        let shoal = book.finish("alice-1", "alice").unwrap();
+        assert_eq!(shoal.winners(), vec!["carol"]);
+    }
+
+    #[test]
+    fn announced_shoals_follow_the_rules() {
+        let mut shoal = shoal();
+        shoal.slots = SHOAL_SLOTS;
+        assert!(shoal.is_valid(100));
+        assert!(!shoal.is_valid(100 + 10 * 60));
+        shoal.end = u64::MAX;
+        assert!(!shoal.is_valid(100));
+        assert!(!shoal.is_open(u64::MAX));
+        shoal.end = 160;
+        shoal.slots = 50;
+        assert!(!shoal.is_valid(100));
+    }
+
+    #[test]
+    fn results_never_arriving_are_dropped() {
+        let mut book = ShoalBook::default();
+        assert!(book.insert(shoal(), 100_000));
+        let mut next = shoal();
+        next.id = "bob-1".to_string();
+        assert!(book.insert(next, 160_000 + SHOAL_WINDOW.as_millis() as u64));
+        assert!(!book.shoals.contains_key("alice-1"));
+    }
+}
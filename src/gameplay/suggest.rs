@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use super::fisher::FishCatalog;
+use super::market::{COIN_ITEM, sell_price};
+use crate::server::{InventoryItem, protocol::Offer};
+
+/// Máximo de sugestões mostradas por comparação
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// Sugere trocas que ajudam os dois lados a completar a coleção: cada peer entrega peixes
+/// repetidos que o outro ainda não tem, sempre guardando um exemplar para si.
+/// `mine` são as quantidades que temos livres, `theirs` o inventário mostrado pelo peer e
+/// `coins` as moedas livres, usadas quando não sobra peixe repetido para dar em troca
+pub fn suggest_trades(
+    catalog: &FishCatalog,
+    mine: &HashMap<String, u32>,
+    theirs: &[InventoryItem],
+    coins: u32,
+) -> Vec<Offer> {
+    let has_theirs = |fish: &str| theirs.iter().any(|i| i.fish_type == fish && i.quantity > 0);
+
+    // Repetidos do peer que ainda não temos, dos mais raros para os mais comuns
+    let mut wanted: Vec<&InventoryItem> = theirs
+        .iter()
+        .filter(|i| i.quantity >= 2 && mine.get(&i.fish_type).copied().unwrap_or(0) == 0)
+        .collect();
+    wanted.sort_by_key(|i| std::cmp::Reverse(catalog.get_rarity_rank(&i.fish_type)));
+
+    // Nossos repetidos que o peer ainda não tem, com quanto podemos abrir mão
+    let mut spare: Vec<(String, u32)> = mine
+        .iter()
+        .filter(|(fish, n)| **n >= 2 && !has_theirs(fish))
+        .map(|(fish, n)| (fish.clone(), n - 1))
+        .collect();
+    spare.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut coins = coins;
+    let mut suggestions = Vec::new();
+    for item in wanted {
+        if suggestions.len() >= MAX_SUGGESTIONS {
+            break;
+        }
+        let points = catalog.get_rarity_points(&item.fish_type);
+        // O repetido com o valor mais próximo do peixe que queremos
+        let best = spare
+            .iter_mut()
+            .filter(|(_, n)| *n > 0)
+            .min_by_key(|(fish, _)| catalog.get_rarity_points(fish).abs_diff(points));
+        let given = match best {
+            Some((fish, n)) => {
+                let quantity = points.div_ceil(catalog.get_rarity_points(fish)).min(*n);
+                *n -= quantity;
+                InventoryItem::new(fish.clone(), quantity)
+            }
+            None => {
+                let price = sell_price(catalog, &item.fish_type);
+                if coins < price {
+                    continue;
+                }
+                coins -= price;
+                InventoryItem::new(COIN_ITEM.to_string(), price)
+            }
+        };
+        suggestions.push(Offer {
+            offered: vec![given],
+            requested: vec![InventoryItem::new(item.fish_type.clone(), 1)],
+        });
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swaps_duplicates_both_sides_lack() {
+        let catalog = FishCatalog::new();
+        let mine = HashMap::from([("sardinha".to_string(), 5), ("atum".to_string(), 1)]);
+        let theirs = vec![
+            InventoryItem::new("pirarucu".to_string(), 3),
+            InventoryItem::new("atum".to_string(), 4),
+        ];
+        let suggestions = suggest_trades(&catalog, &mine, &theirs, 0);
+        assert_eq!(
+            suggestions,
+            vec![Offer {
+                offered: vec![InventoryItem::new("sardinha".to_string(), 2)],
+                requested: vec![InventoryItem::new("pirarucu".to_string(), 1)],
+            }]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_coins() {
+        let catalog = FishCatalog::new();
+        let theirs = vec![InventoryItem::new("pirarucu".to_string(), 2)];
+        assert!(suggest_trades(&catalog, &HashMap::new(), &theirs, 5).is_empty());
+        let suggestions = suggest_trades(&catalog, &HashMap::new(), &theirs, 20);
+        assert_eq!(suggestions[0].to_string(), "moedas|12 > pirarucu|1");
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use super::fisher::RARITY_NAMES;
+use crate::server::Peer;
+
+/// Segundos entre o anúncio e o início de um torneio, para que todos recebam o anúncio
+pub const TOURNAMENT_COUNTDOWN: u64 = 5;
+/// Segundos que o organizador espera após o fim para receber as últimas pontuações
+pub const TOURNAMENT_GRACE: u64 = 2;
+
+/// Torneio de pesca cronometrado. Todos os peers guardam o torneio anunciado, mas apenas
+/// o organizador guarda o placar de todos os participantes
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    pub id: String,
+    pub organizer: Peer,
+    /// Início e fim do torneio, em segundos desde a época UNIX
+    pub start: u64,
+    pub end: u64,
+    /// Raridade mínima para que um peixe conte pontos
+    pub min_tier: u8,
+    pub joined: bool,
+    pub my_score: u32,
+    /// Placar dos participantes, só é preenchido no organizador
+    pub scores: HashMap<String, u32>,
+    pub finished: bool,
+}
+
+impl Tournament {
+    pub fn new(id: String, organizer: Peer, start: u64, end: u64, min_tier: u8) -> Self {
+        Self {
+            id,
+            organizer,
+            start,
+            end,
+            min_tier,
+            joined: false,
+            my_score: 0,
+            scores: HashMap::new(),
+            finished: false,
+        }
+    }
+
+    /// Checa se um torneio anunciado pela rede faz sentido: a janela não termina antes de
+    /// começar e a raridade mínima existe
+    pub fn is_valid(&self) -> bool {
+        self.start <= self.end && (self.min_tier as usize) < RARITY_NAMES.len()
+    }
+
+    /// Checa se um instante está dentro da janela do torneio
+    pub fn is_running(&self, now: u64) -> bool {
+        !self.finished && now >= self.start && now < self.end
+    }
+
+    /// Checa se um peixe pescado agora conta pontos para o torneio
+    pub fn counts(&self, rarity_rank: u8, now: u64) -> bool {
+        self.joined && self.is_running(now) && rarity_rank >= self.min_tier
+    }
+
+    /// Registra a pontuação de um participante. Pontuações nunca diminuem, então
+    /// mensagens atrasadas ou repetidas não atrapalham o placar
+    pub fn record_score(&mut self, username: &str, score: u32) {
+        let entry = self.scores.entry(username.to_string()).or_insert(0);
+        *entry = (*entry).max(score);
+    }
+
+    /// Placar ordenado da maior para a menor pontuação
+    pub fn standings(&self) -> Vec<(String, u32)> {
+        let mut standings: Vec<(String, u32)> =
+            self.scores.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        standings.sort_by(|(name_a, a), (name_b, b)| b.cmp(a).then(name_a.cmp(name_b)));
+        standings
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use super::fisher::FishCatalog;
+use super::market::{is_coin, sell_price};
+use crate::server::InventoryItem;
+
+/// A partir de quantas vezes um lado vale mais que o outro a troca é considerada desigual
+pub const LOPSIDED_RATIO: u32 = 2;
+
+/// Preços observados em trocas e leilões concluídos, em moedas por unidade
+#[derive(Debug, Default)]
+pub struct PriceHistory {
+    observed: HashMap<String, (u64, u32)>,
+}
+
+/// Valor em moedas dos dois lados de uma oferta, do ponto de vista de quem recebe a oferta
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Valuation {
+    /// O que recebemos
+    pub incoming: u32,
+    /// O que entregamos
+    pub outgoing: u32,
+}
+
+impl PriceHistory {
+    /// Registra uma troca concluída. Só trocas de um único peixe por moedas revelam preço
+    pub fn record_trade(&mut self, given: &[InventoryItem], received: &[InventoryItem]) {
+        for (fish, coins) in [(given, received), (received, given)] {
+            if let ([fish], [coins]) = (fish, coins)
+                && !is_coin(&fish.fish_type)
+                && is_coin(&coins.fish_type)
+                && fish.quantity > 0
+            {
+                self.record_price(&fish.fish_type, coins.quantity / fish.quantity);
+            }
+        }
+    }
+
+    /// Registra o preço por unidade pago por um peixe
+    pub fn record_price(&mut self, fish: &str, price: u32) {
+        let entry = self.observed.entry(fish.to_string()).or_default();
+        entry.0 += price as u64;
+        entry.1 += 1;
+    }
+
+    /// Preço de um peixe: a média observada na rede, ou o preço do mercado se nunca vimos
+    /// uma troca dele
+    pub fn price(&self, catalog: &FishCatalog, fish: &str) -> u32 {
+        match self.observed.get(fish) {
+            Some((total, count)) if *count > 0 => (total / *count as u64) as u32,
+            _ => sell_price(catalog, fish),
+        }
+    }
+
+    /// Valor total de uma lista de itens, moedas valem o próprio número. As quantidades
+    /// podem vir de outros peers, então a conta satura em vez de estourar
+    pub fn value(&self, catalog: &FishCatalog, items: &[InventoryItem]) -> u32 {
+        items
+            .iter()
+            .map(|i| {
+                if is_coin(&i.fish_type) {
+                    i.quantity
+                } else {
+                    self.price(catalog, &i.fish_type).saturating_mul(i.quantity)
+                }
+            })
+            .fold(0, u32::saturating_add)
+    }
+
+    /// Avalia uma oferta: `incoming` é o que recebemos e `outgoing` o que entregamos
+    pub fn evaluate(
+        &self,
+        catalog: &FishCatalog,
+        incoming: &[InventoryItem],
+        outgoing: &[InventoryItem],
+    ) -> Valuation {
+        Valuation {
+            incoming: self.value(catalog, incoming),
+            outgoing: self.value(catalog, outgoing),
+        }
+    }
+}
+
+impl Valuation {
+    /// Se um dos lados vale pelo menos `LOPSIDED_RATIO` vezes o outro
+    pub fn is_lopsided(&self) -> bool {
+        let (low, high) = if self.incoming < self.outgoing {
+            (self.incoming, self.outgoing)
+        } else {
+            (self.outgoing, self.incoming)
+        };
+        high > 0 && high >= low.saturating_mul(LOPSIDED_RATIO)
+    }
+
+    /// Se a troca nos favorece
+    pub fn in_our_favor(&self) -> bool {
+        self.incoming >= self.outgoing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observed_prices_override_market() {
+        let catalog = FishCatalog::new();
+        let mut history = PriceHistory::default();
+        assert_eq!(history.price(&catalog, "sardinha"), 5);
+
+        let fish = [InventoryItem::new("sardinha".to_string(), 2)];
+        let coins = [InventoryItem::new("moedas".to_string(), 30)];
+        history.record_trade(&fish, &coins);
+        history.record_price("sardinha", 5);
+        assert_eq!(history.price(&catalog, "sardinha"), 10);
+    }
+
+    #[test]
+    fn lopsided_offers() {
+        let catalog = FishCatalog::new();
+        let history = PriceHistory::default();
+        let fair = history.evaluate(
+            &catalog,
+            &[InventoryItem::new("pirarucu".to_string(), 1)],
+            &[InventoryItem::new("sardinha".to_string(), 2)],
+        );
+        assert_eq!(
+            fair,
+            Valuation {
+                incoming: 12,
+                outgoing: 10
+            }
+        );
+        assert!(!fair.is_lopsided());
+
+        let unfair = history.evaluate(
+            &catalog,
+            &[InventoryItem::new("sardinha".to_string(), 1)],
+            &[InventoryItem::new("peixe-lua".to_string(), 1)],
+        );
+        assert!(unfair.is_lopsided());
+        assert!(!unfair.in_our_favor());
+    }
+
+    #[test]
+    fn huge_quantities_saturate() {
+        let catalog = FishCatalog::new();
+        let history = PriceHistory::default();
+        let items = [
+            InventoryItem::new("peixe-lua".to_string(), u32::MAX),
+            InventoryItem::new("moedas".to_string(), u32::MAX),
+        ];
+        assert_eq!(history.value(&catalog, &items), u32::MAX);
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+
+use crate::server::Peer;
+
+/// Listas de desejos: os peixes que queremos e os que cada peer anunciou querer
+#[derive(Debug, Default)]
+pub struct Wishlist {
+    pub mine: BTreeSet<String>,
+    pub others: HashMap<SocketAddr, (Peer, BTreeSet<String>)>,
+}
+
+impl Wishlist {
+    /// Peers que querem o peixe dado
+    pub fn wishers(&self, fish: &str) -> Vec<Peer> {
+        self.others
+            .values()
+            .filter(|(_, wanted)| wanted.contains(fish))
+            .map(|(peer, _)| peer.clone())
+            .collect()
+    }
+
+    /// Substitui a lista anunciada por um peer, uma lista vazia o remove
+    pub fn set_peer_list(&mut self, peer: Peer, wanted: BTreeSet<String>) {
+        if wanted.is_empty() {
+            self.others.remove(&peer.address());
+        } else {
+            self.others.insert(peer.address(), (peer, wanted));
+        }
+    }
+}
@@ -1,12 +1,37 @@
 pub use crate::event::Event;
 use crate::event::handlers;
+use crate::gameplay::AuctionHouse;
+use crate::gameplay::Consumables;
+use crate::gameplay::DuelBook;
+use crate::gameplay::Environment;
 use crate::gameplay::FishBasket;
 use crate::gameplay::FishCatalog;
+use crate::gameplay::GiftBook;
+use crate::gameplay::GuildBook;
+use crate::gameplay::InventoryView;
+use crate::gameplay::MultiTradeBook;
+use crate::gameplay::OfferBoard;
+use crate::gameplay::OrderBook;
+use crate::gameplay::PartyBook;
+use crate::gameplay::PriceHistory;
+use crate::gameplay::Privacy;
+use crate::gameplay::QuestBook;
+use crate::gameplay::Scarcity;
+use crate::gameplay::SearchBook;
+use crate::gameplay::ShoalBook;
+use crate::gameplay::Tournament;
+use crate::gameplay::TradeHistory;
+use crate::gameplay::TradeRules;
+use crate::gameplay::Wishlist;
 pub use crate::server::ServerBackend;
-use crate::server::{FNP, Peer, protocol::OfferBuff};
+use crate::server::{FNP, InventoryItem, Peer, protocol::OfferBuff};
 use async_channel::{Receiver, Sender};
 use async_dup::Mutex;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+};
 
 pub mod event;
 pub mod gameplay;
@@ -23,16 +48,171 @@ pub struct AppState {
     pub basket: Mutex<FishBasket>,
     // Buffer de ofertas/trocas recebidas
     pub offer_buffers: Mutex<OfferBuff>,
+    // Torneio anunciado na rede, se houver
+    pub tournament: Mutex<Option<Tournament>>,
+    // Missões diárias e seu progresso
+    pub quests: Mutex<QuestBook>,
+    // Iscas disponíveis, cada pesca consome uma
+    pub baits: Mutex<u32>,
+    // Iscas raras e o bônus do último prato, feitos com receitas
+    pub consumables: Mutex<Consumables>,
+    // Aquários comprados ou feitos, cada um aumenta a capacidade da cesta
+    pub aquariums: Mutex<u32>,
+    // Pescas da rede por espécie, usadas no modo de escassez
+    pub scarcity: Mutex<Scarcity>,
+    // Cronograma de clima combinado com a rede
+    pub environment: Mutex<Environment>,
+    // Cardumes avistados na rede e quem respondeu a eles
+    pub shoals: Mutex<ShoalBook>,
+    // Moedas, guardadas ao lado da cesta
+    pub coins: Mutex<u32>,
+    // Leilões abertos na rede
+    pub auctions: Mutex<AuctionHouse>,
+    // Mural de ofertas públicas
+    pub board: Mutex<OfferBoard>,
+    // Livro de ordens de compra e venda
+    pub orders: Mutex<OrderBook>,
+    // Peers cujo inventário pedimos com `$comparar`, a resposta vira sugestões de troca
+    pub comparisons: Mutex<HashSet<SocketAddr>>,
+    // Filtros de `$i` à espera do inventário pedido, pelo endereço do peer
+    pub inspections: Mutex<HashMap<SocketAddr, InventoryView>>,
+    // Quem pode ver o nosso inventário e os pedidos esperando resposta
+    pub privacy: Mutex<Privacy>,
+    // Buscas de peixe pela rede, nossas e repassadas
+    pub searches: Mutex<SearchBook>,
+    // Nossa lista de desejos e as dos outros peers
+    pub wishlist: Mutex<Wishlist>,
+    // Regras de aceite e recusa automáticos de ofertas
+    pub rules: Mutex<TradeRules>,
+    // Preços observados em trocas, usados para avaliar ofertas
+    pub prices: Mutex<PriceHistory>,
+    // Histórico de trocas concluídas, recusadas e expiradas
+    pub history: Mutex<TradeHistory>,
+    // Trocas entre vários peers em andamento
+    pub multitrades: Mutex<MultiTradeBook>,
+    // Presentes enviados aguardando confirmação
+    pub gifts: Mutex<GiftBook>,
+    // Duelos de pesca em andamento
+    pub duels: Mutex<DuelBook>,
+    // Guildas da rede, com a réplica do cesto da nossa
+    pub guilds: Mutex<GuildBook>,
+    // Grupo de pesca temporário e convites pendentes
+    pub party: Mutex<PartyBook>,
+    // Modo de teste, isolado da rede, o único em que `$importar` funciona
+    pub sandbox: Mutex<bool>,
 }
 
 impl AppState {
     pub fn new() -> Self {
+        let fish_catalog = FishCatalog::new();
+        let quests = QuestBook::for_day(gameplay::today(), &fish_catalog);
         Self {
-            fish_catalog: FishCatalog::new(),
+            fish_catalog,
             basket: Mutex::new(FishBasket::new()),
             offer_buffers: Mutex::new(OfferBuff::default()),
+            tournament: Mutex::new(None),
+            quests: Mutex::new(quests),
+            baits: Mutex::new(0),
+            consumables: Mutex::new(Consumables::default()),
+            aquariums: Mutex::new(0),
+            scarcity: Mutex::new(Scarcity::default()),
+            environment: Mutex::new(Environment::new(rand::random(), gameplay::now_secs())),
+            shoals: Mutex::new(ShoalBook::default()),
+            coins: Mutex::new(0),
+            auctions: Mutex::new(AuctionHouse::default()),
+            board: Mutex::new(OfferBoard::default()),
+            orders: Mutex::new(OrderBook::default()),
+            comparisons: Mutex::new(HashSet::new()),
+            inspections: Mutex::new(HashMap::new()),
+            privacy: Mutex::new(Privacy::default()),
+            searches: Mutex::new(SearchBook::default()),
+            wishlist: Mutex::new(Wishlist::default()),
+            rules: Mutex::new(TradeRules::default()),
+            prices: Mutex::new(PriceHistory::default()),
+            history: Mutex::new(TradeHistory::default()),
+            multitrades: Mutex::new(MultiTradeBook::default()),
+            gifts: Mutex::new(GiftBook::default()),
+            duels: Mutex::new(DuelBook::default()),
+            guilds: Mutex::new(GuildBook::default()),
+            party: Mutex::new(PartyBook::default()),
+            sandbox: Mutex::new(false),
         }
     }
+
+    /// Quantidade de um item (peixe ou moedas) que não está comprometida em ofertas feitas
+    /// nem em leilões, ofertas públicas, multitrocas aprovadas, presentes não confirmados,
+    /// apostas de duelos e depósitos na guilda.
+    /// `ignore` desconsidera a oferta feita a um peer, útil quando ela própria está sendo executada
+    pub fn available(&self, item: &str, ignore: Option<SocketAddr>) -> u32 {
+        let total = if gameplay::is_coin(item) {
+            *self.coins.lock()
+        } else {
+            self.basket.lock().map().get(item).copied().unwrap_or(0)
+        };
+        let committed: u32 = self
+            .offer_buffers
+            .lock()
+            .offers_made
+            .iter()
+            .filter(|(addr, _)| Some(**addr) != ignore)
+            .flat_map(|(_, offer)| &offer.offered)
+            .filter(|i| i.fish_type == item)
+            .map(|i| i.quantity)
+            .fold(0, u32::saturating_add);
+        let reserved = [
+            self.auctions.lock().reserved(item),
+            self.board.lock().reserved(item),
+            self.multitrades.lock().reserved(item),
+            self.gifts.lock().reserved(item),
+            self.duels.lock().reserved(item),
+            self.guilds.lock().reserved(item),
+        ]
+        .into_iter()
+        .fold(committed, u32::saturating_add);
+        total.saturating_sub(reserved)
+    }
+
+    /// Retorna o primeiro item que não temos em quantidade suficiente, junto da quantidade
+    /// disponível dele
+    pub fn find_missing(
+        &self,
+        items: &[InventoryItem],
+        ignore: Option<SocketAddr>,
+    ) -> Option<(InventoryItem, u32)> {
+        items.iter().find_map(|item| {
+            let available = self.available(&item.fish_type, ignore);
+            (available < item.quantity).then(|| (item.clone(), available))
+        })
+    }
+
+    /// Quantos peixes cabem na cesta, contando os aquários
+    pub fn capacity(&self) -> u32 {
+        gameplay::basket_capacity(*self.aquariums.lock())
+    }
+
+    /// Checa se os peixes recebidos numa troca cabem na cesta, descontando os entregues.
+    /// Moedas não ocupam espaço. As quantidades podem vir de outros peers, então as somas
+    /// saturam em vez de estourar
+    pub fn has_room(&self, given: &[InventoryItem], received: &[InventoryItem]) -> bool {
+        let fish = |items: &[InventoryItem]| -> u32 {
+            items
+                .iter()
+                .filter(|i| !gameplay::is_coin(&i.fish_type))
+                .map(|i| i.quantity)
+                .fold(0, u32::saturating_add)
+        };
+        let stored = self.basket.lock().total();
+        stored
+            .saturating_add(fish(received))
+            .saturating_sub(fish(given))
+            <= self.capacity()
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Função de dispatch de eventos, ponte entre as diferentes interfaces do sistema
@@ -46,7 +226,13 @@ pub async fn dispatch(
     while let Ok(event) = receiver.recv().await {
         match event {
             Event::PeerDisconnected(socket_addr) => {
-                handlers::handle_peer_disconnected(&app_state.clone(), &server.clone(), socket_addr).await;
+                handlers::handle_peer_disconnected(
+                    &app_state.clone(),
+                    &server.clone(),
+                    socket_addr,
+                    server_sender.clone(),
+                )
+                .await;
             }
             Event::ServerMessage(fnp, socket_addr) => {
                 handlers::handle_server_message(
@@ -60,12 +246,88 @@ pub async fn dispatch(
                 .await;
             }
             Event::UIMessage(fnp) => {
-                handlers::handle_ui_message(&app_state.clone(), fnp, server_sender.clone()).await;
+                handlers::handle_ui_message(
+                    &app_state.clone(),
+                    &server.clone(),
+                    fnp,
+                    server_sender.clone(),
+                )
+                .await;
             }
             Event::Pesca => {
-                handlers::handle_pesca(&app_state.clone()).await;
+                handlers::handle_pesca(
+                    &app_state.clone(),
+                    &server.clone(),
+                    server_sender.clone(),
+                    event_sender.clone(),
+                )
+                .await;
+            }
+            Event::TorneioInicio(id) => {
+                handlers::handle_tournament_start(&app_state.clone(), &id).await;
+            }
+            Event::LeilaoFim(id) => {
+                handlers::handle_auction_end(
+                    &app_state.clone(),
+                    &server.clone(),
+                    &id,
+                    server_sender.clone(),
+                )
+                .await;
+            }
+            Event::MultiTrocaFim(id) => {
+                handlers::handle_multi_trade_end(
+                    &app_state.clone(),
+                    &server.clone(),
+                    &id,
+                    server_sender.clone(),
+                )
+                .await;
+            }
+            Event::CardumeFim(id) => {
+                handlers::handle_shoal_end(
+                    &app_state.clone(),
+                    &server.clone(),
+                    &id,
+                    server_sender.clone(),
+                )
+                .await;
+            }
+            Event::DueloFim(id) => {
+                handlers::handle_duel_end(&app_state.clone(), &id).await;
+            }
+            Event::ProcuraFim(id) => {
+                handlers::handle_search_end(&app_state.clone(), &id).await;
+            }
+            Event::TorneioFim(id) => {
+                handlers::handle_tournament_end(
+                    &app_state.clone(),
+                    &server.clone(),
+                    &id,
+                    server_sender.clone(),
+                )
+                .await;
             }
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_gifts_do_not_fit() {
+        let app_state = AppState::new();
+        app_state
+            .basket
+            .lock()
+            .map_mut()
+            .insert("atum".to_string(), 1);
+        let gift = InventoryItem::new("atum".to_string(), u32::MAX);
+        assert!(!app_state.has_room(&[], std::slice::from_ref(&gift)));
+        assert!(!app_state.has_room(&[], &[gift.clone(), gift]));
+        assert_eq!(app_state.available("atum", None), 1);
+    }
+}
@@ -17,7 +17,7 @@
 // --------------------------------------------------------
 //
 
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use async_channel::unbounded;
 use clap::Parser;
@@ -25,6 +25,7 @@ use smol::io;
 
 // Importando elementos do nosso próprio pacote (fishnet)/importando lib.rs
 use fishnet::AppState;
+use fishnet::gameplay;
 
 /// Endereço de IP do primeiro peer: 127.0.0.1:6000
 const DEFAULT_HOST: ([u8; 4], u16) = ([127, 0, 0, 1], 6000);
@@ -34,6 +35,15 @@ fn main() -> io::Result<()> {
     smol::block_on(async {
         // Lemos argumentos do programa
         let args = fishnet::tui::Args::parse();
+        // Subcomandos rodam sem entrar na rede
+        if let Some(fishnet::tui::CliCommand::Export {
+            usuario,
+            formato,
+            arquivo,
+        }) = args.command()
+        {
+            return export(usuario, formato, arquivo.as_deref());
+        }
         // Pergunta nome do usuário
         let username = fishnet::tui::ask_username();
 
@@ -45,8 +55,23 @@ fn main() -> io::Result<()> {
         let (ssender, sreceiver) = unbounded();
 
         let app_state = Arc::new(AppState::new());
+        // Carrega o histórico de trocas salvo deste usuário
+        app_state
+            .history
+            .lock()
+            .load(Path::new(&format!("historico-{}.csv", username)));
 
-        let server = Arc::new(fishnet::ServerBackend::new(&username, requested_addr)?);
+        app_state.scarcity.lock().enabled = args.scarcity();
+        *app_state.sandbox.lock() = args.sandbox();
+        if args.sandbox() {
+            println!("Modo de teste: o peer não aceita conexões e a cesta não é salva.");
+        }
+
+        let mut server = fishnet::ServerBackend::new(&username, requested_addr)?;
+        if args.sandbox() {
+            server = server.isolated();
+        }
+        let server = Arc::new(server);
 
         let host_peer = server.host();
         println!("Escutando no endereço {}", host_peer.address());
@@ -82,3 +107,31 @@ fn main() -> io::Result<()> {
         server.run(sreceiver, sender).await
     })
 }
+
+/// `fishnet export`: converte a cesta salva de um usuário para o formato pedido
+fn export(username: &str, format: &str, path: Option<&str>) -> io::Result<()> {
+    let saved = gameplay::basket_path(username);
+    let content = std::fs::read_to_string(&saved).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Cesta de {} não encontrada em {}", username, saved),
+        )
+    })?;
+    let (items, coins) = gameplay::basket_from_csv(&content)
+        .and_then(gameplay::split_coins)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let catalog = gameplay::FishCatalog::new();
+    let format = format.to_lowercase();
+    let exported = gameplay::export_basket(&catalog, &format, items, coins).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Formato desconhecido: {}, use csv ou json", format),
+        )
+    })?;
+    let path = path
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("cesta-{}.{}", username, format));
+    std::fs::write(&path, exported)?;
+    println!("Cesta de {} exportada para {}.", username, path);
+    Ok(())
+}
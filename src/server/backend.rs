@@ -22,6 +22,8 @@ pub struct ServerBackend {
     connections: Arc<Mutex<HashMap<net::SocketAddr, Arc<Connection>>>>,
 
     peer_store: Arc<PeerStore>,
+    // Modo de teste: recusa toda conexão recebida
+    isolated: bool,
 }
 
 impl ServerBackend {
@@ -41,9 +43,16 @@ impl ServerBackend {
             listener,
             connections: Arc::new(Mutex::new(HashMap::new())),
             peer_store: Arc::new(PeerStore::new()),
+            isolated: false,
         })
     }
 
+    /// Isola o peer da rede, conexões recebidas são fechadas antes do anúncio de nome
+    pub fn isolated(mut self) -> Self {
+        self.isolated = true;
+        self
+    }
+
     pub fn host(&self) -> Peer {
         self.host.clone()
     }
@@ -72,7 +81,26 @@ impl ServerBackend {
         while let Ok(msg) = msg_recv.recv().await {
             match msg {
                 // mensagens gerais
-                FNP::AnnounceName { .. } | FNP::Broadcast { .. } | FNP::PeerList { .. } => {
+                FNP::AnnounceName { .. }
+                | FNP::Broadcast { .. }
+                | FNP::PeerList { .. }
+                | FNP::TournamentAnnounce { .. }
+                | FNP::TournamentResult { .. }
+                | FNP::AuctionAnnounce { .. }
+                | FNP::AuctionUpdate { .. }
+                | FNP::AuctionClose { .. }
+                | FNP::PublicOffer { .. }
+                | FNP::PublicOfferClosed { .. }
+                | FNP::OrderAnnounce { .. }
+                | FNP::OrderCancel { .. }
+                | FNP::FishSearch { .. }
+                | FNP::Wishlist { .. }
+                | FNP::CatchReport { .. }
+                | FNP::WeatherSchedule { .. }
+                | FNP::ShoalEvent { .. }
+                | FNP::ShoalClaim { .. }
+                | FNP::ShoalResult { .. }
+                | FNP::GuildState { .. } => {
                     self.peer_store().broadcast(self.host(), msg).await;
                 }
                 // mensagens diretas
@@ -101,6 +129,13 @@ impl ServerBackend {
     async fn listen(&self, sender: Sender<Event>) -> smol::io::Result<()> {
         loop {
             let (stream, addr) = self.listener.accept().await?;
+            if self.isolated {
+                crate::tui::warn(&format!(
+                    "Conexão de {} recusada: modo de teste (--sandbox).",
+                    addr
+                ));
+                continue;
+            }
             self.handle_connection(addr, stream, sender.clone()).await?;
         }
     }
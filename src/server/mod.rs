@@ -7,5 +7,6 @@ pub use backend::ServerBackend;
 pub use protocol::FNP;
 pub use protocol::Inventory;
 pub use protocol::InventoryItem;
+pub use protocol::Standings;
 
 pub use peerstore::Peer;
@@ -136,6 +136,12 @@ impl PeerStore {
     }
 }
 
+impl Default for PeerStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Connection {
     pub fn new(stream: Async<TcpStream>) -> Self {
         Self { stream }
@@ -155,11 +161,10 @@ impl Connection {
         sender: Sender<Event>,
         // shutdown receiver
     ) {
-        let peer_addr = self
-            .stream()
-            .get_ref()
-            .peer_addr()
-            .expect("Peer address deveria estar acessível.");
+        // O peer pode fechar a conexão logo de cara, como um peer em modo de teste
+        let Ok(peer_addr) = self.stream().get_ref().peer_addr() else {
+            return;
+        };
         let mut lines = smol::io::BufReader::new(self.stream()).lines();
         while let Some(Ok(line)) = lines.next().await {
             if let Ok(msg) = FNPParser::parse(&line) {
@@ -19,8 +19,20 @@
  * FNP 1.0;
  * REM: (fnp://user@127.0.0.1:6000);
  * DEST: (*|fnp://user@129.0.0.1:4848);
- * CMD: (Message|Inspection|InventoryShowcase|Broadcast|TradeOffer|TradeConfirm|AnnounceName|PeerList);
- * [Content|Inventory|Offer|OfferResponse|Peers]: *;
+ * CMD: (Message|Inspection|InventoryShowcase|InventoryDenied|Broadcast|TradeOffer|TradeConfirm|AnnounceName|PeerList|
+ *       TournamentAnnounce|TournamentJoin|TournamentScore|TournamentResult|
+ *       AuctionAnnounce|AuctionBid|AuctionUpdate|AuctionClose|
+ *       PublicOffer|PublicOfferClaim|PublicOfferClosed|OrderAnnounce|OrderCancel|
+ *       FishSearch|FishSearchResult|Wishlist|WishNotify|
+ *       MultiTradePropose|MultiTradeVote|MultiTradeCommit|Gift|GiftAck|CatchReport|
+ *       WeatherSchedule|ShoalEvent|ShoalClaim|ShoalResult|DuelChallenge|DuelAnswer|DuelReveal|
+ *       GuildState|GuildRequest|GuildAck|PartyInvite|PartyAnswer|PartyState|PartyLeave|
+ *       PartyMessage|PartyCatch);
+ * [Content|Inventory|Offer|OfferResponse|Peers|Tournament|Start|End|MinTier|Score|Standings|
+ *  Auction|Item|MinBid|Bid|Bidder|Winner|Board|Taker|Order|Side|Price|Search|Fish|Hops|
+ *  Owner|Quantity|Wishlist|MultiTrade|Legs|Voter|Vote|Commit|Gift|Accepted|Catches|
+ *  Seed|Since|Shoal|Spawner|Slots|Winners|Duel|Casts|Stake|Commitment|Nonce|
+ *  Guild|Leader|Members|Quorum|Basket|Pending|Version|Request|Action|Party]: *;
  *
  *
  * Content: "text"
@@ -28,9 +40,62 @@
  * Offer: fish1|10 > fish2|10;
  * OfferResponse: true|false;
  * Peers: user1@127.0.0.1:6000,user2@127.0.0.1:6001;
+ * Tournament: user1-1760000000;
+ * Start: 1760000000;
+ * End: 1760000600;
+ * MinTier: 0..6;
+ * Score: 42;
+ * Standings: user1|42, user2|10;
+ * Auction: user1-1;
+ * Item: fish|1;
+ * MinBid: 50;
+ * Bid: 60;
+ * Bidder: user2;
+ * Winner: (user2|*);
+ * Board: user1-1;
+ * Taker: (user2|*);
+ * Order: user1-1;
+ * Side: (compra|venda);
+ * Price: (fish|moedas)|2;
+ * Search: user1-1;
+ * Fish: fish;
+ * Hops: 0..3;
+ * Owner: user2;
+ * Quantity: 3;
+ * Wishlist: fish1, fish2;
+ * MultiTrade: user1-1;
+ * Legs: user1>user2:fish|1+fish2|2, user2>user1:fish3|1;
+ * Voter: user2;
+ * Vote: true|false;
+ * Commit: true|false;
+ * Gift: user1-1;
+ * Accepted: true|false;
+ * Catches: fish|10, fish2|3;
+ * Seed: 12345678901234567890;
+ * Since: 1760000000;
+ * Shoal: user1-1;
+ * Spawner: user1;
+ * Slots: 3;
+ * Winners: user2, user3;
+ * Duel: user1-1;
+ * Casts: 5;
+ * Stake: fish|1, moedas|10;
+ * Commitment: 9876543210;
+ * Nonce: 1234567890;
+ * Guild: tubaroes;
+ * Leader: user1;
+ * Members: user1, user2;
+ * Quorum: 2;
+ * Basket: fish|3, fish2|1;
+ * Pending: user2-1>user2:fish|1:user2:, user3-4>user3:fish2|1:user3:user1;
+ * Version: 7;
+ * Request: user2-1;
+ * Action: (entrar|sair|depositar fish|1|sacar fish|1|aprovar user2-1|recusar user2-1);
+ * Party: user1-1;
  * */
 
 use super::peerstore::Peer;
+use crate::gameplay::{GuildAction, OrderSide, TradeLeg, Withdrawal, parse_legs};
 use regex::Regex;
 use std::collections::HashMap;
 use std::fmt::Display;
@@ -69,6 +134,11 @@ pub enum FNP {
         dest: Peer,
         inventory: Inventory,
     },
+    InventoryDenied {
+        rem: Peer,
+        dest: Peer,
+        reason: String,
+    },
     AnnounceName {
         rem: Peer,
     },
@@ -81,6 +151,243 @@ pub enum FNP {
         rem: Peer,
         dest: Peer,
     },
+    TournamentAnnounce {
+        rem: Peer,
+        id: String,
+        start: u64,
+        end: u64,
+        min_tier: u8,
+    },
+    TournamentJoin {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+    },
+    TournamentScore {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        score: u32,
+    },
+    TournamentResult {
+        rem: Peer,
+        id: String,
+        standings: Standings,
+    },
+    AuctionAnnounce {
+        rem: Peer,
+        id: String,
+        item: InventoryItem,
+        min_bid: u32,
+        end: u64,
+    },
+    AuctionBid {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        amount: u32,
+    },
+    AuctionUpdate {
+        rem: Peer,
+        id: String,
+        bidder: String,
+        amount: u32,
+    },
+    AuctionClose {
+        rem: Peer,
+        id: String,
+        winner: Option<String>,
+        amount: u32,
+    },
+    PublicOffer {
+        rem: Peer,
+        id: String,
+        offer: Offer,
+    },
+    PublicOfferClaim {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+    },
+    PublicOfferClosed {
+        rem: Peer,
+        id: String,
+        taker: Option<String>,
+    },
+    OrderAnnounce {
+        rem: Peer,
+        id: String,
+        side: OrderSide,
+        item: InventoryItem,
+        price: InventoryItem,
+    },
+    OrderCancel {
+        rem: Peer,
+        id: String,
+    },
+    FishSearch {
+        rem: Peer,
+        id: String,
+        fish: String,
+        hops: u8,
+    },
+    FishSearchResult {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        owner: String,
+        quantity: u32,
+    },
+    Wishlist {
+        rem: Peer,
+        fish: Vec<String>,
+    },
+    WishNotify {
+        rem: Peer,
+        dest: Peer,
+        fish: String,
+        quantity: u32,
+    },
+    MultiTradePropose {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        legs: Vec<TradeLeg>,
+    },
+    MultiTradeVote {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        voter: String,
+        approve: bool,
+    },
+    MultiTradeCommit {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        commit: bool,
+    },
+    Gift {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        item: InventoryItem,
+    },
+    GiftAck {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        accepted: bool,
+    },
+    CatchReport {
+        rem: Peer,
+        catches: Vec<InventoryItem>,
+    },
+    WeatherSchedule {
+        rem: Peer,
+        seed: u64,
+        since: u64,
+    },
+    ShoalEvent {
+        rem: Peer,
+        id: String,
+        spawner: String,
+        fish: String,
+        slots: u32,
+        start: u64,
+        end: u64,
+    },
+    ShoalClaim {
+        rem: Peer,
+        id: String,
+    },
+    ShoalResult {
+        rem: Peer,
+        id: String,
+        winners: Vec<String>,
+    },
+    DuelChallenge {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        casts: u32,
+        stake: Vec<InventoryItem>,
+        commitment: u64,
+    },
+    DuelAnswer {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        accepted: bool,
+        stake: Vec<InventoryItem>,
+        commitment: u64,
+    },
+    DuelReveal {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        nonce: u64,
+    },
+    GuildState {
+        rem: Peer,
+        name: String,
+        leader: String,
+        members: Vec<String>,
+        quorum: u32,
+        basket: Vec<InventoryItem>,
+        pending: Vec<Withdrawal>,
+        version: u64,
+    },
+    GuildRequest {
+        rem: Peer,
+        dest: Peer,
+        name: String,
+        id: String,
+        action: GuildAction,
+    },
+    GuildAck {
+        rem: Peer,
+        dest: Peer,
+        name: String,
+        id: String,
+        accepted: bool,
+    },
+    PartyInvite {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+    },
+    PartyAnswer {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        accepted: bool,
+    },
+    PartyState {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        leader: String,
+        members: Vec<String>,
+        version: u64,
+    },
+    PartyLeave {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+    },
+    PartyMessage {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        content: String,
+    },
+    PartyCatch {
+        rem: Peer,
+        dest: Peer,
+        id: String,
+        fish: String,
+    },
 }
 
 impl FNP {
@@ -92,22 +399,103 @@ impl FNP {
             | FNP::TradeConfirm { rem, .. }
             | FNP::InventoryInspection { rem, .. }
             | FNP::InventoryShowcase { rem, .. }
+            | FNP::InventoryDenied { rem, .. }
             | FNP::AnnounceName { rem }
             | FNP::PeerList { rem, .. }
-            | FNP::RejectConnection { rem, .. } => rem,
+            | FNP::RejectConnection { rem, .. }
+            | FNP::TournamentAnnounce { rem, .. }
+            | FNP::TournamentJoin { rem, .. }
+            | FNP::TournamentScore { rem, .. }
+            | FNP::TournamentResult { rem, .. }
+            | FNP::AuctionAnnounce { rem, .. }
+            | FNP::AuctionBid { rem, .. }
+            | FNP::AuctionUpdate { rem, .. }
+            | FNP::AuctionClose { rem, .. }
+            | FNP::PublicOffer { rem, .. }
+            | FNP::PublicOfferClaim { rem, .. }
+            | FNP::PublicOfferClosed { rem, .. }
+            | FNP::OrderAnnounce { rem, .. }
+            | FNP::OrderCancel { rem, .. }
+            | FNP::FishSearch { rem, .. }
+            | FNP::FishSearchResult { rem, .. }
+            | FNP::Wishlist { rem, .. }
+            | FNP::WishNotify { rem, .. }
+            | FNP::MultiTradePropose { rem, .. }
+            | FNP::MultiTradeVote { rem, .. }
+            | FNP::MultiTradeCommit { rem, .. }
+            | FNP::Gift { rem, .. }
+            | FNP::GiftAck { rem, .. }
+            | FNP::DuelChallenge { rem, .. }
+            | FNP::DuelAnswer { rem, .. }
+            | FNP::DuelReveal { rem, .. }
+            | FNP::GuildState { rem, .. }
+            | FNP::GuildRequest { rem, .. }
+            | FNP::GuildAck { rem, .. }
+            | FNP::PartyInvite { rem, .. }
+            | FNP::PartyAnswer { rem, .. }
+            | FNP::PartyState { rem, .. }
+            | FNP::PartyLeave { rem, .. }
+            | FNP::PartyMessage { rem, .. }
+            | FNP::PartyCatch { rem, .. }
+            | FNP::CatchReport { rem, .. }
+            | FNP::WeatherSchedule { rem, .. }
+            | FNP::ShoalEvent { rem, .. }
+            | FNP::ShoalClaim { rem, .. }
+            | FNP::ShoalResult { rem, .. } => rem,
         }
     }
 
     pub fn dest(&self) -> Option<&Peer> {
         match self {
-            FNP::Broadcast { .. } | FNP::AnnounceName { .. } => None,
+            FNP::Broadcast { .. }
+            | FNP::AnnounceName { .. }
+            | FNP::TournamentAnnounce { .. }
+            | FNP::TournamentResult { .. }
+            | FNP::AuctionAnnounce { .. }
+            | FNP::AuctionUpdate { .. }
+            | FNP::AuctionClose { .. }
+            | FNP::PublicOffer { .. }
+            | FNP::PublicOfferClosed { .. }
+            | FNP::OrderAnnounce { .. }
+            | FNP::OrderCancel { .. }
+            | FNP::FishSearch { .. }
+            | FNP::Wishlist { .. }
+            | FNP::CatchReport { .. }
+            | FNP::WeatherSchedule { .. }
+            | FNP::ShoalEvent { .. }
+            | FNP::ShoalClaim { .. }
+            | FNP::ShoalResult { .. }
+            | FNP::GuildState { .. } => None,
             FNP::Message { dest, .. }
             | FNP::TradeOffer { dest, .. }
             | FNP::TradeConfirm { dest, .. }
             | FNP::InventoryInspection { dest, .. }
             | FNP::InventoryShowcase { dest, .. }
+            | FNP::InventoryDenied { dest, .. }
             | FNP::PeerList { dest, .. }
-            | FNP::RejectConnection { dest, .. } => Some(dest),
+            | FNP::RejectConnection { dest, .. }
+            | FNP::TournamentJoin { dest, .. }
+            | FNP::TournamentScore { dest, .. }
+            | FNP::AuctionBid { dest, .. }
+            | FNP::PublicOfferClaim { dest, .. }
+            | FNP::FishSearchResult { dest, .. }
+            | FNP::WishNotify { dest, .. }
+            | FNP::MultiTradePropose { dest, .. }
+            | FNP::MultiTradeVote { dest, .. }
+            | FNP::MultiTradeCommit { dest, .. }
+            | FNP::Gift { dest, .. }
+            | FNP::GiftAck { dest, .. }
+            | FNP::DuelChallenge { dest, .. }
+            | FNP::DuelAnswer { dest, .. }
+            | FNP::DuelReveal { dest, .. }
+            | FNP::GuildRequest { dest, .. }
+            | FNP::GuildAck { dest, .. }
+            | FNP::PartyInvite { dest, .. }
+            | FNP::PartyAnswer { dest, .. }
+            | FNP::PartyState { dest, .. }
+            | FNP::PartyLeave { dest, .. }
+            | FNP::PartyMessage { dest, .. }
+            | FNP::PartyCatch { dest, .. } => Some(dest),
         }
     }
 
@@ -135,9 +523,304 @@ impl FNP {
                 dest,
                 inventory,
             },
+            FNP::InventoryDenied { dest, reason, .. } => FNP::InventoryDenied { rem, dest, reason },
             FNP::AnnounceName { .. } => FNP::AnnounceName { rem },
             FNP::PeerList { dest, peers, .. } => FNP::PeerList { rem, dest, peers },
             FNP::RejectConnection { dest, .. } => FNP::RejectConnection { rem, dest },
+            FNP::TournamentAnnounce {
+                id,
+                start,
+                end,
+                min_tier,
+                ..
+            } => FNP::TournamentAnnounce {
+                rem,
+                id,
+                start,
+                end,
+                min_tier,
+            },
+            FNP::TournamentJoin { dest, id, .. } => FNP::TournamentJoin { rem, dest, id },
+            FNP::TournamentScore {
+                dest, id, score, ..
+            } => FNP::TournamentScore {
+                rem,
+                dest,
+                id,
+                score,
+            },
+            FNP::TournamentResult { id, standings, .. } => {
+                FNP::TournamentResult { rem, id, standings }
+            }
+            FNP::AuctionAnnounce {
+                id,
+                item,
+                min_bid,
+                end,
+                ..
+            } => FNP::AuctionAnnounce {
+                rem,
+                id,
+                item,
+                min_bid,
+                end,
+            },
+            FNP::AuctionBid {
+                dest, id, amount, ..
+            } => FNP::AuctionBid {
+                rem,
+                dest,
+                id,
+                amount,
+            },
+            FNP::AuctionUpdate {
+                id, bidder, amount, ..
+            } => FNP::AuctionUpdate {
+                rem,
+                id,
+                bidder,
+                amount,
+            },
+            FNP::AuctionClose {
+                id, winner, amount, ..
+            } => FNP::AuctionClose {
+                rem,
+                id,
+                winner,
+                amount,
+            },
+            FNP::PublicOffer { id, offer, .. } => FNP::PublicOffer { rem, id, offer },
+            FNP::PublicOfferClaim { dest, id, .. } => FNP::PublicOfferClaim { rem, dest, id },
+            FNP::PublicOfferClosed { id, taker, .. } => FNP::PublicOfferClosed { rem, id, taker },
+            FNP::OrderAnnounce {
+                id,
+                side,
+                item,
+                price,
+                ..
+            } => FNP::OrderAnnounce {
+                rem,
+                id,
+                side,
+                item,
+                price,
+            },
+            FNP::OrderCancel { id, .. } => FNP::OrderCancel { rem, id },
+            FNP::FishSearch { id, fish, hops, .. } => FNP::FishSearch {
+                rem,
+                id,
+                fish,
+                hops,
+            },
+            FNP::FishSearchResult {
+                dest,
+                id,
+                owner,
+                quantity,
+                ..
+            } => FNP::FishSearchResult {
+                rem,
+                dest,
+                id,
+                owner,
+                quantity,
+            },
+            FNP::Wishlist { fish, .. } => FNP::Wishlist { rem, fish },
+            FNP::CatchReport { catches, .. } => FNP::CatchReport { rem, catches },
+            FNP::WeatherSchedule { seed, since, .. } => FNP::WeatherSchedule { rem, seed, since },
+            FNP::ShoalEvent {
+                id,
+                spawner,
+                fish,
+                slots,
+                start,
+                end,
+                ..
+            } => FNP::ShoalEvent {
+                rem,
+                id,
+                spawner,
+                fish,
+                slots,
+                start,
+                end,
+            },
+            FNP::ShoalClaim { id, .. } => FNP::ShoalClaim { rem, id },
+            FNP::ShoalResult { id, winners, .. } => FNP::ShoalResult { rem, id, winners },
+            FNP::WishNotify {
+                dest,
+                fish,
+                quantity,
+                ..
+            } => FNP::WishNotify {
+                rem,
+                dest,
+                fish,
+                quantity,
+            },
+            FNP::MultiTradePropose { dest, id, legs, .. } => FNP::MultiTradePropose {
+                rem,
+                dest,
+                id,
+                legs,
+            },
+            FNP::MultiTradeVote {
+                dest,
+                id,
+                voter,
+                approve,
+                ..
+            } => FNP::MultiTradeVote {
+                rem,
+                dest,
+                id,
+                voter,
+                approve,
+            },
+            FNP::MultiTradeCommit {
+                dest, id, commit, ..
+            } => FNP::MultiTradeCommit {
+                rem,
+                dest,
+                id,
+                commit,
+            },
+            FNP::Gift { dest, id, item, .. } => FNP::Gift {
+                rem,
+                dest,
+                id,
+                item,
+            },
+            FNP::GiftAck {
+                dest, id, accepted, ..
+            } => FNP::GiftAck {
+                rem,
+                dest,
+                id,
+                accepted,
+            },
+            FNP::DuelChallenge {
+                dest,
+                id,
+                casts,
+                stake,
+                commitment,
+                ..
+            } => FNP::DuelChallenge {
+                rem,
+                dest,
+                id,
+                casts,
+                stake,
+                commitment,
+            },
+            FNP::DuelAnswer {
+                dest,
+                id,
+                accepted,
+                stake,
+                commitment,
+                ..
+            } => FNP::DuelAnswer {
+                rem,
+                dest,
+                id,
+                accepted,
+                stake,
+                commitment,
+            },
+            FNP::DuelReveal {
+                dest, id, nonce, ..
+            } => FNP::DuelReveal {
+                rem,
+                dest,
+                id,
+                nonce,
+            },
+            FNP::GuildState {
+                name,
+                leader,
+                members,
+                quorum,
+                basket,
+                pending,
+                version,
+                ..
+            } => FNP::GuildState {
+                rem,
+                name,
+                leader,
+                members,
+                quorum,
+                basket,
+                pending,
+                version,
+            },
+            FNP::GuildRequest {
+                dest,
+                name,
+                id,
+                action,
+                ..
+            } => FNP::GuildRequest {
+                rem,
+                dest,
+                name,
+                id,
+                action,
+            },
+            FNP::GuildAck {
+                dest,
+                name,
+                id,
+                accepted,
+                ..
+            } => FNP::GuildAck {
+                rem,
+                dest,
+                name,
+                id,
+                accepted,
+            },
+            FNP::PartyInvite { dest, id, .. } => FNP::PartyInvite { rem, dest, id },
+            FNP::PartyAnswer {
+                dest, id, accepted, ..
+            } => FNP::PartyAnswer {
+                rem,
+                dest,
+                id,
+                accepted,
+            },
+            FNP::PartyState {
+                dest,
+                id,
+                leader,
+                members,
+                version,
+                ..
+            } => FNP::PartyState {
+                rem,
+                dest,
+                id,
+                leader,
+                members,
+                version,
+            },
+            FNP::PartyLeave { dest, id, .. } => FNP::PartyLeave { rem, dest, id },
+            FNP::PartyMessage {
+                dest, id, content, ..
+            } => FNP::PartyMessage {
+                rem,
+                dest,
+                id,
+                content,
+            },
+            FNP::PartyCatch { dest, id, fish, .. } => FNP::PartyCatch {
+                rem,
+                dest,
+                id,
+                fish,
+            },
         }
     }
 }
@@ -181,54 +864,370 @@ impl FNPParser {
             "TradeOffer" => Ok(FNP::TradeOffer {
                 rem,
                 dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
-                offer: Offer::from_str(&unescape_semicolons(
-                    fields.get("Offer").ok_or("No Offer")?,
-                ))?,
+                offer: Offer::from_str(&unescape_semicolons(
+                    fields.get("Offer").ok_or("No Offer")?,
+                ))?,
+            }),
+            "TradeConfirm" => Ok(FNP::TradeConfirm {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                response: fields
+                    .get("Response")
+                    .ok_or("No CONFIRM")?
+                    .parse()
+                    .map_err(|e: std::str::ParseBoolError| e.to_string())?,
+                offer: Offer::from_str(&unescape_semicolons(
+                    fields.get("Offer").ok_or("No Offer")?,
+                ))?,
+            }),
+            "InventoryShowcase" => Ok(FNP::InventoryShowcase {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                inventory: Inventory::from_str(&unescape_semicolons(
+                    fields.get("Inventory").ok_or("No Inventory")?,
+                ))?,
+            }),
+            "InventoryDenied" => Ok(FNP::InventoryDenied {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                reason: unescape_semicolons(&Self::extract_quoted_content(
+                    fields.get("Content").ok_or("No Content")?,
+                )?),
+            }),
+            "InventoryInspection" => Ok(FNP::InventoryInspection {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+            }),
+            "AnnounceName" => Ok(FNP::AnnounceName { rem }),
+            "PeerList" => {
+                let peers_str = fields.get("Peers").ok_or("No Peers")?;
+                let peers = peers_str
+                    .split(',')
+                    .map(|s| Peer::from_str(s.trim()))
+                    .collect::<Result<Vec<Peer>, _>>()?;
+                Ok(FNP::PeerList {
+                    rem,
+                    dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                    peers,
+                })
+            }
+            "RejectConnection" => Ok(FNP::RejectConnection {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+            }),
+            "TournamentAnnounce" => Ok(FNP::TournamentAnnounce {
+                rem,
+                id: fields.get("Tournament").ok_or("No Tournament")?.clone(),
+                start: Self::parse_number(&fields, "Start")?,
+                end: Self::parse_number(&fields, "End")?,
+                min_tier: Self::parse_number(&fields, "MinTier")?,
+            }),
+            "TournamentJoin" => Ok(FNP::TournamentJoin {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Tournament").ok_or("No Tournament")?.clone(),
+            }),
+            "TournamentScore" => Ok(FNP::TournamentScore {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Tournament").ok_or("No Tournament")?.clone(),
+                score: Self::parse_number(&fields, "Score")?,
+            }),
+            "TournamentResult" => Ok(FNP::TournamentResult {
+                rem,
+                id: fields.get("Tournament").ok_or("No Tournament")?.clone(),
+                standings: Standings::from_str(&unescape_semicolons(
+                    fields.get("Standings").ok_or("No Standings")?,
+                ))?,
+            }),
+            "AuctionAnnounce" => Ok(FNP::AuctionAnnounce {
+                rem,
+                id: fields.get("Auction").ok_or("No Auction")?.clone(),
+                item: InventoryItem::from_str(&unescape_semicolons(
+                    fields.get("Item").ok_or("No Item")?,
+                ))?,
+                min_bid: Self::parse_number(&fields, "MinBid")?,
+                end: Self::parse_number(&fields, "End")?,
+            }),
+            "AuctionBid" => Ok(FNP::AuctionBid {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Auction").ok_or("No Auction")?.clone(),
+                amount: Self::parse_number(&fields, "Bid")?,
+            }),
+            "AuctionUpdate" => Ok(FNP::AuctionUpdate {
+                rem,
+                id: fields.get("Auction").ok_or("No Auction")?.clone(),
+                bidder: fields.get("Bidder").ok_or("No Bidder")?.clone(),
+                amount: Self::parse_number(&fields, "Bid")?,
+            }),
+            "AuctionClose" => Ok(FNP::AuctionClose {
+                rem,
+                id: fields.get("Auction").ok_or("No Auction")?.clone(),
+                winner: match fields.get("Winner").ok_or("No Winner")?.as_str() {
+                    "*" => None,
+                    winner => Some(winner.to_string()),
+                },
+                amount: Self::parse_number(&fields, "Bid")?,
+            }),
+            "PublicOffer" => Ok(FNP::PublicOffer {
+                rem,
+                id: fields.get("Board").ok_or("No Board")?.clone(),
+                offer: Offer::from_str(&unescape_semicolons(
+                    fields.get("Offer").ok_or("No Offer")?,
+                ))?,
+            }),
+            "PublicOfferClaim" => Ok(FNP::PublicOfferClaim {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Board").ok_or("No Board")?.clone(),
+            }),
+            "PublicOfferClosed" => Ok(FNP::PublicOfferClosed {
+                rem,
+                id: fields.get("Board").ok_or("No Board")?.clone(),
+                taker: match fields.get("Taker").ok_or("No Taker")?.as_str() {
+                    "*" => None,
+                    taker => Some(taker.to_string()),
+                },
+            }),
+            "OrderAnnounce" => Ok(FNP::OrderAnnounce {
+                rem,
+                id: fields.get("Order").ok_or("No Order")?.clone(),
+                side: OrderSide::from_str(fields.get("Side").ok_or("No Side")?)?,
+                item: InventoryItem::from_str(&unescape_semicolons(
+                    fields.get("Item").ok_or("No Item")?,
+                ))?,
+                price: InventoryItem::from_str(&unescape_semicolons(
+                    fields.get("Price").ok_or("No Price")?,
+                ))?,
+            }),
+            "OrderCancel" => Ok(FNP::OrderCancel {
+                rem,
+                id: fields.get("Order").ok_or("No Order")?.clone(),
+            }),
+            "FishSearch" => Ok(FNP::FishSearch {
+                rem,
+                id: fields.get("Search").ok_or("No Search")?.clone(),
+                fish: unescape_semicolons(fields.get("Fish").ok_or("No Fish")?),
+                hops: Self::parse_number(&fields, "Hops")?,
+            }),
+            "FishSearchResult" => Ok(FNP::FishSearchResult {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Search").ok_or("No Search")?.clone(),
+                owner: fields.get("Owner").ok_or("No Owner")?.clone(),
+                quantity: Self::parse_number(&fields, "Quantity")?,
+            }),
+            "Wishlist" => Ok(FNP::Wishlist {
+                rem,
+                fish: fields
+                    .get("Wishlist")
+                    .ok_or("No Wishlist")?
+                    .split(',')
+                    .map(|f| unescape_semicolons(f.trim()))
+                    .filter(|f| !f.is_empty())
+                    .collect(),
+            }),
+            "ShoalEvent" => Ok(FNP::ShoalEvent {
+                rem,
+                id: fields.get("Shoal").ok_or("No Shoal")?.clone(),
+                spawner: fields.get("Spawner").ok_or("No Spawner")?.clone(),
+                fish: unescape_semicolons(fields.get("Fish").ok_or("No Fish")?),
+                slots: Self::parse_number(&fields, "Slots")?,
+                start: Self::parse_number(&fields, "Start")?,
+                end: Self::parse_number(&fields, "End")?,
+            }),
+            "ShoalClaim" => Ok(FNP::ShoalClaim {
+                rem,
+                id: fields.get("Shoal").ok_or("No Shoal")?.clone(),
+            }),
+            "ShoalResult" => Ok(FNP::ShoalResult {
+                rem,
+                id: fields.get("Shoal").ok_or("No Shoal")?.clone(),
+                winners: fields
+                    .get("Winners")
+                    .ok_or("No Winners")?
+                    .split(',')
+                    .map(|w| w.trim())
+                    .filter(|w| !w.is_empty())
+                    .map(|w| w.to_string())
+                    .collect(),
+            }),
+            "WeatherSchedule" => Ok(FNP::WeatherSchedule {
+                rem,
+                seed: Self::parse_number(&fields, "Seed")?,
+                since: Self::parse_number(&fields, "Since")?,
+            }),
+            "CatchReport" => Ok(FNP::CatchReport {
+                rem,
+                catches: fields
+                    .get("Catches")
+                    .ok_or("No Catches")?
+                    .split(',')
+                    .map(|c| c.trim())
+                    .filter(|c| !c.is_empty())
+                    .map(|c| InventoryItem::from_str(&unescape_semicolons(c)))
+                    .collect::<Result<_, _>>()?,
+            }),
+            "MultiTradePropose" => Ok(FNP::MultiTradePropose {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("MultiTrade").ok_or("No MultiTrade")?.clone(),
+                legs: parse_legs(&unescape_semicolons(fields.get("Legs").ok_or("No Legs")?))?,
+            }),
+            "MultiTradeVote" => Ok(FNP::MultiTradeVote {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("MultiTrade").ok_or("No MultiTrade")?.clone(),
+                voter: fields.get("Voter").ok_or("No Voter")?.clone(),
+                approve: Self::parse_number(&fields, "Vote")?,
+            }),
+            "MultiTradeCommit" => Ok(FNP::MultiTradeCommit {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("MultiTrade").ok_or("No MultiTrade")?.clone(),
+                commit: Self::parse_number(&fields, "Commit")?,
+            }),
+            "Gift" => Ok(FNP::Gift {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Gift").ok_or("No Gift")?.clone(),
+                item: InventoryItem::from_str(&unescape_semicolons(
+                    fields.get("Item").ok_or("No Item")?,
+                ))?,
+            }),
+            "GuildState" => Ok(FNP::GuildState {
+                rem,
+                name: unescape_semicolons(fields.get("Guild").ok_or("No Guild")?),
+                leader: fields.get("Leader").ok_or("No Leader")?.clone(),
+                members: fields
+                    .get("Members")
+                    .ok_or("No Members")?
+                    .split(',')
+                    .map(|m| m.trim())
+                    .filter(|m| !m.is_empty())
+                    .map(|m| m.to_string())
+                    .collect(),
+                quorum: Self::parse_number(&fields, "Quorum")?,
+                basket: parse_items(fields.get("Basket").ok_or("No Basket")?)?,
+                pending: fields
+                    .get("Pending")
+                    .ok_or("No Pending")?
+                    .split(',')
+                    .map(|w| w.trim())
+                    .filter(|w| !w.is_empty())
+                    .map(|w| Withdrawal::from_str(&unescape_semicolons(w)))
+                    .collect::<Result<_, _>>()?,
+                version: Self::parse_number(&fields, "Version")?,
+            }),
+            "GuildRequest" => Ok(FNP::GuildRequest {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                name: unescape_semicolons(fields.get("Guild").ok_or("No Guild")?),
+                id: fields.get("Request").ok_or("No Request")?.clone(),
+                action: GuildAction::from_str(&unescape_semicolons(
+                    fields.get("Action").ok_or("No Action")?,
+                ))?,
+            }),
+            "GuildAck" => Ok(FNP::GuildAck {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                name: unescape_semicolons(fields.get("Guild").ok_or("No Guild")?),
+                id: fields.get("Request").ok_or("No Request")?.clone(),
+                accepted: Self::parse_number(&fields, "Accepted")?,
+            }),
+            "PartyInvite" => Ok(FNP::PartyInvite {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Party").ok_or("No Party")?.clone(),
+            }),
+            "PartyAnswer" => Ok(FNP::PartyAnswer {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Party").ok_or("No Party")?.clone(),
+                accepted: Self::parse_number(&fields, "Accepted")?,
+            }),
+            "PartyState" => Ok(FNP::PartyState {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Party").ok_or("No Party")?.clone(),
+                leader: fields.get("Leader").ok_or("No Leader")?.clone(),
+                members: fields
+                    .get("Members")
+                    .ok_or("No Members")?
+                    .split(',')
+                    .map(|m| m.trim())
+                    .filter(|m| !m.is_empty())
+                    .map(|m| m.to_string())
+                    .collect(),
+                version: Self::parse_number(&fields, "Version")?,
+            }),
+            "PartyLeave" => Ok(FNP::PartyLeave {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Party").ok_or("No Party")?.clone(),
+            }),
+            "PartyMessage" => Ok(FNP::PartyMessage {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Party").ok_or("No Party")?.clone(),
+                content: unescape_semicolons(&Self::extract_quoted_content(
+                    fields.get("Content").ok_or("No Content")?,
+                )?),
+            }),
+            "PartyCatch" => Ok(FNP::PartyCatch {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Party").ok_or("No Party")?.clone(),
+                fish: fields.get("Fish").ok_or("No Fish")?.clone(),
+            }),
+            "DuelChallenge" => Ok(FNP::DuelChallenge {
+                rem,
+                dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Duel").ok_or("No Duel")?.clone(),
+                casts: Self::parse_number(&fields, "Casts")?,
+                stake: parse_items(fields.get("Stake").ok_or("No Stake")?)?,
+                commitment: Self::parse_number(&fields, "Commitment")?,
             }),
-            "TradeConfirm" => Ok(FNP::TradeConfirm {
+            "DuelAnswer" => Ok(FNP::DuelAnswer {
                 rem,
                 dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
-                response: fields
-                    .get("Response")
-                    .ok_or("No CONFIRM")?
-                    .parse()
-                    .map_err(|e: std::str::ParseBoolError| e.to_string())?,
-                offer: Offer::from_str(&unescape_semicolons(
-                    fields.get("Offer").ok_or("No Offer")?,
-                ))?,
+                id: fields.get("Duel").ok_or("No Duel")?.clone(),
+                accepted: Self::parse_number(&fields, "Accepted")?,
+                stake: parse_items(fields.get("Stake").ok_or("No Stake")?)?,
+                commitment: Self::parse_number(&fields, "Commitment")?,
             }),
-            "InventoryShowcase" => Ok(FNP::InventoryShowcase {
+            "DuelReveal" => Ok(FNP::DuelReveal {
                 rem,
                 dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
-                inventory: Inventory::from_str(&unescape_semicolons(
-                    fields.get("Inventory").ok_or("No Inventory")?,
-                ))?,
+                id: fields.get("Duel").ok_or("No Duel")?.clone(),
+                nonce: Self::parse_number(&fields, "Nonce")?,
             }),
-            "InventoryInspection" => Ok(FNP::InventoryInspection {
+            "GiftAck" => Ok(FNP::GiftAck {
                 rem,
                 dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                id: fields.get("Gift").ok_or("No Gift")?.clone(),
+                accepted: Self::parse_number(&fields, "Accepted")?,
             }),
-            "AnnounceName" => Ok(FNP::AnnounceName { rem }),
-            "PeerList" => {
-                let peers_str = fields.get("Peers").ok_or("No Peers")?;
-                let peers = peers_str
-                    .split(',')
-                    .map(|s| Peer::from_str(s.trim()))
-                    .collect::<Result<Vec<Peer>, _>>()?;
-                Ok(FNP::PeerList {
-                    rem,
-                    dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
-                    peers,
-                })
-            }
-            "RejectConnection" => Ok(FNP::RejectConnection {
+            "WishNotify" => Ok(FNP::WishNotify {
                 rem,
                 dest: Peer::from_str(fields.get("DEST").ok_or("No DEST")?)?,
+                fish: unescape_semicolons(fields.get("Fish").ok_or("No Fish")?),
+                quantity: Self::parse_number(&fields, "Quantity")?,
             }),
             _ => Err(format!("Unknown CMD: {}", cmd)),
         }
     }
 
+    /// Lê um campo numérico da mensagem
+    fn parse_number<T: FromStr>(fields: &HashMap<String, String>, key: &str) -> Result<T, String> {
+        fields
+            .get(key)
+            .ok_or(format!("No {}", key))?
+            .parse()
+            .map_err(|_| format!("Invalid {}", key))
+    }
+
     /// Extrai texto de dentro de aspas duplas
     fn extract_quoted_content(s: &str) -> Result<String, String> {
         let re = Regex::new(r#""([^"]*)""#).unwrap();
@@ -249,6 +1248,41 @@ fn unescape_semicolons(s: &str) -> String {
     s.replace(r"\;", ";")
 }
 
+/// Lê uma lista de itens no formato `fish|1, fish2|2`, que pode ser vazia
+fn parse_items(s: &str) -> Result<Vec<InventoryItem>, String> {
+    s.split(',')
+        .map(|i| i.trim())
+        .filter(|i| !i.is_empty())
+        .map(|i| InventoryItem::from_str(&unescape_semicolons(i)))
+        .collect()
+}
+
+/// Escreve uma lista de itens no formato lido por `parse_items`
+fn format_items(items: &[InventoryItem]) -> String {
+    items
+        .iter()
+        .map(|i| format!("{}|{}", escape_semicolons(&i.fish_type), i.quantity))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Cria uma cópia da oferta com os ponto-e-virgulas dos nomes escapados
+fn escape_offer(offer: &Offer) -> Offer {
+    let escape_items = |items: &[InventoryItem]| {
+        items
+            .iter()
+            .map(|i| InventoryItem {
+                fish_type: escape_semicolons(&i.fish_type),
+                quantity: i.quantity,
+            })
+            .collect()
+    };
+    Offer {
+        offered: escape_items(&offer.offered),
+        requested: escape_items(&offer.requested),
+    }
+}
+
 // Converte o protocolo para string
 impl Display for FNP {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -317,6 +1351,12 @@ impl Display for FNP {
             FNP::InventoryInspection { rem, dest } => {
                 format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: InventoryInspection;")
             }
+            FNP::InventoryDenied { rem, dest, reason } => {
+                let reason = escape_semicolons(reason);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: InventoryDenied; Content: \"{reason}\";"
+                )
+            }
             FNP::InventoryShowcase {
                 rem,
                 dest,
@@ -351,6 +1391,386 @@ impl Display for FNP {
             FNP::RejectConnection { rem, dest } => {
                 format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: RejectConnection;")
             }
+            FNP::TournamentAnnounce {
+                rem,
+                id,
+                start,
+                end,
+                min_tier,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: TournamentAnnounce; Tournament: {id}; Start: {start}; End: {end}; MinTier: {min_tier};"
+                )
+            }
+            FNP::TournamentJoin { rem, dest, id } => {
+                format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: TournamentJoin; Tournament: {id};")
+            }
+            FNP::TournamentScore {
+                rem,
+                dest,
+                id,
+                score,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: TournamentScore; Tournament: {id}; Score: {score};"
+                )
+            }
+            FNP::TournamentResult { rem, id, standings } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: TournamentResult; Tournament: {id}; Standings: {standings};"
+                )
+            }
+            FNP::AuctionAnnounce {
+                rem,
+                id,
+                item,
+                min_bid,
+                end,
+            } => {
+                let item = InventoryItem {
+                    fish_type: escape_semicolons(&item.fish_type),
+                    quantity: item.quantity,
+                };
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: AuctionAnnounce; Auction: {id}; Item: {item}; MinBid: {min_bid}; End: {end};"
+                )
+            }
+            FNP::AuctionBid {
+                rem,
+                dest,
+                id,
+                amount,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: AuctionBid; Auction: {id}; Bid: {amount};"
+                )
+            }
+            FNP::AuctionUpdate {
+                rem,
+                id,
+                bidder,
+                amount,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: AuctionUpdate; Auction: {id}; Bidder: {bidder}; Bid: {amount};"
+                )
+            }
+            FNP::AuctionClose {
+                rem,
+                id,
+                winner,
+                amount,
+            } => {
+                let winner = winner.as_deref().unwrap_or("*");
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: AuctionClose; Auction: {id}; Winner: {winner}; Bid: {amount};"
+                )
+            }
+            FNP::PublicOffer { rem, id, offer } => {
+                let offer = escape_offer(offer);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: PublicOffer; Board: {id}; Offer: {offer};"
+                )
+            }
+            FNP::OrderAnnounce {
+                rem,
+                id,
+                side,
+                item,
+                price,
+            } => {
+                let item = InventoryItem {
+                    fish_type: escape_semicolons(&item.fish_type),
+                    quantity: item.quantity,
+                };
+                let price = InventoryItem {
+                    fish_type: escape_semicolons(&price.fish_type),
+                    quantity: price.quantity,
+                };
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: OrderAnnounce; Order: {id}; Side: {side}; Item: {item}; Price: {price};"
+                )
+            }
+            FNP::OrderCancel { rem, id } => {
+                format!("FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: OrderCancel; Order: {id};")
+            }
+            FNP::FishSearch {
+                rem,
+                id,
+                fish,
+                hops,
+            } => {
+                let fish = escape_semicolons(fish);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: FishSearch; Search: {id}; Fish: {fish}; Hops: {hops};"
+                )
+            }
+            FNP::FishSearchResult {
+                rem,
+                dest,
+                id,
+                owner,
+                quantity,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: FishSearchResult; Search: {id}; Owner: {owner}; Quantity: {quantity};"
+                )
+            }
+            FNP::Wishlist { rem, fish } => {
+                let fish = fish
+                    .iter()
+                    .map(|f| escape_semicolons(f))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: Wishlist; Wishlist: {fish};")
+            }
+            FNP::ShoalEvent {
+                rem,
+                id,
+                spawner,
+                fish,
+                slots,
+                start,
+                end,
+            } => {
+                let fish = escape_semicolons(fish);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: ShoalEvent; Shoal: {id}; Spawner: {spawner}; Fish: {fish}; Slots: {slots}; Start: {start}; End: {end};"
+                )
+            }
+            FNP::ShoalClaim { rem, id } => {
+                format!("FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: ShoalClaim; Shoal: {id};")
+            }
+            FNP::ShoalResult { rem, id, winners } => {
+                let winners = winners.join(", ");
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: ShoalResult; Shoal: {id}; Winners: {winners};"
+                )
+            }
+            FNP::WeatherSchedule { rem, seed, since } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: WeatherSchedule; Seed: {seed}; Since: {since};"
+                )
+            }
+            FNP::CatchReport { rem, catches } => {
+                let catches = catches
+                    .iter()
+                    .map(|c| format!("{}|{}", escape_semicolons(&c.fish_type), c.quantity))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: CatchReport; Catches: {catches};")
+            }
+            FNP::MultiTradePropose {
+                rem,
+                dest,
+                id,
+                legs,
+            } => {
+                let legs = legs
+                    .iter()
+                    .map(|l| escape_semicolons(&l.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: MultiTradePropose; MultiTrade: {id}; Legs: {legs};"
+                )
+            }
+            FNP::MultiTradeVote {
+                rem,
+                dest,
+                id,
+                voter,
+                approve,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: MultiTradeVote; MultiTrade: {id}; Voter: {voter}; Vote: {approve};"
+                )
+            }
+            FNP::MultiTradeCommit {
+                rem,
+                dest,
+                id,
+                commit,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: MultiTradeCommit; MultiTrade: {id}; Commit: {commit};"
+                )
+            }
+            FNP::Gift {
+                rem,
+                dest,
+                id,
+                item,
+            } => {
+                let item = InventoryItem {
+                    fish_type: escape_semicolons(&item.fish_type),
+                    quantity: item.quantity,
+                };
+                format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: Gift; Gift: {id}; Item: {item};")
+            }
+            FNP::GiftAck {
+                rem,
+                dest,
+                id,
+                accepted,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: GiftAck; Gift: {id}; Accepted: {accepted};"
+                )
+            }
+            FNP::DuelChallenge {
+                rem,
+                dest,
+                id,
+                casts,
+                stake,
+                commitment,
+            } => {
+                let stake = format_items(stake);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: DuelChallenge; Duel: {id}; Casts: {casts}; Stake: {stake}; Commitment: {commitment};"
+                )
+            }
+            FNP::DuelAnswer {
+                rem,
+                dest,
+                id,
+                accepted,
+                stake,
+                commitment,
+            } => {
+                let stake = format_items(stake);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: DuelAnswer; Duel: {id}; Accepted: {accepted}; Stake: {stake}; Commitment: {commitment};"
+                )
+            }
+            FNP::GuildState {
+                rem,
+                name,
+                leader,
+                members,
+                quorum,
+                basket,
+                pending,
+                version,
+            } => {
+                let name = escape_semicolons(name);
+                let members = members.join(", ");
+                let basket = format_items(basket);
+                let pending = pending
+                    .iter()
+                    .map(|w| escape_semicolons(&w.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: GuildState; Guild: {name}; Leader: {leader}; Members: {members}; Quorum: {quorum}; Basket: {basket}; Pending: {pending}; Version: {version};"
+                )
+            }
+            FNP::GuildRequest {
+                rem,
+                dest,
+                name,
+                id,
+                action,
+            } => {
+                let name = escape_semicolons(name);
+                let action = escape_semicolons(&action.to_string());
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: GuildRequest; Guild: {name}; Request: {id}; Action: {action};"
+                )
+            }
+            FNP::GuildAck {
+                rem,
+                dest,
+                name,
+                id,
+                accepted,
+            } => {
+                let name = escape_semicolons(name);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: GuildAck; Guild: {name}; Request: {id}; Accepted: {accepted};"
+                )
+            }
+            FNP::PartyInvite { rem, dest, id } => {
+                format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PartyInvite; Party: {id};")
+            }
+            FNP::PartyAnswer {
+                rem,
+                dest,
+                id,
+                accepted,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PartyAnswer; Party: {id}; Accepted: {accepted};"
+                )
+            }
+            FNP::PartyState {
+                rem,
+                dest,
+                id,
+                leader,
+                members,
+                version,
+            } => {
+                let members = members.join(", ");
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PartyState; Party: {id}; Leader: {leader}; Members: {members}; Version: {version};"
+                )
+            }
+            FNP::PartyLeave { rem, dest, id } => {
+                format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PartyLeave; Party: {id};")
+            }
+            FNP::PartyMessage {
+                rem,
+                dest,
+                id,
+                content,
+            } => {
+                let content = escape_semicolons(content);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PartyMessage; Party: {id}; Content: \"{content}\";"
+                )
+            }
+            FNP::PartyCatch {
+                rem,
+                dest,
+                id,
+                fish,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PartyCatch; Party: {id}; Fish: {fish};"
+                )
+            }
+            FNP::DuelReveal {
+                rem,
+                dest,
+                id,
+                nonce,
+            } => {
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: DuelReveal; Duel: {id}; Nonce: {nonce};"
+                )
+            }
+            FNP::WishNotify {
+                rem,
+                dest,
+                fish,
+                quantity,
+            } => {
+                let fish = escape_semicolons(fish);
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: {dest}; CMD: WishNotify; Fish: {fish}; Quantity: {quantity};"
+                )
+            }
+            FNP::PublicOfferClaim { rem, dest, id } => {
+                format!("FNP 1.0; REM: {rem}; DEST: {dest}; CMD: PublicOfferClaim; Board: {id};")
+            }
+            FNP::PublicOfferClosed { rem, id, taker } => {
+                let taker = taker.as_deref().unwrap_or("*");
+                format!(
+                    "FNP 1.0; REM: {rem}; DEST: fnp://*; CMD: PublicOfferClosed; Board: {id}; Taker: {taker};"
+                )
+            }
         };
         write!(f, "{}", s)
     }
@@ -372,6 +1792,25 @@ impl InventoryItem {
     }
 }
 
+impl FromStr for InventoryItem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (fish_type, quantity) = s
+            .trim()
+            .split_once('|')
+            .ok_or("Invalid inventory item format".to_string())?;
+        let quantity = quantity
+            .trim()
+            .parse()
+            .map_err(|_| "Invalid quantity".to_string())?;
+        Ok(InventoryItem {
+            fish_type: fish_type.trim().to_string(),
+            quantity,
+        })
+    }
+}
+
 impl Display for InventoryItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}|{}", self.fish_type, self.quantity)
@@ -481,14 +1920,56 @@ impl Display for Offer {
             .collect::<Vec<_>>()
             .join(",");
 
-        let requested: String = self
-            .requested
+        let requested: String = self
+            .requested
+            .iter()
+            .map(|item| format!("{}|{}", item.fish_type, item.quantity))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        write!(f, "{} > {}", offered, requested)
+    }
+}
+
+/// Classificação final de um torneio, lista de pescadores e suas pontuações
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Standings {
+    pub entries: Vec<(String, u32)>,
+}
+
+impl FromStr for Standings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let entries: Result<Vec<(String, u32)>, String> = s
+            .split(',')
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (name, score) = entry
+                    .split_once('|')
+                    .ok_or("Invalid standings entry format".to_string())?;
+                let score = score
+                    .trim()
+                    .parse()
+                    .map_err(|_| "Invalid score".to_string())?;
+                Ok((name.trim().to_string(), score))
+            })
+            .collect();
+
+        Ok(Standings { entries: entries? })
+    }
+}
+
+impl Display for Standings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self
+            .entries
             .iter()
-            .map(|item| format!("{}|{}", item.fish_type, item.quantity))
+            .map(|(name, score)| format!("{}|{}", escape_semicolons(name), score))
             .collect::<Vec<_>>()
-            .join(",");
-
-        write!(f, "{} > {}", offered, requested)
+            .join(", ");
+        write!(f, "{s}")
     }
 }
 
@@ -818,6 +2299,665 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tournament_announce_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: TournamentAnnounce;
+            Tournament: user1-100;
+            Start: 100;
+            End: 700;
+            MinTier: 2;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::TournamentAnnounce {
+                rem,
+                id,
+                start,
+                end,
+                min_tier,
+            }) => {
+                assert_eq!(rem.username(), "user1");
+                assert_eq!(id, "user1-100");
+                assert_eq!(start, 100);
+                assert_eq!(end, 700);
+                assert_eq!(min_tier, 2);
+            }
+            _ => panic!("Should parse as TournamentAnnounce"),
+        }
+    }
+
+    #[test]
+    fn test_tournament_score_parsing() {
+        let protocol = r#"
+            REM: fnp://user2@127.0.0.1:6001;
+            DEST: fnp://user1@127.0.0.1:6000;
+            CMD: TournamentScore;
+            Tournament: user1-100;
+            Score: 42;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::TournamentScore {
+                dest, id, score, ..
+            }) => {
+                assert_eq!(dest.username(), "user1");
+                assert_eq!(id, "user1-100");
+                assert_eq!(score, 42);
+            }
+            _ => panic!("Should parse as TournamentScore"),
+        }
+    }
+
+    #[test]
+    fn test_tournament_result_round_trip() {
+        let fnp = FNP::TournamentResult {
+            rem: Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap()),
+            id: "user1-100".to_string(),
+            standings: Standings {
+                entries: vec![("user2".to_string(), 42), ("user1".to_string(), 10)],
+            },
+        };
+
+        assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+    }
+
+    #[test]
+    fn test_invalid_tournament_score() {
+        let protocol = r#"
+            REM: fnp://user2@127.0.0.1:6001;
+            DEST: fnp://user1@127.0.0.1:6000;
+            CMD: TournamentScore;
+            Tournament: user1-100;
+            Score: muitos;
+        "#;
+
+        assert!(FNPParser::parse(protocol).is_err());
+    }
+
+    #[test]
+    fn test_auction_announce_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: AuctionAnnounce;
+            Auction: user1-1;
+            Item: peixe-lua|2;
+            MinBid: 50;
+            End: 1000;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::AuctionAnnounce {
+                id,
+                item,
+                min_bid,
+                end,
+                ..
+            }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(item, InventoryItem::new("peixe-lua".to_string(), 2));
+                assert_eq!(min_bid, 50);
+                assert_eq!(end, 1000);
+            }
+            _ => panic!("Should parse as AuctionAnnounce"),
+        }
+    }
+
+    #[test]
+    fn test_auction_bid_parsing() {
+        let protocol = r#"
+            REM: fnp://user2@127.0.0.1:6001;
+            DEST: fnp://user1@127.0.0.1:6000;
+            CMD: AuctionBid;
+            Auction: user1-1;
+            Bid: 60;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::AuctionBid {
+                dest, id, amount, ..
+            }) => {
+                assert_eq!(dest.username(), "user1");
+                assert_eq!(id, "user1-1");
+                assert_eq!(amount, 60);
+            }
+            _ => panic!("Should parse as AuctionBid"),
+        }
+    }
+
+    #[test]
+    fn test_auction_close_round_trip() {
+        let rem = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        let sold = FNP::AuctionClose {
+            rem: rem.clone(),
+            id: "user1-1".to_string(),
+            winner: Some("user2".to_string()),
+            amount: 60,
+        };
+        let unsold = FNP::AuctionClose {
+            rem,
+            id: "user1-1".to_string(),
+            winner: None,
+            amount: 0,
+        };
+
+        assert_eq!(FNPParser::parse(&sold.to_string()), Ok(sold));
+        assert_eq!(FNPParser::parse(&unsold.to_string()), Ok(unsold));
+    }
+
+    #[test]
+    fn test_public_offer_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: PublicOffer;
+            Board: user1-1;
+            Offer: atum|3 > piranha|1;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::PublicOffer { rem, id, offer }) => {
+                assert_eq!(rem.username(), "user1");
+                assert_eq!(id, "user1-1");
+                assert_eq!(offer.to_string(), "atum|3 > piranha|1");
+            }
+            _ => panic!("Should parse as PublicOffer"),
+        }
+    }
+
+    #[test]
+    fn test_public_offer_closed_round_trip() {
+        let rem = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        for taker in [Some("user2".to_string()), None] {
+            let fnp = FNP::PublicOfferClosed {
+                rem: rem.clone(),
+                id: "user1-1".to_string(),
+                taker,
+            };
+            assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+        }
+    }
+
+    #[test]
+    fn test_order_announce_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: OrderAnnounce;
+            Order: user1-1;
+            Side: compra;
+            Item: peixe-espada|1;
+            Price: moedas|200;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::OrderAnnounce {
+                id,
+                side,
+                item,
+                price,
+                ..
+            }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(side, OrderSide::Compra);
+                assert_eq!(item, InventoryItem::new("peixe-espada".to_string(), 1));
+                assert_eq!(price, InventoryItem::new("moedas".to_string(), 200));
+            }
+            _ => panic!("Should parse as OrderAnnounce"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_order_side() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: OrderAnnounce;
+            Order: user1-1;
+            Side: emprestimo;
+            Item: peixe-espada|1;
+            Price: moedas|200;
+        "#;
+
+        assert!(FNPParser::parse(protocol).is_err());
+    }
+
+    #[test]
+    fn test_fish_search_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: FishSearch;
+            Search: user1-1;
+            Fish: peixe-espada;
+            Hops: 2;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::FishSearch { id, fish, hops, .. }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(fish, "peixe-espada");
+                assert_eq!(hops, 2);
+            }
+            _ => panic!("Should parse as FishSearch"),
+        }
+    }
+
+    #[test]
+    fn test_fish_search_result_round_trip() {
+        let fnp = FNP::FishSearchResult {
+            rem: Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap()),
+            dest: Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap()),
+            id: "user1-1".to_string(),
+            owner: "user3".to_string(),
+            quantity: 4,
+        };
+        assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+    }
+
+    #[test]
+    fn test_wishlist_round_trip() {
+        let rem = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        for fish in [
+            vec!["peixe-lua".to_string(), "atum".to_string()],
+            Vec::new(),
+        ] {
+            let fnp = FNP::Wishlist {
+                rem: rem.clone(),
+                fish,
+            };
+            assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+        }
+    }
+
+    #[test]
+    fn test_wish_notify_parsing() {
+        let protocol = r#"
+            REM: fnp://user2@127.0.0.1:6001;
+            DEST: fnp://user1@127.0.0.1:6000;
+            CMD: WishNotify;
+            Fish: peixe-lua;
+            Quantity: 2;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::WishNotify { fish, quantity, .. }) => {
+                assert_eq!(fish, "peixe-lua");
+                assert_eq!(quantity, 2);
+            }
+            _ => panic!("Should parse as WishNotify"),
+        }
+    }
+
+    #[test]
+    fn test_multi_trade_propose_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://user2@127.0.0.1:6001;
+            CMD: MultiTradePropose;
+            MultiTrade: user1-1;
+            Legs: user1>user2:atum|1, user2>user3:salmão|1+atum|2, user3>user1:peixe-lua|1;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::MultiTradePropose { id, legs, .. }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(legs.len(), 3);
+                assert_eq!(legs[1].from, "user2");
+                assert_eq!(legs[1].to, "user3");
+                assert_eq!(
+                    legs[1].items,
+                    vec![
+                        InventoryItem::new("salmão".to_string(), 1),
+                        InventoryItem::new("atum".to_string(), 2)
+                    ]
+                );
+            }
+            _ => panic!("Should parse as MultiTradePropose"),
+        }
+    }
+
+    #[test]
+    fn test_multi_trade_vote_and_commit_round_trip() {
+        let rem = Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap());
+        let dest = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        let vote = FNP::MultiTradeVote {
+            rem: rem.clone(),
+            dest: dest.clone(),
+            id: "user1-1".to_string(),
+            voter: "user2".to_string(),
+            approve: true,
+        };
+        assert_eq!(FNPParser::parse(&vote.to_string()), Ok(vote));
+        let commit = FNP::MultiTradeCommit {
+            rem,
+            dest,
+            id: "user1-1".to_string(),
+            commit: false,
+        };
+        assert_eq!(FNPParser::parse(&commit.to_string()), Ok(commit));
+    }
+
+    #[test]
+    fn test_weather_schedule_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: WeatherSchedule;
+            Seed: 18446744073709551615;
+            Since: 1760000000;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::WeatherSchedule { seed, since, .. }) => {
+                assert_eq!(seed, u64::MAX);
+                assert_eq!(since, 1760000000);
+            }
+            _ => panic!("Should parse as WeatherSchedule"),
+        }
+    }
+
+    #[test]
+    fn test_shoal_event_parsing() {
+        let protocol = r#"
+            REM: fnp://user2@127.0.0.1:6001;
+            DEST: fnp://*;
+            CMD: ShoalEvent;
+            Shoal: user1-1;
+            Spawner: user1;
+            Fish: salmão;
+            Slots: 3;
+            Start: 1760000000;
+            End: 1760000060;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::ShoalEvent {
+                rem,
+                id,
+                spawner,
+                fish,
+                slots,
+                start,
+                end,
+            }) => {
+                assert_eq!(rem.username(), "user2");
+                assert_eq!(id, "user1-1");
+                assert_eq!(spawner, "user1");
+                assert_eq!(fish, "salmão");
+                assert_eq!(slots, 3);
+                assert_eq!(end - start, 60);
+            }
+            _ => panic!("Should parse as ShoalEvent"),
+        }
+    }
+
+    #[test]
+    fn test_shoal_claim_round_trip() {
+        let rem = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        let fnp = FNP::ShoalClaim {
+            rem: rem.clone(),
+            id: "user2-4".to_string(),
+        };
+        assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+        for winners in [vec!["user2".to_string(), "user3".to_string()], Vec::new()] {
+            let fnp = FNP::ShoalResult {
+                rem: rem.clone(),
+                id: "user1-2".to_string(),
+                winners,
+            };
+            assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+        }
+    }
+
+    #[test]
+    fn test_catch_report_round_trip() {
+        let rem = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        for catches in [
+            vec![
+                InventoryItem::new("sardinha".to_string(), 12),
+                InventoryItem::new("peixe-lua".to_string(), 1),
+            ],
+            Vec::new(),
+        ] {
+            let fnp = FNP::CatchReport {
+                rem: rem.clone(),
+                catches,
+            };
+            assert_eq!(FNPParser::parse(&fnp.to_string()), Ok(fnp));
+        }
+    }
+
+    #[test]
+    fn test_gift_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://user2@127.0.0.1:6001;
+            CMD: Gift;
+            Gift: user1-1;
+            Item: peixe-lua|2;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::Gift { id, item, .. }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(item, InventoryItem::new("peixe-lua".to_string(), 2));
+            }
+            _ => panic!("Should parse as Gift"),
+        }
+    }
+
+    #[test]
+    fn test_gift_ack_round_trip() {
+        let ack = FNP::GiftAck {
+            rem: Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap()),
+            dest: Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap()),
+            id: "user1-1".to_string(),
+            accepted: true,
+        };
+        assert_eq!(FNPParser::parse(&ack.to_string()), Ok(ack));
+    }
+
+    #[test]
+    fn test_duel_challenge_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://user2@127.0.0.1:6001;
+            CMD: DuelChallenge;
+            Duel: user1-1;
+            Casts: 5;
+            Stake: salmão|1, moedas|10;
+            Commitment: 9876543210;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::DuelChallenge {
+                id,
+                casts,
+                stake,
+                commitment,
+                ..
+            }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(casts, 5);
+                assert_eq!(
+                    stake,
+                    vec![
+                        InventoryItem::new("salmão".to_string(), 1),
+                        InventoryItem::new("moedas".to_string(), 10)
+                    ]
+                );
+                assert_eq!(commitment, 9876543210);
+            }
+            _ => panic!("Should parse as DuelChallenge"),
+        }
+    }
+
+    #[test]
+    fn test_duel_answer_and_reveal_round_trip() {
+        let user1 = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        let user2 = Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap());
+        let answer = FNP::DuelAnswer {
+            rem: user2.clone(),
+            dest: user1.clone(),
+            id: "user1-1".to_string(),
+            accepted: false,
+            stake: Vec::new(),
+            commitment: 0,
+        };
+        assert_eq!(FNPParser::parse(&answer.to_string()), Ok(answer));
+        let reveal = FNP::DuelReveal {
+            rem: user1,
+            dest: user2,
+            id: "user1-1".to_string(),
+            nonce: u64::MAX,
+        };
+        assert_eq!(FNPParser::parse(&reveal.to_string()), Ok(reveal));
+    }
+
+    #[test]
+    fn test_guild_state_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://*;
+            CMD: GuildState;
+            Guild: tubaroes;
+            Leader: user1;
+            Members: user1, user2;
+            Quorum: 2;
+            Basket: atum|3;
+            Pending: user2-1>user2:atum|1:user2:;
+            Version: 7;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::GuildState {
+                name,
+                leader,
+                members,
+                quorum,
+                basket,
+                pending,
+                version,
+                ..
+            }) => {
+                assert_eq!(name, "tubaroes");
+                assert_eq!(leader, "user1");
+                assert_eq!(members, vec!["user1", "user2"]);
+                assert_eq!(quorum, 2);
+                assert_eq!(basket, vec![InventoryItem::new("atum".to_string(), 3)]);
+                assert_eq!(pending.len(), 1);
+                assert_eq!(pending[0].requester, "user2");
+                assert_eq!(version, 7);
+            }
+            _ => panic!("Should parse as GuildState"),
+        }
+    }
+
+    #[test]
+    fn test_guild_request_round_trip() {
+        let user1 = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        let user2 = Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap());
+        let request = FNP::GuildRequest {
+            rem: user2.clone(),
+            dest: user1.clone(),
+            name: "tubaroes".to_string(),
+            id: "user2-1".to_string(),
+            action: GuildAction::Depositar(InventoryItem::new("atum".to_string(), 2)),
+        };
+        assert_eq!(FNPParser::parse(&request.to_string()), Ok(request));
+        let ack = FNP::GuildAck {
+            rem: user1,
+            dest: user2,
+            name: "tubaroes".to_string(),
+            id: "user2-1".to_string(),
+            accepted: true,
+        };
+        assert_eq!(FNPParser::parse(&ack.to_string()), Ok(ack));
+    }
+
+    #[test]
+    fn test_inventory_denied_round_trip() {
+        let denied = FNP::InventoryDenied {
+            rem: Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap()),
+            dest: Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap()),
+            reason: "o inventário é só para amigos".to_string(),
+        };
+        assert_eq!(FNPParser::parse(&denied.to_string()), Ok(denied));
+    }
+
+    #[test]
+    fn test_party_state_parsing() {
+        let protocol = r#"
+            REM: fnp://user1@127.0.0.1:6000;
+            DEST: fnp://user2@127.0.0.1:6001;
+            CMD: PartyState;
+            Party: user1-1;
+            Leader: user1;
+            Members: user1, user2, user3;
+            Version: 3;
+        "#;
+
+        match FNPParser::parse(protocol) {
+            Ok(FNP::PartyState {
+                id,
+                leader,
+                members,
+                version,
+                ..
+            }) => {
+                assert_eq!(id, "user1-1");
+                assert_eq!(leader, "user1");
+                assert_eq!(members, vec!["user1", "user2", "user3"]);
+                assert_eq!(version, 3);
+            }
+            _ => panic!("Should parse as PartyState"),
+        }
+    }
+
+    #[test]
+    fn test_party_messages_round_trip() {
+        let user1 = Peer::new("user1".to_string(), "127.0.0.1:6000".parse().unwrap());
+        let user2 = Peer::new("user2".to_string(), "127.0.0.1:6001".parse().unwrap());
+        let id = "user1-1".to_string();
+        let messages = [
+            FNP::PartyInvite {
+                rem: user1.clone(),
+                dest: user2.clone(),
+                id: id.clone(),
+            },
+            FNP::PartyAnswer {
+                rem: user2.clone(),
+                dest: user1.clone(),
+                id: id.clone(),
+                accepted: true,
+            },
+            FNP::PartyLeave {
+                rem: user2.clone(),
+                dest: user1.clone(),
+                id: id.clone(),
+            },
+            FNP::PartyMessage {
+                rem: user1.clone(),
+                dest: user2.clone(),
+                id: id.clone(),
+                content: "bora; pro lago".to_string(),
+            },
+            FNP::PartyCatch {
+                rem: user1,
+                dest: user2,
+                id,
+                fish: "peixe-dourado".to_string(),
+            },
+        ];
+        for msg in messages {
+            assert_eq!(FNPParser::parse(&msg.to_string()), Ok(msg));
+        }
+    }
+
     // Testes de erro
     #[test]
     fn test_missing_rem_field() {
@@ -1,10 +1,10 @@
 use std::net::SocketAddr;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 /// Argumentos da linha de comando
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 pub struct Args {
     /// Flag dizendo se o peer sendo instanciado é o primeiro da rede
     #[arg(short, long)]
@@ -15,6 +15,29 @@ pub struct Args {
     /// Endereço onde se deseja bindar o peer sendo instanciado
     #[arg(short, long, value_parser = parse_addr, required_unless_present = "first")]
     bind: Option<SocketAddr>,
+    /// Liga o modo de escassez, em que espécies muito pescadas na rede ficam mais raras
+    #[arg(short, long)]
+    escassez: bool,
+    /// Modo de teste: o peer fica isolado da rede e pode importar cestas com `$importar`
+    #[arg(short, long, conflicts_with = "peers")]
+    sandbox: bool,
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+/// Subcomandos que rodam sem entrar na rede
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// Exporta a cesta salva de um usuário ao sair (`cesta-salva-<usuario>.csv`)
+    Export {
+        /// Nome do usuário dono da cesta
+        usuario: String,
+        /// Formato do arquivo gerado: json ou csv
+        #[arg(short, long, default_value = "json")]
+        formato: String,
+        /// Arquivo de saída, por padrão `cesta-<usuario>.<formato>`
+        arquivo: Option<String>,
+    },
 }
 
 impl Args {
@@ -29,6 +52,18 @@ impl Args {
     pub fn bind_port(&self) -> Option<SocketAddr> {
         self.bind
     }
+
+    pub fn scarcity(&self) -> bool {
+        self.escassez
+    }
+
+    pub fn sandbox(&self) -> bool {
+        self.sandbox
+    }
+
+    pub fn command(&self) -> Option<&CliCommand> {
+        self.command.as_ref()
+    }
 }
 
 /// Parseando e validando os endereços
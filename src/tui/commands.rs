@@ -6,9 +6,38 @@
 //! Os comandos representados aqui refletem os que estavam em `eval()`:
 //!  - `$p` / `$pescar`
 //!  - `$l` / `$listar`
-//!  - `$i` / `$inventario` [<peer>]
+//!  - `$i` / `$inventario` [<peer>] [--raridade <raridade>] [--ordem quantidade|nome|raridade] [--busca <trecho>]
 //!  - `$t` / `$troca` <peer> <offer...>
+//!  - `$privacidade` [publico|amigos|perguntar | esconder <raridade> | mostrar <raridade> | permitir <peer> | negar <peer>]
+//!  - `$amigos` [add <peer> | remover <peer>]
+//!  - `$comparar` <peer>
+//!  - `$procurar` <peixe> [saltos]
+//!  - `$desejo` [add <peixe> | remover <peixe>]
+//!  - `$regras` [<regra> | silenciar <peer> | liberar <peer>]
+//!  - `$historico` [@peer] [peixe] | exportar <csv|json> [arquivo]
+//!  - `$exportar` <csv|json> [arquivo]
+//!  - `$importar` <arquivo>
 //!  - `$c` / `$confirmar` <s|n> <peer>
+//!  - `$dar` <peer> <peixe>|<quantidade>
+//!  - `$duelo` [<peer> [<peixe>|<quantidade>] | aceitar <peer> [<peixe>|<quantidade>] | recusar <peer>]
+//!  - `$guilda` [criar <nome> [quórum] | entrar <nome> | sair | depositar <peixe>|<quantidade> | sacar <peixe>|<quantidade> | aprovar <id> | recusar <id> | quorum <n>]
+//!  - `$grupo` [convidar <peer> | aceitar <peer> | recusar <peer> | sair | falar <mensagem>]
+//!  - `$gp` <mensagem>
+//!  - `$multitroca` [propor <pernas...> | aceitar <id> | recusar <id>]
+//!  - `$torneio` [iniciar <duração> [raridade] | entrar]
+//!  - `$missoes`
+//!  - `$craft` / `$cozinhar` [<receita> [peixe]]
+//!  - `$soltar` <peixe> [quantidade]
+//!  - `$escassez` [ligar|desligar]
+//!  - `$clima`
+//!  - `$cardume`
+//!  - `$aquario` [comprar]
+//!  - `$vender` [<peixe> [quantidade] | <peixe>|<quantidade>]
+//!  - `$leilao` [<peixe>|<quantidade> <lance mínimo> <duração>]
+//!  - `$lance` <leilão> <valor>
+//!  - `$mural` [publicar <offer...> | pegar <id> | retirar <id>]
+//!  - `$ordens`
+//!  - `$ordem` <comprar|vender> <peixe>|<quantidade> <item>|<preço> | cancelar <id>
 //!  - `$q` / `$quit`
 //!  - `$h` / `$help`
 
@@ -16,9 +45,153 @@
 pub enum Command {
     Pescar,
     List,
-    Inventario(Option<String>),
-    Trade { peer_str: String, offer_str: String },
-    ConfirmTrade { resp: bool, peer_str: String },
+    Inventario {
+        name: Option<String>,
+        raridade: Option<String>,
+        ordem: Option<String>,
+        busca: Option<String>,
+    },
+    Trade {
+        peer_str: String,
+        offer_str: String,
+    },
+    ConfirmTrade {
+        resp: bool,
+        peer_str: String,
+    },
+    Comparar(String),
+    Procurar {
+        fish: String,
+        hops: Option<String>,
+    },
+    Privacidade,
+    PrivacidadeAcesso(String),
+    PrivacidadeRaridade {
+        tier_str: String,
+        hidden: bool,
+    },
+    PrivacidadeResponder {
+        peer_str: String,
+        allow: bool,
+    },
+    Amigos,
+    AmigoAdd(String),
+    AmigoRemover(String),
+    Desejos,
+    DesejoAdd(String),
+    DesejoRemover(String),
+    Historico {
+        peer: Option<String>,
+        fish: Option<String>,
+    },
+    HistoricoExportar {
+        format: String,
+        path: Option<String>,
+    },
+    Exportar {
+        format: String,
+        path: Option<String>,
+    },
+    Importar(String),
+    Regras,
+    RegraAlternar(String),
+    Silenciar {
+        peer_str: String,
+        muted: bool,
+    },
+    Dar {
+        peer_str: String,
+        item_str: String,
+    },
+    Duelos,
+    Duelo {
+        peer_str: String,
+        stake_str: Option<String>,
+    },
+    DueloResponder {
+        peer_str: String,
+        accept: bool,
+        stake_str: Option<String>,
+    },
+    Guilda,
+    GuildaCriar {
+        name: String,
+        quorum_str: Option<String>,
+    },
+    GuildaPedido {
+        name: Option<String>,
+        action: String,
+    },
+    Grupo,
+    GrupoConvidar(String),
+    GrupoResponder {
+        peer_str: String,
+        accept: bool,
+    },
+    GrupoSair,
+    GrupoFalar(String),
+    MultiTrocas,
+    MultiTrocaPropor {
+        legs_str: String,
+    },
+    MultiTrocaVotar {
+        id: String,
+        approve: bool,
+    },
+    TorneioIniciar {
+        duracao: String,
+        raridade: Option<String>,
+    },
+    TorneioEntrar,
+    TorneioStatus,
+    Missoes,
+    Soltar {
+        fish: String,
+        quantity: Option<String>,
+    },
+    Aquario,
+    AquarioComprar,
+    Escassez(Option<bool>),
+    Clima,
+    Cardume,
+    Receita {
+        cook: bool,
+        recipe: Option<String>,
+        fish: Option<String>,
+    },
+    Vender {
+        fish: Option<String>,
+        quantity: Option<String>,
+    },
+    LeilaoListar,
+    LeilaoCriar {
+        item_str: String,
+        min_bid: String,
+        duracao: String,
+    },
+    Lance {
+        id: String,
+        valor: String,
+    },
+    MuralListar,
+    MuralPublicar {
+        offer_str: String,
+    },
+    MuralPegar {
+        id: String,
+    },
+    MuralRetirar {
+        id: String,
+    },
+    Ordens,
+    OrdemCriar {
+        side: String,
+        item_str: String,
+        price_str: String,
+    },
+    OrdemCancelar {
+        id: String,
+    },
     Quit,
     Help,
     Unknown(String),
@@ -42,8 +215,26 @@ pub fn parse_command(line: &str) -> Option<Command> {
         "$p" | "$pescar" => Some(Command::Pescar),
         "$l" | "$listar" => Some(Command::List),
         "$i" | "$inventario" => {
-            let arg = parts.get(1).map(|s| s.to_string());
-            Some(Command::Inventario(arg))
+            let (mut name, mut raridade, mut ordem, mut busca) = (None, None, None, None);
+            let mut args = parts[1..].iter();
+            while let Some(arg) = args.next() {
+                let mut value = || args.next().map(|s| s.to_string()).unwrap_or_default();
+                match arg.to_lowercase().as_str() {
+                    "--raridade" => raridade = Some(value()),
+                    "--ordem" => ordem = Some(value()),
+                    "--busca" => busca = Some(value()),
+                    flag if flag.starts_with("--") => {
+                        return Some(Command::Unknown(line.to_string()));
+                    }
+                    _ => name = Some(arg.to_string()),
+                }
+            }
+            Some(Command::Inventario {
+                name,
+                raridade,
+                ordem,
+                busca,
+            })
         }
         "$t" | "$troca" => {
             let peer_str = parts.get(1).map(|s| s.to_string()).unwrap_or_default();
@@ -62,6 +253,270 @@ pub fn parse_command(line: &str) -> Option<Command> {
             let peer_str = parts.get(2).map(|s| s.to_string()).unwrap_or_default();
             Some(Command::ConfirmTrade { resp, peer_str })
         }
+        "$comparar" => Some(Command::Comparar(
+            parts
+                .get(1)
+                .map(|s| s.trim_start_matches('@').to_string())
+                .unwrap_or_default(),
+        )),
+        "$procurar" => Some(Command::Procurar {
+            fish: parts.get(1).map(|s| s.to_string()).unwrap_or_default(),
+            hops: parts.get(2).map(|s| s.to_string()),
+        }),
+        "$privacidade" => {
+            let arg = parts
+                .get(2)
+                .map(|s| s.trim_start_matches('@').to_string())
+                .unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::Privacidade),
+                Some("esconder") => Some(Command::PrivacidadeRaridade {
+                    tier_str: arg,
+                    hidden: true,
+                }),
+                Some("mostrar") => Some(Command::PrivacidadeRaridade {
+                    tier_str: arg,
+                    hidden: false,
+                }),
+                Some("permitir") => Some(Command::PrivacidadeResponder {
+                    peer_str: arg,
+                    allow: true,
+                }),
+                Some("negar") => Some(Command::PrivacidadeResponder {
+                    peer_str: arg,
+                    allow: false,
+                }),
+                Some(mode) => Some(Command::PrivacidadeAcesso(mode.to_string())),
+            }
+        }
+        "$amigo" | "$amigos" => {
+            let peer = parts
+                .get(2)
+                .map(|s| s.trim_start_matches('@').to_string())
+                .unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::Amigos),
+                Some("add") | Some("adicionar") => Some(Command::AmigoAdd(peer)),
+                Some("rm") | Some("remover") => Some(Command::AmigoRemover(peer)),
+                _ => Some(Command::Unknown(line.to_string())),
+            }
+        }
+        "$desejo" | "$desejos" => {
+            let fish = parts.get(2).map(|s| s.to_string()).unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::Desejos),
+                Some("add") | Some("adicionar") => Some(Command::DesejoAdd(fish)),
+                Some("rm") | Some("remover") => Some(Command::DesejoRemover(fish)),
+                _ => Some(Command::Unknown(line.to_string())),
+            }
+        }
+        "$historico" | "$histórico" => {
+            if parts
+                .get(1)
+                .is_some_and(|s| s.eq_ignore_ascii_case("exportar"))
+            {
+                return Some(Command::HistoricoExportar {
+                    format: parts.get(2).map(|s| s.to_lowercase()).unwrap_or_default(),
+                    path: parts.get(3).map(|s| s.to_string()),
+                });
+            }
+            // Argumentos com `@` são o peer, os outros o peixe
+            let args = parts.get(1..).unwrap_or(&[]);
+            Some(Command::Historico {
+                peer: args
+                    .iter()
+                    .find_map(|s| s.strip_prefix('@'))
+                    .map(|s| s.to_string()),
+                fish: args
+                    .iter()
+                    .find(|s| !s.starts_with('@'))
+                    .map(|s| s.to_string()),
+            })
+        }
+        "$exportar" => Some(Command::Exportar {
+            format: parts.get(1).map(|s| s.to_lowercase()).unwrap_or_default(),
+            path: parts.get(2).map(|s| s.to_string()),
+        }),
+        "$importar" => Some(Command::Importar(
+            parts.get(1).map(|s| s.to_string()).unwrap_or_default(),
+        )),
+        "$regras" | "$regra" => {
+            let peer_str = parts
+                .get(2)
+                .map(|s| s.trim_start_matches('@').to_string())
+                .unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::Regras),
+                Some("silenciar") => Some(Command::Silenciar {
+                    peer_str,
+                    muted: true,
+                }),
+                Some("liberar") => Some(Command::Silenciar {
+                    peer_str,
+                    muted: false,
+                }),
+                Some(rule) => Some(Command::RegraAlternar(rule.to_string())),
+            }
+        }
+        "$dar" => Some(Command::Dar {
+            peer_str: parts
+                .get(1)
+                .map(|s| s.trim_start_matches('@').to_string())
+                .unwrap_or_default(),
+            item_str: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+        }),
+        "$duelo" | "$duelos" => {
+            let peer = |i: usize| {
+                parts
+                    .get(i)
+                    .map(|s| s.trim_start_matches('@').to_string())
+                    .unwrap_or_default()
+            };
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::Duelos),
+                Some("aceitar") => Some(Command::DueloResponder {
+                    peer_str: peer(2),
+                    accept: true,
+                    stake_str: parts.get(3).map(|s| s.to_string()),
+                }),
+                Some("recusar") => Some(Command::DueloResponder {
+                    peer_str: peer(2),
+                    accept: false,
+                    stake_str: None,
+                }),
+                Some(_) => Some(Command::Duelo {
+                    peer_str: peer(1),
+                    stake_str: parts.get(2).map(|s| s.to_string()),
+                }),
+            }
+        }
+        "$guilda" | "$guildas" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+            None => Some(Command::Guilda),
+            Some("criar") => Some(Command::GuildaCriar {
+                name: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+                quorum_str: parts.get(3).map(|s| s.to_string()),
+            }),
+            Some("entrar") => Some(Command::GuildaPedido {
+                name: parts.get(2).map(|s| s.to_string()),
+                action: "entrar".to_string(),
+            }),
+            Some(_) => Some(Command::GuildaPedido {
+                name: None,
+                action: parts[1..].join(" "),
+            }),
+        },
+        "$grupo" => {
+            let peer = parts
+                .get(2)
+                .map(|s| s.trim_start_matches('@').to_string())
+                .unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::Grupo),
+                Some("convidar") => Some(Command::GrupoConvidar(peer)),
+                Some("aceitar") => Some(Command::GrupoResponder {
+                    peer_str: peer,
+                    accept: true,
+                }),
+                Some("recusar") => Some(Command::GrupoResponder {
+                    peer_str: peer,
+                    accept: false,
+                }),
+                Some("sair") => Some(Command::GrupoSair),
+                Some("falar") => Some(Command::GrupoFalar(parts[2..].join(" "))),
+                _ => Some(Command::Unknown(line.to_string())),
+            }
+        }
+        "$gp" => Some(Command::GrupoFalar(parts[1..].join(" "))),
+        "$multitroca" | "$multitrocas" => {
+            let id = parts.get(2).map(|s| s.to_string()).unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::MultiTrocas),
+                Some("propor") => Some(Command::MultiTrocaPropor {
+                    legs_str: parts[2..].join(" "),
+                }),
+                Some("aceitar") => Some(Command::MultiTrocaVotar { id, approve: true }),
+                Some("recusar") => Some(Command::MultiTrocaVotar { id, approve: false }),
+                _ => Some(Command::Unknown(line.to_string())),
+            }
+        }
+        "$torneio" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+            Some("iniciar") => Some(Command::TorneioIniciar {
+                duracao: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+                raridade: parts.get(3).map(|s| s.to_string()),
+            }),
+            Some("entrar") => Some(Command::TorneioEntrar),
+            None | Some("status") => Some(Command::TorneioStatus),
+            _ => Some(Command::Unknown(line.to_string())),
+        },
+        "$missoes" | "$missões" => Some(Command::Missoes),
+        "$soltar" => Some(Command::Soltar {
+            fish: parts.get(1).map(|s| s.to_string()).unwrap_or_default(),
+            quantity: parts.get(2).map(|s| s.to_string()),
+        }),
+        "$aquario" | "$aquário" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+            None => Some(Command::Aquario),
+            Some("comprar") => Some(Command::AquarioComprar),
+            _ => Some(Command::Unknown(line.to_string())),
+        },
+        "$clima" | "$tempo" => Some(Command::Clima),
+        "$cardume" | "$cardumes" => Some(Command::Cardume),
+        "$escassez" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+            None => Some(Command::Escassez(None)),
+            Some("ligar") => Some(Command::Escassez(Some(true))),
+            Some("desligar") => Some(Command::Escassez(Some(false))),
+            _ => Some(Command::Unknown(line.to_string())),
+        },
+        "$craft" | "$cozinhar" => Some(Command::Receita {
+            cook: cmd == "$cozinhar",
+            recipe: parts.get(1).map(|s| s.to_string()),
+            fish: parts.get(2).map(|s| s.to_string()),
+        }),
+        "$vender" => {
+            let (fish, quantity) = match parts.get(1).and_then(|s| s.split_once('|')) {
+                Some((fish, quantity)) => (Some(fish.to_string()), Some(quantity.to_string())),
+                None => (
+                    parts.get(1).map(|s| s.to_string()),
+                    parts.get(2).map(|s| s.to_string()),
+                ),
+            };
+            Some(Command::Vender { fish, quantity })
+        }
+        "$leilao" | "$leilão" => match parts.get(1) {
+            None => Some(Command::LeilaoListar),
+            Some(item) => Some(Command::LeilaoCriar {
+                item_str: item.to_string(),
+                min_bid: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+                duracao: parts.get(3).map(|s| s.to_string()).unwrap_or_default(),
+            }),
+        },
+        "$lance" => Some(Command::Lance {
+            id: parts.get(1).map(|s| s.to_string()).unwrap_or_default(),
+            valor: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+        }),
+        "$mural" => {
+            let arg = parts.get(2).map(|s| s.to_string()).unwrap_or_default();
+            match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                None => Some(Command::MuralListar),
+                Some("publicar") => Some(Command::MuralPublicar {
+                    offer_str: parts.get(2..).unwrap_or(&[]).join(" "),
+                }),
+                Some("pegar") => Some(Command::MuralPegar { id: arg }),
+                Some("retirar") => Some(Command::MuralRetirar { id: arg }),
+                _ => Some(Command::Unknown(line.to_string())),
+            }
+        }
+        "$ordens" => Some(Command::Ordens),
+        "$ordem" => match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+            None => Some(Command::Ordens),
+            Some("cancelar") => Some(Command::OrdemCancelar {
+                id: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+            }),
+            Some(side) => Some(Command::OrdemCriar {
+                side: side.to_string(),
+                item_str: parts.get(2).map(|s| s.to_string()).unwrap_or_default(),
+                price_str: parts.get(3).map(|s| s.to_string()).unwrap_or_default(),
+            }),
+        },
         "$q" | "$quit" => Some(Command::Quit),
         "$h" | "$help" => Some(Command::Help),
         _ => Some(Command::Unknown(line.to_string())),
@@ -85,10 +540,36 @@ mod tests {
 
     #[test]
     fn parse_inventario() {
-        assert_eq!(parse_command("$i"), Some(Command::Inventario(None)));
+        assert_eq!(
+            parse_command("$i"),
+            Some(Command::Inventario {
+                name: None,
+                raridade: None,
+                ordem: None,
+                busca: None
+            })
+        );
         assert_eq!(
             parse_command("$i alice"),
-            Some(Command::Inventario(Some("alice".to_string())))
+            Some(Command::Inventario {
+                name: Some("alice".to_string()),
+                raridade: None,
+                ordem: None,
+                busca: None
+            })
+        );
+        assert_eq!(
+            parse_command("$i --raridade epico alice --ordem quantidade --busca sal"),
+            Some(Command::Inventario {
+                name: Some("alice".to_string()),
+                raridade: Some("epico".to_string()),
+                ordem: Some("quantidade".to_string()),
+                busca: Some("sal".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$i --cor azul"),
+            Some(Command::Unknown("$i --cor azul".to_string()))
         );
     }
 
@@ -138,6 +619,472 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_torneio() {
+        assert_eq!(
+            parse_command("$torneio iniciar 10m epico"),
+            Some(Command::TorneioIniciar {
+                duracao: "10m".to_string(),
+                raridade: Some("epico".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$torneio iniciar 30s"),
+            Some(Command::TorneioIniciar {
+                duracao: "30s".to_string(),
+                raridade: None
+            })
+        );
+        assert_eq!(
+            parse_command("$torneio entrar"),
+            Some(Command::TorneioEntrar)
+        );
+        assert_eq!(parse_command("$torneio"), Some(Command::TorneioStatus));
+        assert_eq!(
+            parse_command("$torneio sair"),
+            Some(Command::Unknown("$torneio sair".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_missoes() {
+        assert_eq!(parse_command("$missoes"), Some(Command::Missoes));
+        assert_eq!(parse_command("$missões"), Some(Command::Missoes));
+    }
+
+    #[test]
+    fn parse_vender() {
+        assert_eq!(
+            parse_command("$vender"),
+            Some(Command::Vender {
+                fish: None,
+                quantity: None
+            })
+        );
+        assert_eq!(
+            parse_command("$vender atum 3"),
+            Some(Command::Vender {
+                fish: Some("atum".to_string()),
+                quantity: Some("3".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$vender atum|3"),
+            Some(Command::Vender {
+                fish: Some("atum".to_string()),
+                quantity: Some("3".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn parse_leilao_and_lance() {
+        assert_eq!(parse_command("$leilao"), Some(Command::LeilaoListar));
+        assert_eq!(
+            parse_command("$leilao peixe-lua|1 50 5m"),
+            Some(Command::LeilaoCriar {
+                item_str: "peixe-lua|1".to_string(),
+                min_bid: "50".to_string(),
+                duracao: "5m".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$lance alice-1 60"),
+            Some(Command::Lance {
+                id: "alice-1".to_string(),
+                valor: "60".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_mural() {
+        assert_eq!(parse_command("$mural"), Some(Command::MuralListar));
+        assert_eq!(
+            parse_command("$mural publicar atum|3 > piranha|1"),
+            Some(Command::MuralPublicar {
+                offer_str: "atum|3 > piranha|1".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$mural pegar alice-1"),
+            Some(Command::MuralPegar {
+                id: "alice-1".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$mural retirar alice-1"),
+            Some(Command::MuralRetirar {
+                id: "alice-1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_ordens() {
+        assert_eq!(parse_command("$ordens"), Some(Command::Ordens));
+        assert_eq!(
+            parse_command("$ordem comprar peixe-espada|1 shiny|2"),
+            Some(Command::OrdemCriar {
+                side: "comprar".to_string(),
+                item_str: "peixe-espada|1".to_string(),
+                price_str: "shiny|2".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$ordem cancelar alice-1"),
+            Some(Command::OrdemCancelar {
+                id: "alice-1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_comparar() {
+        assert_eq!(
+            parse_command("$comparar @alice"),
+            Some(Command::Comparar("alice".to_string()))
+        );
+        assert_eq!(
+            parse_command("$comparar alice"),
+            Some(Command::Comparar("alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_procurar() {
+        assert_eq!(
+            parse_command("$procurar peixe-espada"),
+            Some(Command::Procurar {
+                fish: "peixe-espada".to_string(),
+                hops: None
+            })
+        );
+        assert_eq!(
+            parse_command("$procurar peixe-espada 2"),
+            Some(Command::Procurar {
+                fish: "peixe-espada".to_string(),
+                hops: Some("2".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn parse_desejo() {
+        assert_eq!(parse_command("$desejo"), Some(Command::Desejos));
+        assert_eq!(
+            parse_command("$desejo add peixe-lua"),
+            Some(Command::DesejoAdd("peixe-lua".to_string()))
+        );
+        assert_eq!(
+            parse_command("$desejo remover peixe-lua"),
+            Some(Command::DesejoRemover("peixe-lua".to_string()))
+        );
+        assert_eq!(
+            parse_command("$desejo trocar peixe-lua"),
+            Some(Command::Unknown("$desejo trocar peixe-lua".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_regras() {
+        assert_eq!(parse_command("$regras"), Some(Command::Regras));
+        assert_eq!(
+            parse_command("$regras aceitar-melhor"),
+            Some(Command::RegraAlternar("aceitar-melhor".to_string()))
+        );
+        assert_eq!(
+            parse_command("$regras silenciar @bob"),
+            Some(Command::Silenciar {
+                peer_str: "bob".to_string(),
+                muted: true
+            })
+        );
+        assert_eq!(
+            parse_command("$regras liberar bob"),
+            Some(Command::Silenciar {
+                peer_str: "bob".to_string(),
+                muted: false
+            })
+        );
+    }
+
+    #[test]
+    fn parse_soltar_e_aquario() {
+        assert_eq!(
+            parse_command("$soltar sardinha 3"),
+            Some(Command::Soltar {
+                fish: "sardinha".to_string(),
+                quantity: Some("3".to_string())
+            })
+        );
+        assert_eq!(parse_command("$aquario"), Some(Command::Aquario));
+        assert_eq!(
+            parse_command("$aquario comprar"),
+            Some(Command::AquarioComprar)
+        );
+        assert_eq!(
+            parse_command("$aquario vender"),
+            Some(Command::Unknown("$aquario vender".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_clima() {
+        assert_eq!(parse_command("$clima"), Some(Command::Clima));
+        assert_eq!(parse_command("$tempo"), Some(Command::Clima));
+    }
+
+    #[test]
+    fn parse_duelo() {
+        assert_eq!(parse_command("$duelos"), Some(Command::Duelos));
+        assert_eq!(
+            parse_command("$duelo @bob"),
+            Some(Command::Duelo {
+                peer_str: "bob".to_string(),
+                stake_str: None
+            })
+        );
+        assert_eq!(
+            parse_command("$duelo bob salmão|1"),
+            Some(Command::Duelo {
+                peer_str: "bob".to_string(),
+                stake_str: Some("salmão|1".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$duelo aceitar @alice moedas|10"),
+            Some(Command::DueloResponder {
+                peer_str: "alice".to_string(),
+                accept: true,
+                stake_str: Some("moedas|10".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$duelo recusar alice"),
+            Some(Command::DueloResponder {
+                peer_str: "alice".to_string(),
+                accept: false,
+                stake_str: None
+            })
+        );
+    }
+
+    #[test]
+    fn parse_guilda() {
+        assert_eq!(parse_command("$guilda"), Some(Command::Guilda));
+        assert_eq!(
+            parse_command("$guilda criar tubaroes 3"),
+            Some(Command::GuildaCriar {
+                name: "tubaroes".to_string(),
+                quorum_str: Some("3".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$guilda entrar tubaroes"),
+            Some(Command::GuildaPedido {
+                name: Some("tubaroes".to_string()),
+                action: "entrar".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$guilda sacar salmão|2"),
+            Some(Command::GuildaPedido {
+                name: None,
+                action: "sacar salmão|2".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_grupo() {
+        assert_eq!(parse_command("$grupo"), Some(Command::Grupo));
+        assert_eq!(
+            parse_command("$grupo convidar @bob"),
+            Some(Command::GrupoConvidar("bob".to_string()))
+        );
+        assert_eq!(
+            parse_command("$grupo recusar alice"),
+            Some(Command::GrupoResponder {
+                peer_str: "alice".to_string(),
+                accept: false
+            })
+        );
+        assert_eq!(parse_command("$grupo sair"), Some(Command::GrupoSair));
+        assert_eq!(
+            parse_command("$grupo falar bora pro lago"),
+            Some(Command::GrupoFalar("bora pro lago".to_string()))
+        );
+        assert_eq!(
+            parse_command("$gp bora"),
+            Some(Command::GrupoFalar("bora".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_privacidade() {
+        assert_eq!(parse_command("$privacidade"), Some(Command::Privacidade));
+        assert_eq!(
+            parse_command("$privacidade Amigos"),
+            Some(Command::PrivacidadeAcesso("amigos".to_string()))
+        );
+        assert_eq!(
+            parse_command("$privacidade esconder abissal"),
+            Some(Command::PrivacidadeRaridade {
+                tier_str: "abissal".to_string(),
+                hidden: true
+            })
+        );
+        assert_eq!(
+            parse_command("$privacidade permitir @bob"),
+            Some(Command::PrivacidadeResponder {
+                peer_str: "bob".to_string(),
+                allow: true
+            })
+        );
+        assert_eq!(
+            parse_command("$amigos add @bob"),
+            Some(Command::AmigoAdd("bob".to_string()))
+        );
+        assert_eq!(
+            parse_command("$amigos remover bob"),
+            Some(Command::AmigoRemover("bob".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_cardume() {
+        assert_eq!(parse_command("$cardume"), Some(Command::Cardume));
+        assert_eq!(parse_command("$cardumes"), Some(Command::Cardume));
+    }
+
+    #[test]
+    fn parse_escassez() {
+        assert_eq!(parse_command("$escassez"), Some(Command::Escassez(None)));
+        assert_eq!(
+            parse_command("$escassez ligar"),
+            Some(Command::Escassez(Some(true)))
+        );
+        assert_eq!(
+            parse_command("$escassez desligar"),
+            Some(Command::Escassez(Some(false)))
+        );
+    }
+
+    #[test]
+    fn parse_receita() {
+        assert_eq!(
+            parse_command("$craft"),
+            Some(Command::Receita {
+                cook: false,
+                recipe: None,
+                fish: None
+            })
+        );
+        assert_eq!(
+            parse_command("$cozinhar moqueca salmão"),
+            Some(Command::Receita {
+                cook: true,
+                recipe: Some("moqueca".to_string()),
+                fish: Some("salmão".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn parse_dar() {
+        assert_eq!(
+            parse_command("$dar @bob peixe-lua|2"),
+            Some(Command::Dar {
+                peer_str: "bob".to_string(),
+                item_str: "peixe-lua|2".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$dar bob"),
+            Some(Command::Dar {
+                peer_str: "bob".to_string(),
+                item_str: String::new()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_multitroca() {
+        assert_eq!(parse_command("$multitroca"), Some(Command::MultiTrocas));
+        assert_eq!(
+            parse_command("$multitroca propor eu>bob:atum|1 bob>carol:salmão|2"),
+            Some(Command::MultiTrocaPropor {
+                legs_str: "eu>bob:atum|1 bob>carol:salmão|2".to_string()
+            })
+        );
+        assert_eq!(
+            parse_command("$multitroca aceitar bob-1"),
+            Some(Command::MultiTrocaVotar {
+                id: "bob-1".to_string(),
+                approve: true
+            })
+        );
+        assert_eq!(
+            parse_command("$multitroca recusar bob-1"),
+            Some(Command::MultiTrocaVotar {
+                id: "bob-1".to_string(),
+                approve: false
+            })
+        );
+        assert_eq!(
+            parse_command("$multitroca votar bob-1"),
+            Some(Command::Unknown("$multitroca votar bob-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_historico() {
+        assert_eq!(
+            parse_command("$historico"),
+            Some(Command::Historico {
+                peer: None,
+                fish: None
+            })
+        );
+        assert_eq!(
+            parse_command("$historico atum @bob"),
+            Some(Command::Historico {
+                peer: Some("bob".to_string()),
+                fish: Some("atum".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$historico exportar CSV trocas.csv"),
+            Some(Command::HistoricoExportar {
+                format: "csv".to_string(),
+                path: Some("trocas.csv".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn parse_exportar_importar() {
+        assert_eq!(
+            parse_command("$exportar JSON"),
+            Some(Command::Exportar {
+                format: "json".to_string(),
+                path: None
+            })
+        );
+        assert_eq!(
+            parse_command("$exportar csv minha-cesta.csv"),
+            Some(Command::Exportar {
+                format: "csv".to_string(),
+                path: Some("minha-cesta.csv".to_string())
+            })
+        );
+        assert_eq!(
+            parse_command("$importar cesta.json"),
+            Some(Command::Importar("cesta.json".to_string()))
+        );
+    }
+
     #[test]
     fn parse_quit_and_help() {
         assert_eq!(parse_command("$q"), Some(Command::Quit));
@@ -1,10 +1,22 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
 
 use async_channel::Sender;
 
 use crate::{
     AppState, Event,
-    server::{self, Peer, peerstore::PeerStore, protocol::Offer},
+    event::handlers::{
+        catch_report, inventory_showcase, match_orders, party_messages, schedule_tournament,
+        show_valuation, stake_to_string, warn_full_basket,
+    },
+    gameplay::{
+        self, ActiveBonus, Auction, Duel, Gift, MultiTrade, Order, OrderSide, Product, PublicOffer,
+        Recipe, RecipeKind, Search, TOURNAMENT_COUNTDOWN, TOURNAMENT_GRACE, Tournament, TradeRule,
+    },
+    server::{self, InventoryItem, Peer, peerstore::PeerStore, protocol::Offer},
     tui::{commands::Command, err, log},
 };
 
@@ -23,9 +35,11 @@ pub async fn handle_command(
         Command::List => {
             log("-- PESCADORES ONLINE --");
             for peer in peer_store.all_pears().await {
+                let tag = app_state.guilds.lock().tag(peer.username());
                 log(&format!(
-                    "> {} ({})",
+                    "> {}{} ({})",
                     peer.username(),
+                    tag,
                     peer_store
                         .get_by_username(peer.username())
                         .await
@@ -34,9 +48,40 @@ pub async fn handle_command(
                 ));
             }
         }
-        Command::Inventario(name) => {
+        Command::Inventario {
+            name,
+            raridade,
+            ordem,
+            busca,
+        } => {
+            let tier = match raridade.map(|r| gameplay::rarity_rank_from_name(&r)) {
+                None => None,
+                Some(Some(tier)) => Some(tier),
+                Some(None) => {
+                    err(&format!(
+                        "Raridade não existe, as disponíveis são: {}.",
+                        gameplay::RARITY_NAMES.join(", ")
+                    ));
+                    return;
+                }
+            };
+            let Ok(order) = ordem.map_or(Ok(gameplay::InventoryOrder::default()), |o| {
+                gameplay::InventoryOrder::from_str(&o)
+            }) else {
+                err("Ordem não existe, use quantidade, nome ou raridade.");
+                return;
+            };
+            let view = gameplay::InventoryView {
+                tier,
+                order,
+                search: busca.filter(|b| !b.is_empty()),
+            };
             if let Some(peer_name) = name {
                 if let Some(peer_info) = peer_store.get_by_username(&peer_name).await {
+                    app_state
+                        .inspections
+                        .lock()
+                        .insert(peer_info.peer.address(), view);
                     sender
                         .send(Event::UIMessage(server::FNP::InventoryInspection {
                             rem: my_peer.clone(),
@@ -49,6 +94,7 @@ pub async fn handle_command(
                 }
             } else {
                 // Se não há um peer como argumento, inspeciona o próprio inventário
+                app_state.inspections.lock().insert(my_peer.address(), view);
                 sender
                     .send(Event::UIMessage(server::FNP::InventoryInspection {
                         rem: my_peer.clone(),
@@ -72,65 +118,35 @@ pub async fn handle_command(
             if let Some(peer_info) = peer_store.get_by_username(&peer_str).await {
                 match Offer::from_str(&offer_str) {
                     Ok(parsed_offer) => {
-                        // Snapshots do inventario e do buffer, assim não bloqueamos os recursos
-                        let basket_snapshot = {
-                            let guard = app_state.basket.lock();
-                            guard.map().clone()
-                        };
-                        let offers_made = {
-                            let guard = app_state.offer_buffers.lock();
-                            guard.offers_made.clone()
-                        };
-
-                        // Calculamos quantos peixes de cada tipo estão em outras ofertas abertas
-                        let mut offered_quantities: std::collections::HashMap<String, u32> =
-                            std::collections::HashMap::new();
-                        for existing_offer in offers_made.values() {
-                            for item in &existing_offer.offered {
-                                *offered_quantities
-                                    .entry(item.fish_type.clone())
-                                    .or_insert(0) += item.quantity;
-                            }
-                        }
-
-                        let mut is_valid = true;
-                        // Validando a troca atual de acordo com a quantidade de peixes não
-                        // comprometida
-                        for item_to_offer in &parsed_offer.offered {
-                            let total_in_inventory = basket_snapshot
-                                .get(&item_to_offer.fish_type)
-                                .copied()
-                                .unwrap_or(0);
-                            let already_offered = offered_quantities
-                                .get(&item_to_offer.fish_type)
-                                .copied()
-                                .unwrap_or(0);
-                            let available = total_in_inventory.saturating_sub(already_offered);
-
-                            if available < item_to_offer.quantity {
-                                err(&format!(
-                                    "Você não tem peixes suficientes para a troca. (Disponível: {} {})",
-                                    available, item_to_offer.fish_type
-                                ));
-                                is_valid = false;
-                                break;
-                            }
+                        // Validando a troca atual de acordo com a quantidade de peixes e
+                        // moedas não comprometida em outras ofertas abertas
+                        if let Some((item, available)) =
+                            app_state.find_missing(&parsed_offer.offered, None)
+                        {
+                            err(&format!(
+                                "Você não tem itens suficientes para a troca. (Disponível: {} {})",
+                                available, item.fish_type
+                            ));
+                            return;
                         }
-                        if is_valid {
-                            app_state
-                                .offer_buffers
-                                .lock()
-                                .offers_made
-                                .insert(peer_info.peer.address(), parsed_offer.clone());
-                            sender
-                                .send(Event::UIMessage(server::FNP::TradeOffer {
-                                    rem: my_peer.clone(),
-                                    dest: peer_info.peer.clone(),
-                                    offer: parsed_offer,
-                                }))
-                                .await
-                                .ok();
+                        if !app_state.has_room(&parsed_offer.offered, &parsed_offer.requested) {
+                            warn_full_basket(&app_state);
+                            return;
                         }
+                        show_valuation(&app_state, &parsed_offer.requested, &parsed_offer.offered);
+                        app_state
+                            .offer_buffers
+                            .lock()
+                            .offers_made
+                            .insert(peer_info.peer.address(), parsed_offer.clone());
+                        sender
+                            .send(Event::UIMessage(server::FNP::TradeOffer {
+                                rem: my_peer.clone(),
+                                dest: peer_info.peer.clone(),
+                                offer: parsed_offer,
+                            }))
+                            .await
+                            .ok();
                     }
                     Err(_) => {
                         err("* Argumentos de oferta inválidos.");
@@ -140,6 +156,434 @@ pub async fn handle_command(
                 err("* Peer não encontrado.");
             }
         }
+        Command::Comparar(peer_str) => {
+            let Some(peer_info) = peer_store.get_by_username(&peer_str).await else {
+                err("* Peer não encontrado.");
+                return;
+            };
+            if peer_info.peer.address() == my_peer.address() {
+                err("Este comando não é válido para você mesmo");
+                return;
+            }
+            // A comparação é feita quando o inventário do peer chegar
+            app_state
+                .comparisons
+                .lock()
+                .insert(peer_info.peer.address());
+            log("Pedindo o inventário do peer para comparar...");
+            sender
+                .send(Event::UIMessage(server::FNP::InventoryInspection {
+                    rem: my_peer.clone(),
+                    dest: peer_info.peer.clone(),
+                }))
+                .await
+                .ok();
+        }
+        Command::Procurar { fish, hops } => {
+            if !app_state.fish_catalog.contains(&fish) {
+                err("* Peixe não encontrado no catálogo, use: $procurar <peixe> [saltos]");
+                return;
+            }
+            let hops = match hops.map(|h| h.parse::<u8>()) {
+                None => 0,
+                Some(Ok(h)) if h <= gameplay::MAX_SEARCH_HOPS => h,
+                Some(_) => {
+                    err(&format!(
+                        "* O número de saltos deve ir de 0 a {}.",
+                        gameplay::MAX_SEARCH_HOPS
+                    ));
+                    return;
+                }
+            };
+            let id = {
+                let mut searches = app_state.searches.lock();
+                let id = searches.ids.next(my_peer.username());
+                searches.searches.insert(
+                    id.clone(),
+                    Search {
+                        fish: fish.clone(),
+                        back: None,
+                        results: HashMap::new(),
+                    },
+                );
+                id
+            };
+            log(&format!(
+                "Procurando {} pela rede, resultado em {}s...",
+                fish,
+                gameplay::SEARCH_TIMEOUT.as_secs()
+            ));
+            crate::event::schedule(
+                sender.clone(),
+                gameplay::SEARCH_TIMEOUT,
+                Event::ProcuraFim(id.clone()),
+            );
+            sender
+                .send(Event::UIMessage(server::FNP::FishSearch {
+                    rem: my_peer.clone(),
+                    id,
+                    fish,
+                    hops,
+                }))
+                .await
+                .ok();
+        }
+        Command::Privacidade => {
+            let privacy = app_state.privacy.lock();
+            println!("-- PRIVACIDADE --");
+            log(&format!(
+                "Modo: {} ({})",
+                privacy.access,
+                privacy.access.description()
+            ));
+            if !privacy.hidden_tiers.is_empty() {
+                let hidden: Vec<&str> = privacy
+                    .hidden_tiers
+                    .iter()
+                    .map(|rank| gameplay::RARITY_NAMES[*rank as usize])
+                    .collect();
+                log(&format!("Raridades escondidas: {}", hidden.join(", ")));
+            }
+            let mut pending: Vec<&String> = privacy.pending.keys().collect();
+            pending.sort();
+            for name in pending {
+                log(&format!(
+                    "{} quer ver o seu inventário, '$privacidade permitir {}' ou '$privacidade negar {}'.",
+                    name, name, name
+                ));
+            }
+        }
+        Command::PrivacidadeAcesso(mode) => {
+            let Ok(access) = gameplay::InventoryAccess::from_str(&mode) else {
+                err(
+                    "Formato errado, o correto é:\n $privacidade [publico|amigos|perguntar | esconder <raridade> | mostrar <raridade> | permitir <peer> | negar <peer>]",
+                );
+                return;
+            };
+            app_state.privacy.lock().access = access;
+            log(&format!(
+                "Privacidade do inventário: {} ({}).",
+                access,
+                access.description()
+            ));
+        }
+        Command::PrivacidadeRaridade { tier_str, hidden } => {
+            let Some(tier) = gameplay::rarity_rank_from_name(&tier_str) else {
+                err(&format!(
+                    "Raridade não existe, as disponíveis são: {}.",
+                    gameplay::RARITY_NAMES.join(", ")
+                ));
+                return;
+            };
+            let mut privacy = app_state.privacy.lock();
+            let name = gameplay::RARITY_NAMES[tier as usize];
+            if hidden {
+                privacy.hidden_tiers.insert(tier);
+                log(&format!(
+                    "Peixes da raridade {} não aparecem mais para os peers.",
+                    name
+                ));
+            } else {
+                privacy.hidden_tiers.remove(&tier);
+                log(&format!(
+                    "Peixes da raridade {} voltam a aparecer para os peers.",
+                    name
+                ));
+            }
+        }
+        Command::PrivacidadeResponder { peer_str, allow } => {
+            let Some(peer) = app_state.privacy.lock().pending.remove(&peer_str) else {
+                err(&format!("* Nenhum pedido de inventário de {}.", peer_str));
+                return;
+            };
+            let reply = if allow {
+                log(&format!("Inventário mostrado para {}.", peer_str));
+                inventory_showcase(&app_state, my_peer.clone(), peer)
+            } else {
+                log(&format!("Pedido de {} negado.", peer_str));
+                server::FNP::InventoryDenied {
+                    rem: my_peer.clone(),
+                    dest: peer,
+                    reason: "o pedido foi recusado".to_string(),
+                }
+            };
+            sender.send(Event::UIMessage(reply)).await.ok();
+        }
+        Command::Amigos => {
+            let privacy = app_state.privacy.lock();
+            println!("-- AMIGOS --");
+            if privacy.friends.is_empty() {
+                log("[Lista vazia, use '$amigos add <peer>']");
+            }
+            for friend in &privacy.friends {
+                println!("> {}", friend);
+            }
+        }
+        Command::AmigoAdd(peer_str) => {
+            if peer_str.is_empty() {
+                err("Formato errado, o correto é:\n $amigos add <peer>");
+                return;
+            }
+            if !app_state.privacy.lock().friends.insert(peer_str.clone()) {
+                err(&format!("* {} já é seu amigo.", peer_str));
+                return;
+            }
+            log(&format!("{} agora é seu amigo.", peer_str));
+        }
+        Command::AmigoRemover(peer_str) => {
+            if !app_state.privacy.lock().friends.remove(&peer_str) {
+                err(&format!("* {} não está na sua lista de amigos.", peer_str));
+                return;
+            }
+            log(&format!("{} saiu da sua lista de amigos.", peer_str));
+        }
+        Command::Desejos => {
+            let wishlist = app_state.wishlist.lock();
+            println!("-- LISTA DE DESEJOS --");
+            if wishlist.mine.is_empty() {
+                log("[Lista vazia, use '$desejo add <peixe>']");
+            }
+            for fish in &wishlist.mine {
+                let style = app_state.fish_catalog.get_style_for_fish(fish);
+                println!("> {}", style.style(fish));
+            }
+            let mut others: Vec<_> = wishlist.others.values().collect();
+            others.sort_by(|a, b| a.0.username().cmp(b.0.username()));
+            for (peer, wanted) in others {
+                let wanted: Vec<&str> = wanted.iter().map(|f| f.as_str()).collect();
+                println!("{} quer: {}", peer.username(), wanted.join(", "));
+            }
+        }
+        Command::DesejoAdd(fish) | Command::DesejoRemover(fish)
+            if !app_state.fish_catalog.contains(&fish) =>
+        {
+            err("* Peixe não encontrado no catálogo, use: $desejo [add|remover] <peixe>");
+        }
+        Command::DesejoAdd(fish) => {
+            let fish_list = {
+                let mut wishlist = app_state.wishlist.lock();
+                if !wishlist.mine.insert(fish.clone()) {
+                    err("* Esse peixe já está na sua lista de desejos.");
+                    return;
+                }
+                wishlist.mine.iter().cloned().collect()
+            };
+            log(&format!(
+                "{} adicionado à lista de desejos, os peers vão avisar quando conseguirem um.",
+                fish
+            ));
+            sender
+                .send(Event::UIMessage(server::FNP::Wishlist {
+                    rem: my_peer.clone(),
+                    fish: fish_list,
+                }))
+                .await
+                .ok();
+        }
+        Command::DesejoRemover(fish) => {
+            let fish_list = {
+                let mut wishlist = app_state.wishlist.lock();
+                if !wishlist.mine.remove(&fish) {
+                    err("* Esse peixe não está na sua lista de desejos.");
+                    return;
+                }
+                wishlist.mine.iter().cloned().collect()
+            };
+            log(&format!("{} removido da lista de desejos.", fish));
+            sender
+                .send(Event::UIMessage(server::FNP::Wishlist {
+                    rem: my_peer.clone(),
+                    fish: fish_list,
+                }))
+                .await
+                .ok();
+        }
+        Command::Historico { peer, fish } => {
+            let history = app_state.history.lock();
+            let records = history.filter(peer.as_deref(), fish.as_deref());
+            println!("-- HISTÓRICO DE TROCAS --");
+            if records.is_empty() {
+                log("[Nenhuma troca registrada]");
+            }
+            let now = gameplay::now_secs();
+            for r in records {
+                let items = |items: &[InventoryItem]| {
+                    if items.is_empty() {
+                        return "nada".to_string();
+                    }
+                    items
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+                println!(
+                    "há {} [{}] {}: deu {} e recebeu {}",
+                    gameplay::format_elapsed(now.saturating_sub(r.time)),
+                    r.outcome,
+                    r.peer,
+                    items(&r.given),
+                    items(&r.received)
+                );
+            }
+        }
+        Command::HistoricoExportar { format, path } => {
+            let content = {
+                let history = app_state.history.lock();
+                let records = history.filter(None, None);
+                match format.as_str() {
+                    "csv" => gameplay::history_to_csv(&records),
+                    "json" => gameplay::history_to_json(&records),
+                    _ => {
+                        err(
+                            "Formato errado, o correto é:\n $historico exportar <csv|json> [arquivo]",
+                        );
+                        return;
+                    }
+                }
+            };
+            let path = path.unwrap_or_else(|| format!("trocas-{}.{}", my_peer.username(), format));
+            match std::fs::write(&path, content) {
+                Ok(()) => log(&format!("Histórico exportado para {}.", path)),
+                Err(e) => err(&format!("* Não foi possível exportar o histórico: {}", e)),
+            }
+        }
+        Command::Exportar { format, path } => {
+            let Some(content) = basket_export(&app_state, &format) else {
+                err("Formato errado, o correto é:\n $exportar <csv|json> [arquivo]");
+                return;
+            };
+            let path = path.unwrap_or_else(|| format!("cesta-{}.{}", my_peer.username(), format));
+            match std::fs::write(&path, content) {
+                Ok(()) => log(&format!("Cesta exportada para {}.", path)),
+                Err(e) => err(&format!("* Não foi possível exportar a cesta: {}", e)),
+            }
+        }
+        Command::Importar(path) => {
+            if !*app_state.sandbox.lock() {
+                err(
+                    "* Importar só é permitido no modo de teste (--sandbox): não há registro verificável das pescas para validar a cesta.",
+                );
+                return;
+            }
+            if path.is_empty() {
+                err("Formato errado, o correto é:\n $importar <arquivo>");
+                return;
+            }
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    err(&format!("* Não foi possível ler {}: {}", path, e));
+                    return;
+                }
+            };
+            let items = if path.ends_with(".json") {
+                gameplay::basket_from_json(&content)
+            } else {
+                gameplay::basket_from_csv(&content)
+            };
+            let (fish, coins) = match items.and_then(gameplay::split_coins) {
+                Ok(split) => split,
+                Err(e) => {
+                    err(&format!("* Cesta inválida: {}", e));
+                    return;
+                }
+            };
+            if let Some(unknown) = fish
+                .iter()
+                .find(|i| !app_state.fish_catalog.contains(&i.fish_type))
+            {
+                err(&format!("* Peixe desconhecido: {}", unknown.fish_type));
+                return;
+            }
+            let Some(total) = fish
+                .iter()
+                .try_fold(0u32, |sum, i| sum.checked_add(i.quantity))
+            else {
+                err("* Cesta inválida: peixes demais");
+                return;
+            };
+            let capacity = app_state.capacity();
+            if total > capacity {
+                err(&format!(
+                    "* A cesta importada tem {} peixes, mas só cabem {}.",
+                    total, capacity
+                ));
+                return;
+            }
+            {
+                let mut basket = app_state.basket.lock();
+                let map = basket.map_mut();
+                map.clear();
+                for item in &fish {
+                    *map.entry(item.fish_type.clone()).or_insert(0) += item.quantity;
+                }
+            }
+            *app_state.coins.lock() = coins;
+            log(&format!(
+                "Cesta importada de {}: {} peixe(s) e {} moedas.",
+                path, total, coins
+            ));
+        }
+        Command::Regras => {
+            let rules = app_state.rules.lock();
+            println!("-- REGRAS DE TROCA --");
+            for rule in gameplay::TRADE_RULES {
+                let state = if rules.enabled.contains(&rule) {
+                    "ligada"
+                } else {
+                    "desligada"
+                };
+                println!("> [{}] {} - {}", state, rule, rule.description());
+            }
+            if !rules.muted.is_empty() {
+                let muted: Vec<&str> = rules.muted.iter().map(|m| m.as_str()).collect();
+                println!("Silenciados: {}", muted.join(", "));
+            }
+            println!("-- DECISÕES AUTOMÁTICAS --");
+            if rules.log.is_empty() {
+                log("[Nenhuma oferta respondida pelas regras]");
+            }
+            let now = gameplay::now_secs();
+            for d in &rules.log {
+                println!(
+                    "há {}s: oferta de {} ({}) {} por {}",
+                    now.saturating_sub(d.time),
+                    d.peer,
+                    d.offer,
+                    if d.accepted { "aceita" } else { "recusada" },
+                    d.rule
+                );
+            }
+        }
+        Command::RegraAlternar(name) => {
+            let Ok(rule) = TradeRule::from_str(&name) else {
+                err("* Regra não existe, use '$regras' para ver as disponíveis.");
+                return;
+            };
+            let mut rules = app_state.rules.lock();
+            if rules.enabled.remove(&rule) {
+                log(&format!("Regra {} desligada.", rule));
+            } else {
+                rules.enabled.insert(rule);
+                log(&format!("Regra {} ligada: {}.", rule, rule.description()));
+            }
+        }
+        Command::Silenciar { peer_str, muted } => {
+            if peer_str.is_empty() {
+                err("Formato errado, o correto é:\n $regras <silenciar|liberar> <peer>");
+                return;
+            }
+            let mut rules = app_state.rules.lock();
+            if muted {
+                rules.muted.insert(peer_str.clone());
+                log(&format!("{} silenciado.", peer_str));
+            } else if rules.muted.remove(&peer_str) {
+                log(&format!("{} liberado.", peer_str));
+            } else {
+                err("* Esse peer não está silenciado.");
+            }
+        }
         Command::ConfirmTrade { resp, peer_str } => {
             if let Some(peer_info) = peer_store.get_by_username(&peer_str).await {
                 let opt_offer = {
@@ -167,29 +611,1718 @@ pub async fn handle_command(
                 err("* Peer não encontrado.");
             }
         }
-        Command::Quit => {
-            log("Encerrando fishnet, boa pescaria...");
-            std::process::exit(0);
+        Command::Dar { peer_str, item_str } => {
+            let item = match InventoryItem::from_str(&item_str) {
+                Ok(item) if item.quantity > 0 => item,
+                _ => {
+                    err(
+                        "Formato de presente errado, o correto é:\n $dar <peer> <peixe>|<quantidade>",
+                    );
+                    return;
+                }
+            };
+            let Some(peer_info) = peer_store.get_by_username(&peer_str).await else {
+                err("* Peer não encontrado.");
+                return;
+            };
+            if peer_info.peer.address() == my_peer.address() {
+                err("Este comando não é válido para você mesmo");
+                return;
+            }
+            // Peixes reservados em ofertas, leilões e afins não podem ser dados
+            if let Some((item, available)) =
+                app_state.find_missing(std::slice::from_ref(&item), None)
+            {
+                err(&format!(
+                    "Você não tem itens suficientes para o presente. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            // O item fica reservado até o peer confirmar que recebeu
+            let id = {
+                let mut gifts = app_state.gifts.lock();
+                let id = gifts.ids.next(my_peer.username());
+                gifts.pending.insert(
+                    id.clone(),
+                    Gift {
+                        id: id.clone(),
+                        to: peer_info.peer.clone(),
+                        item: item.clone(),
+                    },
+                );
+                id
+            };
+            log(&format!(
+                "Presente de {} enviado para {}, aguardando confirmação...",
+                item,
+                peer_info.peer.username()
+            ));
+            sender
+                .send(Event::UIMessage(server::FNP::Gift {
+                    rem: my_peer.clone(),
+                    dest: peer_info.peer,
+                    id,
+                    item,
+                }))
+                .await
+                .ok();
         }
-        Command::Help => {
-            log("fishnet 1.0.0");
-            log("Options:");
-            log("\t anything - Broadcast de mensagens para todos os peers conectados.");
-            log("\t @peer - Envia uma mensagem direta para um dado peer.");
-            log("\t $[l]istar - Lista todos os peers conectados a você.");
-            log("\t $[p]escar - Pesca um peixe aleatorio.");
-            log(
-                "\t $[i]nventario <peer> - Mostra o inventário do jogador, pode opcionalmente mostrar o inventário de um peer.",
+        Command::Duelos => {
+            let mut duels: Vec<Duel> = app_state.duels.lock().duels.values().cloned().collect();
+            duels.sort_by(|a, b| a.id.cmp(&b.id));
+            println!("-- DUELOS --");
+            if duels.is_empty() {
+                log(
+                    "Nenhum duelo em andamento. Use '$duelo <peer> [peixe|n]' para desafiar alguém.",
+                );
+            }
+            for duel in duels {
+                let state = match (duel.accepted, duel.challenger) {
+                    (true, _) => "revelando as sementes",
+                    (false, true) => "aguardando resposta",
+                    (false, false) => "aguardando você responder",
+                };
+                println!("> {} com {}: {}", duel.id, duel.opponent.username(), state);
+                if !duel.my_stake.is_empty() {
+                    log(&format!(
+                        "    Sua aposta: {}",
+                        stake_to_string(&duel.my_stake)
+                    ));
+                }
+                if !duel.their_stake.is_empty() {
+                    log(&format!(
+                        "    Aposta de {}: {}",
+                        duel.opponent.username(),
+                        stake_to_string(&duel.their_stake)
+                    ));
+                }
+            }
+        }
+        Command::Duelo {
+            peer_str,
+            stake_str,
+        } => {
+            let Some(stake) = parse_stake(stake_str) else {
+                err(
+                    "Formato de aposta errado, o correto é:\n $duelo <peer> [<peixe>|<quantidade>]",
+                );
+                return;
+            };
+            let Some(peer_info) = peer_store.get_by_username(&peer_str).await else {
+                err("* Peer não encontrado.");
+                return;
+            };
+            if peer_info.peer.address() == my_peer.address() {
+                err("Este comando não é válido para você mesmo");
+                return;
+            }
+            if app_state.duels.lock().with_peer(&peer_str).is_some() {
+                err(&format!("Você já tem um duelo com {}.", peer_str));
+                return;
+            }
+            if let Some((item, available)) = app_state.find_missing(&stake, None) {
+                err(&format!(
+                    "Você não tem itens suficientes para a aposta. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            // A nossa metade da semente fica guardada, o oponente só recebe o compromisso
+            let nonce: u64 = rand::random();
+            let id = {
+                let mut duels = app_state.duels.lock();
+                let id = duels.ids.next(my_peer.username());
+                duels.duels.insert(
+                    id.clone(),
+                    Duel {
+                        id: id.clone(),
+                        opponent: peer_info.peer.clone(),
+                        challenger: true,
+                        casts: gameplay::DUEL_CASTS,
+                        my_stake: stake.clone(),
+                        their_stake: Vec::new(),
+                        nonce,
+                        their_commitment: None,
+                        accepted: false,
+                        deadline: gameplay::now_secs() + gameplay::DUEL_TIMEOUT.as_secs(),
+                    },
+                );
+                id
+            };
+            log(&format!(
+                "Desafio enviado para {}, aguardando resposta por até {}s...",
+                peer_str,
+                gameplay::DUEL_TIMEOUT.as_secs()
+            ));
+            crate::event::schedule(
+                sender.clone(),
+                gameplay::DUEL_TIMEOUT,
+                Event::DueloFim(id.clone()),
             );
-            log(
-                "\t $[t]roca <peer> (peixe|quatidade,... > peixe|quantidade,...) - Envia uma oferta de troca para um peer.",
+            sender
+                .send(Event::UIMessage(server::FNP::DuelChallenge {
+                    rem: my_peer.clone(),
+                    dest: peer_info.peer,
+                    id,
+                    casts: gameplay::DUEL_CASTS,
+                    stake,
+                    commitment: gameplay::commitment(nonce),
+                }))
+                .await
+                .ok();
+        }
+        Command::Guilda => {
+            let guilds = app_state.guilds.lock();
+            if let Some(guild) = guilds.of_member(my_peer.username()) {
+                println!("-- GUILDA {} --", guild.name);
+                log(&format!(
+                    "Líder: {}, quórum de saque: {}",
+                    guild.leader,
+                    guild.effective_quorum()
+                ));
+                log(&format!(
+                    "Membros: {}",
+                    guild.members.iter().cloned().collect::<Vec<_>>().join(", ")
+                ));
+                if guild.basket.is_empty() {
+                    log("[Cesto vazio, use '$guilda depositar <peixe|n>']");
+                }
+                for (fish, quantity) in &guild.basket {
+                    let style = app_state.fish_catalog.get_style_for_fish(fish);
+                    println!("> {} x{}", style.style(fish), quantity);
+                }
+                for w in &guild.pending {
+                    log(&format!(
+                        "Saque {}: {} pediu {} ({} de {} aprovações)",
+                        w.id,
+                        w.requester,
+                        w.item,
+                        w.approvals.len(),
+                        guild.effective_quorum()
+                    ));
+                }
+                return;
+            }
+            if guilds.guilds.is_empty() {
+                log("Nenhuma guilda na rede. Crie uma com '$guilda criar <nome> [quórum]'.");
+                return;
+            }
+            println!("-- GUILDAS --");
+            let mut known: Vec<&gameplay::Guild> = guilds.guilds.values().collect();
+            known.sort_by(|a, b| a.name.cmp(&b.name));
+            for guild in known {
+                log(&format!(
+                    "> {} (líder: {}, {} membros)",
+                    guild.name,
+                    guild.leader,
+                    guild.members.len()
+                ));
+            }
+            log("Use '$guilda entrar <nome>' para pedir a entrada.");
+        }
+        Command::GuildaCriar { name, quorum_str } => {
+            let valid = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+            if name.is_empty() || !name.chars().all(valid) {
+                err("Formato errado, o correto é:
+ $guilda criar <nome> [quórum]");
+                return;
+            }
+            let quorum = match quorum_str.map(|s| s.parse::<u32>()) {
+                None => gameplay::GUILD_QUORUM,
+                Some(Ok(quorum)) if quorum > 0 => quorum,
+                Some(_) => {
+                    err("O quórum precisa ser um número maior que zero.");
+                    return;
+                }
+            };
+            let guild = {
+                let mut guilds = app_state.guilds.lock();
+                if let Some(guild) = guilds.of_member(my_peer.username()) {
+                    err(&format!("Você já é membro da guilda {}.", guild.name));
+                    return;
+                }
+                if guilds.guilds.contains_key(&name) {
+                    err(&format!("Já existe uma guilda chamada {}.", name));
+                    return;
+                }
+                let guild =
+                    gameplay::Guild::new(name.clone(), my_peer.username().to_string(), quorum);
+                guilds.guilds.insert(name.clone(), guild.clone());
+                guild
+            };
+            log(&format!(
+                "Guilda {} criada! Saques do cesto precisam de {} aprovações.",
+                name, quorum
+            ));
+            sender
+                .send(Event::UIMessage(crate::event::handlers::guild_state(
+                    my_peer.clone(),
+                    &guild,
+                )))
+                .await
+                .ok();
+        }
+        Command::GuildaPedido { name, action } => {
+            let Ok(action) = gameplay::GuildAction::from_str(&action) else {
+                err(
+                    "Formato errado, o correto é:
+ $guilda [criar <nome> [quórum] | entrar <nome> | sair | depositar <peixe|n> | sacar <peixe|n> | aprovar <id> | recusar <id> | quorum <n>]",
+                );
+                return;
+            };
+            let (name, leader) = {
+                let guilds = app_state.guilds.lock();
+                let guild = match &name {
+                    Some(name) => guilds.guilds.get(name),
+                    None => guilds.of_member(my_peer.username()),
+                };
+                match guild {
+                    Some(guild) => (guild.name.clone(), guild.leader.clone()),
+                    None if name.is_some() => {
+                        err("* Guilda não encontrada, veja as guildas com '$guilda'.");
+                        return;
+                    }
+                    None => {
+                        err("Você não é membro de nenhuma guilda.");
+                        return;
+                    }
+                }
+            };
+            match &action {
+                gameplay::GuildAction::Depositar(item) => {
+                    if gameplay::is_coin(&item.fish_type) {
+                        err("O cesto da guilda só guarda peixes.");
+                        return;
+                    }
+                    if let Some((item, available)) =
+                        app_state.find_missing(std::slice::from_ref(item), None)
+                    {
+                        err(&format!(
+                            "Você não tem itens suficientes. (Disponível: {} {})",
+                            available, item.fish_type
+                        ));
+                        return;
+                    }
+                }
+                gameplay::GuildAction::Sacar(item)
+                    if !app_state.has_room(&[], std::slice::from_ref(item)) =>
+                {
+                    err("Sua cesta está cheia, solte peixes com '$soltar' antes de sacar.");
+                    return;
+                }
+                gameplay::GuildAction::Quorum(_) if leader != my_peer.username() => {
+                    err(&format!("Só o líder da guilda, {}, muda o quórum.", leader));
+                    return;
+                }
+                gameplay::GuildAction::Votar { id, .. } => {
+                    let guilds = app_state.guilds.lock();
+                    let pending = guilds.guilds.get(&name).map(|g| &g.pending);
+                    if !pending.is_some_and(|p| p.iter().any(|w| &w.id == id)) {
+                        err(&format!("* Saque {} não encontrado.", id));
+                        return;
+                    }
+                }
+                gameplay::GuildAction::Entrar => {
+                    if let Some(guild) = app_state.guilds.lock().of_member(my_peer.username()) {
+                        err(&format!("Você já é membro da guilda {}.", guild.name));
+                        return;
+                    }
+                }
+                _ => {}
+            }
+            let dest = if leader == my_peer.username() {
+                my_peer.clone()
+            } else {
+                match peer_store.get_by_username(&leader).await {
+                    Some(info) => info.peer,
+                    None => {
+                        err(&format!("O líder da guilda, {}, não está online.", leader));
+                        return;
+                    }
+                }
+            };
+            let id = {
+                let mut guilds = app_state.guilds.lock();
+                let id = guilds.ids.next(my_peer.username());
+                // Votos e quórum não têm resposta, o estado novo da guilda já mostra o efeito
+                if !matches!(
+                    action,
+                    gameplay::GuildAction::Votar { .. } | gameplay::GuildAction::Quorum(_)
+                ) {
+                    guilds
+                        .requests
+                        .insert(id.clone(), (name.clone(), action.clone()));
+                }
+                id
+            };
+            if dest.address() != my_peer.address() {
+                log(&format!(
+                    "Pedido enviado para {}, líder da guilda {}.",
+                    leader, name
+                ));
+            }
+            sender
+                .send(Event::UIMessage(server::FNP::GuildRequest {
+                    rem: my_peer.clone(),
+                    dest,
+                    name,
+                    id,
+                    action,
+                }))
+                .await
+                .ok();
+        }
+        Command::Grupo => {
+            let Some(party) = app_state.party.lock().party.clone() else {
+                log("Você não está num grupo. Convide alguém com '$grupo convidar <peer>'.");
+                return;
+            };
+            println!("-- GRUPO --");
+            log(&format!("Líder: {}", party.leader));
+            for member in &party.members {
+                let tag = app_state.guilds.lock().tag(member);
+                println!("> {}{}", member, tag);
+            }
+            log(&format!(
+                "Bônus de {}% nas raridades acima de comum. Converse com '$gp <mensagem>'.",
+                party.bonus()
+            ));
+        }
+        Command::GrupoConvidar(peer_str) => {
+            let Some(peer_info) = peer_store.get_by_username(&peer_str).await else {
+                err("* Peer não encontrado.");
+                return;
+            };
+            if peer_info.peer.address() == my_peer.address() {
+                err("Este comando não é válido para você mesmo");
+                return;
+            }
+            let id = {
+                let mut book = app_state.party.lock();
+                let party_id = match &book.party {
+                    Some(party) if party.leader != my_peer.username() => {
+                        err(&format!("Só o líder do grupo, {}, convida.", party.leader));
+                        return;
+                    }
+                    Some(party) if party.members.contains(&peer_str) => {
+                        err(&format!("{} já está no grupo.", peer_str));
+                        return;
+                    }
+                    Some(party) if party.members.len() >= gameplay::PARTY_SIZE => {
+                        err(&format!(
+                            "O grupo está cheio, cabem {} pescadores.",
+                            gameplay::PARTY_SIZE
+                        ));
+                        return;
+                    }
+                    Some(party) => Some(party.id.clone()),
+                    None => None,
+                };
+                let id = party_id.unwrap_or_else(|| book.ids.next(my_peer.username()));
+                book.sent.insert(peer_str.clone(), id.clone());
+                id
+            };
+            log(&format!("Convite enviado para {}.", peer_str));
+            sender
+                .send(Event::UIMessage(server::FNP::PartyInvite {
+                    rem: my_peer.clone(),
+                    dest: peer_info.peer,
+                    id,
+                }))
+                .await
+                .ok();
+        }
+        Command::GrupoResponder { peer_str, accept } => {
+            let id = {
+                let mut book = app_state.party.lock();
+                if accept && book.party.is_some() {
+                    err("Você já está num grupo, saia dele com '$grupo sair'.");
+                    return;
+                }
+                let id = book.invites.remove(&peer_str);
+                if accept {
+                    book.accepted = id.clone().map(|id| (peer_str.clone(), id));
+                }
+                id
+            };
+            let Some(id) = id else {
+                err(&format!("* Nenhum convite de {}.", peer_str));
+                return;
+            };
+            let Some(peer_info) = peer_store.get_by_username(&peer_str).await else {
+                err("* Peer não encontrado.");
+                return;
+            };
+            if accept {
+                log(&format!("Convite aceito! Aguardando {}...", peer_str));
+            }
+            sender
+                .send(Event::UIMessage(server::FNP::PartyAnswer {
+                    rem: my_peer.clone(),
+                    dest: peer_info.peer,
+                    id,
+                    accepted: accept,
+                }))
+                .await
+                .ok();
+        }
+        Command::GrupoSair => {
+            let Some(party) = app_state.party.lock().party.take() else {
+                err("Você não está num grupo.");
+                return;
+            };
+            let make = |dest| server::FNP::PartyLeave {
+                rem: my_peer.clone(),
+                dest,
+                id: party.id.clone(),
+            };
+            for msg in party_messages(&peer_store, &party, my_peer.username(), make).await {
+                sender.send(Event::UIMessage(msg)).await.ok();
+            }
+            log("Você saiu do grupo.");
+        }
+        Command::GrupoFalar(content) => {
+            let Some(party) = app_state.party.lock().party.clone() else {
+                err("Você não está num grupo.");
+                return;
+            };
+            if content.is_empty() {
+                err("Formato errado, o correto é:\n $gp <mensagem>");
+                return;
+            }
+            let make = |dest| server::FNP::PartyMessage {
+                rem: my_peer.clone(),
+                dest,
+                id: party.id.clone(),
+                content: content.clone(),
+            };
+            for msg in party_messages(&peer_store, &party, my_peer.username(), make).await {
+                sender.send(Event::UIMessage(msg)).await.ok();
+            }
+        }
+        Command::DueloResponder {
+            peer_str,
+            accept,
+            stake_str,
+        } => {
+            let duel = app_state
+                .duels
+                .lock()
+                .with_peer(&peer_str)
+                .filter(|d| !d.challenger && !d.accepted)
+                .cloned();
+            let Some(mut duel) = duel else {
+                err(&format!(
+                    "Nenhum desafio de {} aguardando resposta.",
+                    peer_str
+                ));
+                return;
+            };
+            let answer = |accepted: bool, stake: Vec<InventoryItem>, commitment: u64| {
+                server::FNP::DuelAnswer {
+                    rem: my_peer.clone(),
+                    dest: duel.opponent.clone(),
+                    id: duel.id.clone(),
+                    accepted,
+                    stake,
+                    commitment,
+                }
+            };
+            if !accept {
+                app_state.duels.lock().duels.remove(&duel.id);
+                log(&format!("Você recusou o duelo com {}.", peer_str));
+                sender
+                    .send(Event::UIMessage(answer(false, Vec::new(), 0)))
+                    .await
+                    .ok();
+                return;
+            }
+            let Some(stake) = parse_stake(stake_str) else {
+                err(
+                    "Formato de aposta errado, o correto é:\n $duelo aceitar <peer> [<peixe>|<quantidade>]",
+                );
+                return;
+            };
+            if let Some((item, available)) = app_state.find_missing(&stake, None) {
+                err(&format!(
+                    "Você não tem itens suficientes para a aposta. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            if !app_state.has_room(&[], &duel.their_stake) {
+                warn_full_basket(&app_state);
+                return;
+            }
+            let msg = answer(true, stake.clone(), gameplay::commitment(duel.nonce));
+            duel.my_stake = stake;
+            duel.accepted = true;
+            // O desafiante tem um prazo inteiro, a partir do aceite, para revelar a semente
+            duel.deadline = gameplay::now_secs() + gameplay::DUEL_TIMEOUT.as_secs();
+            crate::event::schedule(
+                sender.clone(),
+                gameplay::DUEL_TIMEOUT,
+                Event::DueloFim(duel.id.clone()),
             );
-            log("\t $[c]onfirmar <s|n> <peer> - Pedido de confirmação de troca");
-            log("\t $[q]uit - Encerra o programa.");
-            log("\t $[h]elp - Mostra essa mensagem de ajuda.");
+            app_state.duels.lock().duels.insert(duel.id.clone(), duel);
+            log(&format!(
+                "Duelo aceito! Aguardando {} revelar a semente...",
+                peer_str
+            ));
+            sender.send(Event::UIMessage(msg)).await.ok();
         }
-        Command::Unknown(unk) => {
-            err(&format!("Comando ({}) não existe", unk));
+        Command::MultiTrocas => {
+            let mut trades: Vec<MultiTrade> = app_state
+                .multitrades
+                .lock()
+                .trades
+                .values()
+                .cloned()
+                .collect();
+            trades.sort_by(|a, b| a.id.cmp(&b.id));
+            println!("-- MULTITROCAS --");
+            if trades.is_empty() {
+                log("[Nenhuma multitroca em andamento, use '$multitroca propor <pernas...>']");
+            }
+            for t in trades {
+                println!(
+                    "> [{}] coordenada por {}, aprovada por {}/{}",
+                    t.id,
+                    t.coordinator.username(),
+                    t.approvals.len(),
+                    t.participants().len()
+                );
+                for leg in &t.legs {
+                    let items: Vec<String> = leg.items.iter().map(|i| i.to_string()).collect();
+                    let approved = if t.approvals.contains(&leg.from) {
+                        " (aprovado)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "    {} entrega {} para {}{}",
+                        leg.from,
+                        items.join(","),
+                        leg.to,
+                        approved
+                    );
+                }
+            }
         }
+        Command::MultiTrocaPropor { legs_str } => {
+            let Ok(legs) = gameplay::parse_legs(&legs_str) else {
+                err(
+                    "Formato de multitroca errado, o correto é:\n $multitroca propor de>para:peixe|n+peixe|n de>para:peixe|n ...",
+                );
+                return;
+            };
+            let mut trade = MultiTrade {
+                id: String::new(),
+                coordinator: my_peer.clone(),
+                legs,
+                approvals: BTreeSet::from([my_peer.username().to_string()]),
+                reserved: Vec::new(),
+                peers: Vec::new(),
+                mine: true,
+            };
+            let participants = trade.participants();
+            if participants.len() < 3 {
+                err(
+                    "* Uma multitroca precisa de pelo menos três participantes, use '$t' para trocar com um peer.",
+                );
+                return;
+            }
+            if !participants.contains(my_peer.username()) {
+                err("* Você precisa participar da multitroca que propõe.");
+                return;
+            }
+            for name in participants.iter().filter(|p| *p != my_peer.username()) {
+                let Some(peer_info) = peer_store.get_by_username(name).await else {
+                    err(&format!("* Peer {} não encontrado.", name));
+                    return;
+                };
+                trade.peers.push(peer_info.peer);
+            }
+            let gives = trade.gives(my_peer.username());
+            if let Some((item, available)) = app_state.find_missing(&gives, None) {
+                err(&format!(
+                    "Você não tem itens suficientes para a multitroca. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            if !app_state.has_room(&gives, &trade.receives(my_peer.username())) {
+                warn_full_basket(&app_state);
+                return;
+            }
+            show_valuation(&app_state, &trade.receives(my_peer.username()), &gives);
+            // Quem propõe já aprova, então reserva agora o que vai entregar
+            trade.reserved = gives;
+            {
+                let mut multitrades = app_state.multitrades.lock();
+                trade.id = multitrades.ids.next(my_peer.username());
+                multitrades.trades.insert(trade.id.clone(), trade.clone());
+            }
+            log(&format!(
+                "Multitroca {} proposta, aguardando a aprovação de todos por até {}s...",
+                trade.id,
+                gameplay::MULTI_TRADE_TIMEOUT.as_secs()
+            ));
+            crate::event::schedule(
+                sender.clone(),
+                gameplay::MULTI_TRADE_TIMEOUT,
+                Event::MultiTrocaFim(trade.id.clone()),
+            );
+            for peer in trade.peers {
+                sender
+                    .send(Event::UIMessage(server::FNP::MultiTradePropose {
+                        rem: my_peer.clone(),
+                        dest: peer,
+                        id: trade.id.clone(),
+                        legs: trade.legs.clone(),
+                    }))
+                    .await
+                    .ok();
+            }
+        }
+        Command::MultiTrocaVotar { id, approve } => {
+            let Some(trade) = app_state.multitrades.lock().trades.get(&id).cloned() else {
+                err("* Multitroca não encontrada, use '$multitroca' para ver as em andamento.");
+                return;
+            };
+            if trade.mine {
+                if approve {
+                    err("* Você propôs esta multitroca, ela já tem a sua aprovação.");
+                    return;
+                }
+                // O coordenador desiste: todos são avisados que a troca foi abortada
+                app_state.multitrades.lock().trades.remove(&id);
+                log(&format!("Multitroca {} cancelada.", id));
+                for peer in trade.peers {
+                    sender
+                        .send(Event::UIMessage(server::FNP::MultiTradeCommit {
+                            rem: my_peer.clone(),
+                            dest: peer,
+                            id: id.clone(),
+                            commit: false,
+                        }))
+                        .await
+                        .ok();
+                }
+                return;
+            }
+            if approve {
+                if trade.approvals.contains(my_peer.username()) {
+                    err("* Você já aprovou esta multitroca.");
+                    return;
+                }
+                let gives = trade.gives(my_peer.username());
+                if let Some((item, available)) = app_state.find_missing(&gives, None) {
+                    err(&format!(
+                        "Você não tem itens suficientes para a multitroca. (Disponível: {} {})",
+                        available, item.fish_type
+                    ));
+                    return;
+                }
+                if !app_state.has_room(&gives, &trade.receives(my_peer.username())) {
+                    warn_full_basket(&app_state);
+                    return;
+                }
+                // O que entregamos fica reservado até o coordenador efetivar ou abortar
+                if let Some(t) = app_state.multitrades.lock().trades.get_mut(&id) {
+                    t.reserved = gives;
+                    t.approvals.insert(my_peer.username().to_string());
+                }
+                log(&format!(
+                    "Multitroca {} aprovada, aguardando os demais participantes...",
+                    id
+                ));
+            } else {
+                app_state.multitrades.lock().trades.remove(&id);
+                log(&format!("Multitroca {} recusada.", id));
+            }
+            sender
+                .send(Event::UIMessage(server::FNP::MultiTradeVote {
+                    rem: my_peer.clone(),
+                    dest: trade.coordinator,
+                    id,
+                    voter: my_peer.username().to_string(),
+                    approve,
+                }))
+                .await
+                .ok();
+        }
+        Command::TorneioIniciar { duracao, raridade } => {
+            let Some(duration) = gameplay::parse_duration(&duracao) else {
+                err("Formato de torneio errado, o correto é:\n $torneio iniciar 10m [raridade]");
+                return;
+            };
+            let min_tier = match raridade {
+                Some(r) => match gameplay::rarity_rank_from_name(&r) {
+                    Some(rank) => rank,
+                    None => {
+                        err(&format!("* Raridade ({}) não existe.", r));
+                        return;
+                    }
+                },
+                None => 0,
+            };
+            let tournament = {
+                let mut current = app_state.tournament.lock();
+                if current.as_ref().is_some_and(|t| !t.finished) {
+                    err("* Já existe um torneio em andamento.");
+                    return;
+                }
+                let start = gameplay::now_secs() + TOURNAMENT_COUNTDOWN;
+                let Some(end) = start.checked_add(duration.as_secs()) else {
+                    err("* Duração longa demais.");
+                    return;
+                };
+                let mut tournament = Tournament::new(
+                    format!("{}-{}", my_peer.username(), start),
+                    my_peer.clone(),
+                    start,
+                    end,
+                    min_tier,
+                );
+                // O organizador sempre participa do próprio torneio
+                tournament.joined = true;
+                tournament.record_score(my_peer.username(), 0);
+                *current = Some(tournament.clone());
+                tournament
+            };
+            log(&format!(
+                "Torneio {} anunciado, começa em {}s!",
+                tournament.id, TOURNAMENT_COUNTDOWN
+            ));
+            schedule_tournament(&tournament, sender.clone(), TOURNAMENT_GRACE);
+            sender
+                .send(Event::UIMessage(server::FNP::TournamentAnnounce {
+                    rem: my_peer.clone(),
+                    id: tournament.id,
+                    start: tournament.start,
+                    end: tournament.end,
+                    min_tier: tournament.min_tier,
+                }))
+                .await
+                .ok();
+        }
+        Command::TorneioEntrar => {
+            let join_msg = {
+                let mut current = app_state.tournament.lock();
+                let Some(t) = current.as_mut().filter(|t| !t.finished) else {
+                    err("* Nenhum torneio em andamento.");
+                    return;
+                };
+                if t.joined {
+                    err("* Você já está participando deste torneio.");
+                    return;
+                }
+                t.joined = true;
+                server::FNP::TournamentJoin {
+                    rem: my_peer.clone(),
+                    dest: t.organizer.clone(),
+                    id: t.id.clone(),
+                }
+            };
+            log("Você entrou no torneio, boa pescaria!");
+            sender.send(Event::UIMessage(join_msg)).await.ok();
+        }
+        Command::TorneioStatus => {
+            let Some(t) = app_state.tournament.lock().clone() else {
+                log("Nenhum torneio anunciado. Use '$torneio iniciar <duração> [raridade]'.");
+                return;
+            };
+            let now = gameplay::now_secs();
+            println!("-- TORNEIO {} --", t.id);
+            log(&format!("Organizador: {}", t.organizer.username()));
+            log(&format!(
+                "Raridade mínima: {}",
+                gameplay::RARITY_NAMES[t.min_tier as usize]
+            ));
+            if t.finished || now >= t.end {
+                log("Situação: encerrado");
+            } else if now < t.start {
+                log(&format!("Situação: começa em {}s", t.start - now));
+            } else {
+                log(&format!("Situação: termina em {}s", t.end - now));
+            }
+            if t.joined {
+                log(&format!("Sua pontuação: {}", t.my_score));
+            }
+            for (name, score) in t.standings() {
+                println!("> {} - {} ponto(s)", name, score);
+            }
+        }
+        Command::Missoes => {
+            let quests = {
+                let mut quests = app_state.quests.lock();
+                quests.refresh(gameplay::today(), &app_state.fish_catalog);
+                quests.clone()
+            };
+            println!("-- MISSÕES DO DIA --");
+            for quest in &quests.quests {
+                let mark = if quest.is_done() { "x" } else { " " };
+                println!(
+                    "[{}] {} ({}/{})",
+                    mark,
+                    quest.goal.description(),
+                    quest.progress,
+                    quest.goal.target()
+                );
+                log(&format!("    Recompensa: {}", quest.reward.description()));
+            }
+            log(&format!("Iscas disponíveis: {}", app_state.baits.lock()));
+        }
+        Command::Soltar { fish, quantity } => {
+            let Ok(quantity) = quantity.as_deref().unwrap_or("1").parse::<u32>() else {
+                err("Formato errado, o correto é:\n $soltar peixe [quantidade]");
+                return;
+            };
+            if fish.is_empty() || quantity == 0 || gameplay::is_coin(&fish) {
+                err("Formato errado, o correto é:\n $soltar peixe [quantidade]");
+                return;
+            }
+            // Peixes reservados em ofertas abertas não podem ser soltos
+            let available = app_state.available(&fish, None);
+            if available < quantity || !app_state.basket.lock().take(&fish, quantity) {
+                err(&format!(
+                    "Você não tem peixes suficientes para soltar. (Disponível: {} {})",
+                    available, fish
+                ));
+                return;
+            }
+            let style = app_state.fish_catalog.get_style_for_fish(&fish);
+            println!(
+                "Você soltou {} {}(s) de volta na água.",
+                quantity,
+                style.style(&fish)
+            );
+            log(&format!(
+                "Cesta: {}/{} peixes",
+                app_state.basket.lock().total(),
+                app_state.capacity()
+            ));
+        }
+        Command::Aquario => {
+            println!("-- AQUÁRIOS --");
+            log(&format!(
+                "Cesta: {}/{} peixes ({} aquário(s))",
+                app_state.basket.lock().total(),
+                app_state.capacity(),
+                app_state.aquariums.lock()
+            ));
+            log(&format!(
+                "Cada aquário dá +{} de espaço. Compre com '$aquario comprar' por {} moeda(s) ou faça com '$craft aquario'.",
+                gameplay::AQUARIUM_CAPACITY,
+                gameplay::AQUARIUM_PRICE
+            ));
+        }
+        Command::AquarioComprar => {
+            let available = app_state.available(gameplay::COIN_ITEM, None);
+            if available < gameplay::AQUARIUM_PRICE {
+                err(&format!(
+                    "Você não tem moedas suficientes. (Disponível: {}, preço: {})",
+                    available,
+                    gameplay::AQUARIUM_PRICE
+                ));
+                return;
+            }
+            {
+                let mut coins = app_state.coins.lock();
+                *coins = coins.saturating_sub(gameplay::AQUARIUM_PRICE);
+            }
+            *app_state.aquariums.lock() += 1;
+            log(&format!(
+                "Você comprou um aquário, agora cabem {} peixes na cesta.",
+                app_state.capacity()
+            ));
+        }
+        Command::Cardume => {
+            let now = gameplay::now_millis();
+            let shoals: Vec<gameplay::Shoal> = app_state.shoals.lock().open(now).cloned().collect();
+            if shoals.is_empty() {
+                log("Nenhum cardume à vista. Eles aparecem de vez em quando ao pescar.");
+                return;
+            }
+            for shoal in shoals {
+                let style = app_state.fish_catalog.get_style_for_fish(&shoal.fish);
+                println!("-- CARDUME {} --", shoal.id);
+                log(&format!(
+                    "Peixe: {}, avistado por {}",
+                    style.style(&shoal.fish),
+                    shoal.spawner
+                ));
+                log(&format!(
+                    "Vagas: {}, fecha em {}s",
+                    shoal.slots,
+                    (shoal.end * 1000).saturating_sub(now) / 1000
+                ));
+                for (position, (name, _)) in shoal.ranking().iter().enumerate() {
+                    println!("> {}º {}", position + 1, name);
+                }
+            }
+        }
+        Command::Clima => {
+            let now = gameplay::now_secs();
+            let environment = app_state.environment.lock().clone();
+            let weather = environment.weather_at(now);
+            let time = gameplay::Environment::time_of_day(now);
+            println!("-- CLIMA --");
+            log(&format!("Agora: {} ({})", weather, weather.description()));
+            log(&format!("Período: {} ({})", time, time.description()));
+            for (fish, condition) in gameplay::EXCLUSIVE_SPECIES {
+                let unlocked = condition == gameplay::Condition::Weather(weather)
+                    || condition == gameplay::Condition::Time(time);
+                if unlocked {
+                    let style = app_state.fish_catalog.get_style_for_fish(fish);
+                    println!("> {} pode ser pescado agora!", style.style(&fish));
+                }
+            }
+            // O cronograma é determinístico, então dá para prever os próximos períodos
+            let slot = gameplay::WEATHER_SLOT.as_secs();
+            log("Previsão:");
+            for next in 1..=4 {
+                let start = (now / slot + next) * slot;
+                log(&format!(
+                    "    em {}: {}",
+                    gameplay::format_elapsed(start - now),
+                    environment.weather_at(start)
+                ));
+            }
+        }
+        Command::Escassez(Some(enabled)) => {
+            let report = {
+                let mut scarcity = app_state.scarcity.lock();
+                scarcity.enabled = enabled;
+                scarcity.refresh(gameplay::now_secs());
+                if enabled {
+                    catch_report(my_peer.clone(), &scarcity)
+                } else {
+                    // Um relato vazio tira as nossas pescas da conta dos outros peers
+                    server::FNP::CatchReport {
+                        rem: my_peer.clone(),
+                        catches: Vec::new(),
+                    }
+                }
+            };
+            log(&format!(
+                "Modo de escassez {}.",
+                if enabled { "ligado" } else { "desligado" }
+            ));
+            sender.send(Event::UIMessage(report)).await.ok();
+        }
+        Command::Escassez(None) => {
+            let (enabled, totals, weights) = {
+                let mut scarcity = app_state.scarcity.lock();
+                scarcity.refresh(gameplay::now_secs());
+                (
+                    scarcity.enabled,
+                    scarcity.totals(),
+                    scarcity.weights(&app_state.fish_catalog),
+                )
+            };
+            println!("-- ESCASSEZ --");
+            log(&format!(
+                "Modo {}, pescas dos últimos {} min na rede.",
+                if enabled { "ligado" } else { "desligado" },
+                gameplay::SCARCITY_WINDOW.as_secs() / 60
+            ));
+            let mut weights: Vec<(String, u32)> = weights.into_iter().collect();
+            weights.sort_by_key(|(fish, weight)| (*weight, fish.clone()));
+            if weights.is_empty() {
+                log("[Nenhuma espécie escassa no momento]");
+            }
+            for (fish, weight) in weights {
+                let style = app_state.fish_catalog.get_style_for_fish(&fish);
+                println!(
+                    "> {}: {} pesca(s), chance x{}.{:02}",
+                    style.style(&fish),
+                    totals.get(&fish).unwrap_or(&0),
+                    weight / 100,
+                    weight % 100
+                );
+            }
+            if !enabled {
+                log("Use '$escassez ligar' para que estes pesos valham nas suas pescas.");
+            }
+        }
+        Command::Receita { cook, recipe, fish } => {
+            let kind = if cook {
+                RecipeKind::Cook
+            } else {
+                RecipeKind::Craft
+            };
+            let Some(name) = recipe else {
+                println!(
+                    "{}",
+                    if cook {
+                        "-- COZINHA --"
+                    } else {
+                        "-- BANCADA --"
+                    }
+                );
+                for r in gameplay::RECIPES.iter().filter(|r| r.kind == kind) {
+                    println!("> {}: {}", r.name, r.ingredients());
+                    log(&format!("    Produz: {}", r.product.description()));
+                }
+                return;
+            };
+            let Some(recipe) = Recipe::find(kind, &name) else {
+                err(&format!(
+                    "* Receita não existe, use '{}' para ver as disponíveis.",
+                    if cook { "$cozinhar" } else { "$craft" }
+                ));
+                return;
+            };
+            // Sem peixe escolhido, usamos o ingrediente que mais temos disponível
+            let fish = match fish {
+                Some(fish) => fish,
+                None => {
+                    let basket: Vec<String> =
+                        app_state.basket.lock().map().keys().cloned().collect();
+                    let best = basket
+                        .into_iter()
+                        .filter(|f| recipe.accepts(&app_state.fish_catalog, f))
+                        .max_by_key(|f| app_state.available(f, None));
+                    let Some(best) = best else {
+                        err(&format!("* Você não tem {}.", recipe.ingredients()));
+                        return;
+                    };
+                    best
+                }
+            };
+            if !recipe.accepts(&app_state.fish_catalog, &fish) {
+                err(&format!(
+                    "* A receita {} pede {}.",
+                    recipe.name,
+                    recipe.ingredients()
+                ));
+                return;
+            }
+            // Peixes reservados em ofertas abertas não podem ser usados
+            let available = app_state.available(&fish, None);
+            if available < recipe.quantity {
+                err(&format!(
+                    "Você não tem peixes suficientes para a receita. (Disponível: {} {}, precisa de {})",
+                    available, fish, recipe.quantity
+                ));
+                return;
+            }
+            if !app_state.basket.lock().take(&fish, recipe.quantity) {
+                err("* Os peixes da receita não estão mais na cesta.");
+                return;
+            }
+            let style = app_state.fish_catalog.get_style_for_fish(&fish);
+            println!(
+                "Você usou {} {}(s) para fazer {}.",
+                recipe.quantity,
+                style.style(&fish),
+                recipe.name
+            );
+            match recipe.product {
+                Product::Baits(n) => {
+                    *app_state.baits.lock() += n;
+                    log(&format!("+ {} isca(s)", n));
+                }
+                Product::RareBaits(n) => {
+                    app_state.consumables.lock().rare_baits += n;
+                    log(&format!("+ {} isca(s) rara(s)", n));
+                }
+                Product::FishChance { rank, percent } => {
+                    if rand::random::<u32>() % 100 < percent {
+                        let prize = gameplay::fishing_from_rank(&app_state.fish_catalog, rank);
+                        *app_state
+                            .basket
+                            .lock()
+                            .map_mut()
+                            .entry(prize.clone())
+                            .or_insert(0) += 1;
+                        let style = app_state.fish_catalog.get_style_for_fish(&prize);
+                        println!("Sorte! Você ganhou um(a) {}!", style.style(&prize));
+                    } else {
+                        log("Não foi dessa vez, nenhum peixe saiu da receita.");
+                    }
+                }
+                Product::Aquariums(n) => {
+                    *app_state.aquariums.lock() += n;
+                    log(&format!(
+                        "+ {} aquário(s), agora cabem {} peixes na cesta.",
+                        n,
+                        app_state.capacity()
+                    ));
+                }
+                Product::Bonus { rolls, duration } => {
+                    // Comer um prato novo substitui o bônus do anterior
+                    let replaced = app_state.consumables.lock().bonus.replace(ActiveBonus {
+                        dish: recipe.name.to_string(),
+                        rolls,
+                        until: gameplay::now_secs().saturating_add(duration.as_secs()),
+                    });
+                    if replaced.is_some_and(|b| b.until > gameplay::now_secs()) {
+                        crate::tui::warn("O bônus do prato anterior foi substituído.");
+                    }
+                    log(&format!(
+                        "Você comeu {}: {}.",
+                        recipe.name,
+                        recipe.product.description()
+                    ));
+                }
+            }
+        }
+        Command::Vender { fish, quantity } => {
+            let Some(fish) = fish else {
+                println!("-- MERCADO --");
+                for (rank, price) in gameplay::RARITY_PRICES.iter().enumerate() {
+                    log(&format!(
+                        "> {}: {} moeda(s)",
+                        gameplay::RARITY_NAMES[rank],
+                        price
+                    ));
+                }
+                log(&format!("Suas moedas: {}", app_state.coins.lock()));
+                return;
+            };
+            let Ok(quantity) = quantity.as_deref().unwrap_or("1").parse::<u32>() else {
+                err("Formato de venda errado, o correto é:\n $vender peixe [quantidade]");
+                return;
+            };
+            if quantity == 0 || gameplay::is_coin(&fish) {
+                err("Formato de venda errado, o correto é:\n $vender peixe [quantidade]");
+                return;
+            }
+            // Peixes reservados em ofertas abertas não podem ser vendidos
+            let available = app_state.available(&fish, None);
+            if available < quantity {
+                err(&format!(
+                    "Você não tem peixes suficientes para vender. (Disponível: {} {})",
+                    available, fish
+                ));
+                return;
+            }
+            let Some(price) =
+                gameplay::sell_price(&app_state.fish_catalog, &fish).checked_mul(quantity)
+            else {
+                err("* Quantidade grande demais para vender de uma vez.");
+                return;
+            };
+            {
+                let mut basket = app_state.basket.lock();
+                if let Some(count) = basket.map_mut().get_mut(&fish) {
+                    *count -= quantity;
+                    if *count == 0 {
+                        basket.map_mut().remove(&fish);
+                    }
+                }
+            }
+            let total = {
+                let mut coins = app_state.coins.lock();
+                *coins = coins.saturating_add(price);
+                *coins
+            };
+            let style = app_state.fish_catalog.get_style_for_fish(&fish);
+            println!(
+                "Você vendeu {} {}(s) por {} moeda(s).",
+                quantity,
+                style.style(&fish),
+                price
+            );
+            log(&format!("Suas moedas: {}", total));
+        }
+        Command::LeilaoListar => {
+            let mut auctions: Vec<Auction> = app_state
+                .auctions
+                .lock()
+                .auctions
+                .values()
+                .cloned()
+                .collect();
+            auctions.sort_by_key(|a| a.end);
+            println!("-- LEILÕES ABERTOS --");
+            if auctions.is_empty() {
+                log("[Nenhum leilão aberto, use '$leilao peixe|n <lance mínimo> <duração>']");
+            }
+            let now = gameplay::now_secs();
+            for a in auctions {
+                let style = app_state.fish_catalog.get_style_for_fish(&a.item.fish_type);
+                let highest = match &a.highest {
+                    Some((bidder, amount)) => format!("{} moeda(s) de {}", amount, bidder),
+                    None => format!("sem lances (mínimo {})", a.min_bid),
+                };
+                println!(
+                    "> [{}] {} {}(s) de {} - {} - termina em {}s",
+                    a.id,
+                    a.item.quantity,
+                    style.style(&a.item.fish_type),
+                    a.seller.username(),
+                    highest,
+                    a.end.saturating_sub(now)
+                );
+            }
+        }
+        Command::LeilaoCriar {
+            item_str,
+            min_bid,
+            duracao,
+        } => {
+            let usage =
+                "Formato de leilão errado, o correto é:\n $leilao peixe|n <lance mínimo> <duração>";
+            let (Ok(item), Ok(min_bid), Some(duration)) = (
+                InventoryItem::from_str(&item_str),
+                min_bid.parse::<u32>(),
+                gameplay::parse_duration(&duracao),
+            ) else {
+                err(usage);
+                return;
+            };
+            if item.quantity == 0 || gameplay::is_coin(&item.fish_type) {
+                err(usage);
+                return;
+            }
+            let Some(end) = gameplay::now_secs().checked_add(duration.as_secs()) else {
+                err("* Duração longa demais.");
+                return;
+            };
+            let available = app_state.available(&item.fish_type, None);
+            if available < item.quantity {
+                err(&format!(
+                    "Você não tem peixes suficientes para leiloar. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            let auction = {
+                let mut auctions = app_state.auctions.lock();
+                let auction = Auction {
+                    id: auctions.ids.next(my_peer.username()),
+                    seller: my_peer.clone(),
+                    item,
+                    min_bid,
+                    end,
+                    highest: None,
+                    bids: Vec::new(),
+                    mine: true,
+                    my_bid: 0,
+                };
+                auctions
+                    .auctions
+                    .insert(auction.id.clone(), auction.clone());
+                auction
+            };
+            log(&format!(
+                "Leilão {} aberto por {}s, o peixe fica reservado até o fim.",
+                auction.id,
+                duration.as_secs()
+            ));
+            crate::event::schedule(
+                sender.clone(),
+                duration,
+                Event::LeilaoFim(auction.id.clone()),
+            );
+            sender
+                .send(Event::UIMessage(server::FNP::AuctionAnnounce {
+                    rem: my_peer.clone(),
+                    id: auction.id,
+                    item: auction.item,
+                    min_bid: auction.min_bid,
+                    end: auction.end,
+                }))
+                .await
+                .ok();
+        }
+        Command::Lance { id, valor } => {
+            let Ok(amount) = valor.parse::<u32>() else {
+                err("Formato de lance errado, o correto é:\n $lance <leilão> <valor>");
+                return;
+            };
+            let bid_msg = {
+                let Some(auction) = app_state.auctions.lock().auctions.get(&id).cloned() else {
+                    err("* Leilão não encontrado.");
+                    return;
+                };
+                if auction.mine {
+                    err("* Você não pode dar lances no seu próprio leilão.");
+                    return;
+                }
+                if amount < auction.next_min_bid() {
+                    err(&format!(
+                        "* O lance deve ser de pelo menos {} moeda(s).",
+                        auction.next_min_bid()
+                    ));
+                    return;
+                }
+                if !app_state.has_room(&[], std::slice::from_ref(&auction.item)) {
+                    warn_full_basket(&app_state);
+                    return;
+                }
+                // As moedas do nosso lance atual neste leilão podem ser reaproveitadas
+                let available = app_state
+                    .available(gameplay::COIN_ITEM, None)
+                    .saturating_add(auction.my_bid);
+                if available < amount {
+                    err(&format!(
+                        "Você não tem moedas suficientes. (Disponível: {})",
+                        available
+                    ));
+                    return;
+                }
+                // Reservamos as moedas até o vendedor anunciar que fomos superados
+                if let Some(a) = app_state.auctions.lock().auctions.get_mut(&id) {
+                    a.my_bid = amount;
+                }
+                server::FNP::AuctionBid {
+                    rem: my_peer.clone(),
+                    dest: auction.seller,
+                    id,
+                    amount,
+                }
+            };
+            sender.send(Event::UIMessage(bid_msg)).await.ok();
+        }
+        Command::MuralListar => {
+            let mut offers: Vec<PublicOffer> =
+                app_state.board.lock().offers.values().cloned().collect();
+            offers.sort_by(|a, b| a.id.cmp(&b.id));
+            println!("-- MURAL DE OFERTAS --");
+            if offers.is_empty() {
+                log("[Nenhuma oferta no mural, use '$mural publicar peixe|n,... > peixe|n,...']");
+            }
+            for o in offers {
+                println!("> [{}] {}: {}", o.id, o.owner.username(), o.offer);
+            }
+        }
+        Command::MuralPublicar { offer_str } => {
+            let Ok(offer) = Offer::from_str(&offer_str) else {
+                err(
+                    "Formato de oferta errado, o correto é:\n $mural publicar peixe|x,peixe|y,... > peixe|z,peixe|w,...",
+                );
+                return;
+            };
+            if let Some((item, available)) = app_state.find_missing(&offer.offered, None) {
+                err(&format!(
+                    "Você não tem itens suficientes para a oferta. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            if !app_state.has_room(&offer.offered, &offer.requested) {
+                warn_full_basket(&app_state);
+                return;
+            }
+            let public_offer = {
+                let mut board = app_state.board.lock();
+                let public_offer = PublicOffer {
+                    id: board.ids.next(my_peer.username()),
+                    owner: my_peer.clone(),
+                    offer,
+                    mine: true,
+                    claimed: false,
+                };
+                board
+                    .offers
+                    .insert(public_offer.id.clone(), public_offer.clone());
+                public_offer
+            };
+            log(&format!(
+                "Oferta {} publicada no mural, o primeiro que pegar leva.",
+                public_offer.id
+            ));
+            sender
+                .send(Event::UIMessage(server::FNP::PublicOffer {
+                    rem: my_peer.clone(),
+                    id: public_offer.id,
+                    offer: public_offer.offer,
+                }))
+                .await
+                .ok();
+        }
+        Command::MuralPegar { id } => {
+            let Some(public_offer) = app_state.board.lock().offers.get(&id).cloned() else {
+                err("* Oferta não encontrada no mural.");
+                return;
+            };
+            if public_offer.mine {
+                err("* Você não pode pegar sua própria oferta.");
+                return;
+            }
+            if let Some((item, available)) =
+                app_state.find_missing(&public_offer.offer.requested, None)
+            {
+                err(&format!(
+                    "Você não tem itens suficientes para a oferta. (Disponível: {} {})",
+                    available, item.fish_type
+                ));
+                return;
+            }
+            if !app_state.has_room(&public_offer.offer.requested, &public_offer.offer.offered) {
+                warn_full_basket(&app_state);
+                return;
+            }
+            if let Some(o) = app_state.board.lock().offers.get_mut(&id) {
+                o.claimed = true;
+            }
+            log("Pedido enviado, aguardando quem publicou...");
+            sender
+                .send(Event::UIMessage(server::FNP::PublicOfferClaim {
+                    rem: my_peer.clone(),
+                    dest: public_offer.owner,
+                    id,
+                }))
+                .await
+                .ok();
+        }
+        Command::MuralRetirar { id } => {
+            let removed = {
+                let mut board = app_state.board.lock();
+                if !board.offers.get(&id).is_some_and(|o| o.mine) {
+                    err("* Oferta sua não encontrada no mural.");
+                    return;
+                }
+                board.offers.remove(&id)
+            };
+            if removed.is_some() {
+                log(&format!("Oferta {} retirada do mural.", id));
+                sender
+                    .send(Event::UIMessage(server::FNP::PublicOfferClosed {
+                        rem: my_peer.clone(),
+                        id,
+                        taker: None,
+                    }))
+                    .await
+                    .ok();
+            }
+        }
+        Command::Ordens => {
+            let mut orders: Vec<Order> = app_state.orders.lock().orders.values().cloned().collect();
+            orders.sort_by(|a, b| a.id.cmp(&b.id));
+            println!("-- LIVRO DE ORDENS --");
+            if orders.is_empty() {
+                log("[Nenhuma ordem aberta, use '$ordem comprar|vender peixe|n item|preço']");
+            }
+            for o in orders {
+                let limit = match o.side {
+                    OrderSide::Compra => "até",
+                    OrderSide::Venda => "pelo menos",
+                };
+                println!(
+                    "> [{}] {}: {} de {}, {} {} cada",
+                    o.id,
+                    o.owner.username(),
+                    o.side,
+                    o.item,
+                    limit,
+                    o.price
+                );
+            }
+        }
+        Command::OrdemCriar {
+            side,
+            item_str,
+            price_str,
+        } => {
+            let (Ok(side), Ok(item), Ok(price)) = (
+                OrderSide::from_str(&side),
+                InventoryItem::from_str(&item_str),
+                InventoryItem::from_str(&price_str),
+            ) else {
+                err(
+                    "Formato de ordem errado, o correto é:\n $ordem <comprar|vender> <peixe>|<quantidade> <item>|<preço por unidade>",
+                );
+                return;
+            };
+            if item.quantity == 0 || price.quantity == 0 || item.fish_type == price.fish_type {
+                err("* A quantidade e o preço precisam ser positivos e de itens diferentes.");
+                return;
+            }
+            let order = {
+                let mut orders = app_state.orders.lock();
+                let order = Order {
+                    id: orders.ids.next(my_peer.username()),
+                    owner: my_peer.clone(),
+                    side,
+                    item,
+                    price,
+                    mine: true,
+                };
+                orders.orders.insert(order.id.clone(), order.clone());
+                order
+            };
+            log(&format!(
+                "Ordem de {} {} registrada: {} por {} cada.",
+                order.side, order.id, order.item, order.price
+            ));
+            // Os peixes de uma ordem de venda não ficam reservados, a disponibilidade é
+            // checada quando ela casa com uma ordem de compra
+            sender
+                .send(Event::UIMessage(server::FNP::OrderAnnounce {
+                    rem: my_peer.clone(),
+                    id: order.id,
+                    side: order.side,
+                    item: order.item,
+                    price: order.price,
+                }))
+                .await
+                .ok();
+            for offer in match_orders(&app_state, my_peer.clone()) {
+                sender.send(Event::UIMessage(offer)).await.ok();
+            }
+        }
+        Command::OrdemCancelar { id } => {
+            let removed = {
+                let mut orders = app_state.orders.lock();
+                if !orders.orders.get(&id).is_some_and(|o| o.mine) {
+                    err("* Ordem sua não encontrada.");
+                    return;
+                }
+                orders.orders.remove(&id)
+            };
+            if removed.is_some() {
+                log(&format!("Ordem {} cancelada.", id));
+                sender
+                    .send(Event::UIMessage(server::FNP::OrderCancel {
+                        rem: my_peer.clone(),
+                        id,
+                    }))
+                    .await
+                    .ok();
+            }
+        }
+        Command::Quit => {
+            // Guarda a cesta para o `fishnet export`. No modo de teste a cesta não vale
+            if !*app_state.sandbox.lock() {
+                let path = gameplay::basket_path(my_peer.username());
+                let content = basket_export(&app_state, "csv").unwrap_or_default();
+                if let Err(e) = std::fs::write(&path, content) {
+                    err(&format!(
+                        "* Não foi possível salvar a cesta em {}: {}",
+                        path, e
+                    ));
+                }
+            }
+            log("Encerrando fishnet, boa pescaria...");
+            std::process::exit(0);
+        }
+        Command::Help => {
+            log("fishnet 1.0.0");
+            log("Options:");
+            log("\t anything - Broadcast de mensagens para todos os peers conectados.");
+            log("\t @peer - Envia uma mensagem direta para um dado peer.");
+            log("\t $[l]istar - Lista todos os peers conectados a você.");
+            log("\t $[p]escar - Pesca um peixe aleatorio.");
+            log(
+                "\t $[i]nventario <peer> [--raridade <raridade>] [--ordem quantidade|nome|raridade] [--busca <trecho>] - Mostra o inventário do jogador, pode opcionalmente mostrar o inventário de um peer.",
+            );
+            log(
+                "\t $[t]roca <peer> (peixe|quatidade,... > peixe|quantidade,...) - Envia uma oferta de troca para um peer. Use moedas|n para incluir moedas.",
+            );
+            log("\t $[c]onfirmar <s|n> <peer> - Pedido de confirmação de troca");
+            log(
+                "\t $duelo [<peer> [peixe|n] | aceitar <peer> [peixe|n] | recusar <peer>] - Desafia um peer para um duelo de pesca, com uma aposta opcional para o vencedor.",
+            );
+            log(
+                "\t $dar <peer> <peixe|n> - Dá peixes ou moedas a um peer, sem pedir nada em troca.",
+            );
+            log(
+                "\t $guilda [criar <nome> [quórum] | entrar <nome> | sair | depositar <peixe|n> | sacar <peixe|n> | aprovar <id> | recusar <id> | quorum <n>] - Guilda com um cesto compartilhado, saques precisam da aprovação dos membros.",
+            );
+            log(
+                "\t $grupo [convidar <peer> | aceitar <peer> | recusar <peer> | sair] - Grupo de pesca, cada membro aumenta as chances de peixes raros de todos.",
+            );
+            log(
+                "\t $gp <mensagem> - Conversa com o grupo, que também é avisado das suas pescas raras.",
+            );
+            log(
+                "\t $multitroca [propor <de>para:peixe|n ...> | aceitar <id> | recusar <id>] - Troca entre três ou mais peers, só acontece se todos aprovarem.",
+            );
+            log(
+                "\t $procurar <peixe> [saltos] - Pergunta à rede quem tem um peixe, os saltos repassam a busca adiante.",
+            );
+            log(
+                "\t $desejo [add <peixe> | remover <peixe>] - Lista de desejos, os peers avisam quando pescam ou recebem o peixe.",
+            );
+            log(
+                "\t $historico [@peer] [peixe] | exportar <csv|json> [arquivo] - Mostra ou exporta o histórico de trocas.",
+            );
+            log("\t $exportar <csv|json> [arquivo] - Exporta a cesta e as moedas para um arquivo.");
+            log(
+                "\t $importar <arquivo> - Troca a cesta pela de um arquivo exportado, só no modo --sandbox.",
+            );
+            log(
+                "\t $regras [<regra> | silenciar <peer> | liberar <peer>] - Mostra e alterna as regras de resposta automática a ofertas.",
+            );
+            log(
+                "\t $privacidade [publico|amigos|perguntar | esconder <raridade> | mostrar <raridade> | permitir <peer> | negar <peer>] - Escolhe quem vê o seu inventário e responde pedidos.",
+            );
+            log(
+                "\t $amigos [add <peer> | remover <peer>] - Amigos sempre podem ver o seu inventário.",
+            );
+            log(
+                "\t $comparar <peer> - Compara inventários e sugere trocas que completam a coleção dos dois.",
+            );
+            log(
+                "\t $torneio [iniciar <duração> [raridade] | entrar] - Organiza, entra ou mostra um torneio de pesca.",
+            );
+            log("\t $missoes - Mostra as missões diárias e o seu progresso.");
+            log(
+                "\t $cardume - Mostra os cardumes abertos e quem já respondeu. Os primeiros a pescar ganham um peixe extra.",
+            );
+            log(
+                "\t $clima - Mostra o clima e o período do dia da rede, que mudam as chances de pesca, e a previsão.",
+            );
+            log(
+                "\t $escassez [ligar|desligar] - Mostra ou alterna o modo em que espécies muito pescadas na rede ficam mais raras.",
+            );
+            log(
+                "\t $soltar <peixe> [quantidade] - Solta peixes de volta na água para liberar espaço na cesta.",
+            );
+            log(
+                "\t $aquario [comprar] - Mostra a capacidade da cesta ou compra um aquário para aumentá-la.",
+            );
+            log(
+                "\t $craft | $cozinhar [receita [peixe]] - Transforma peixes repetidos em iscas e itens, ou cozinha pratos com bônus de pesca.",
+            );
+            log(
+                "\t $vender [peixe] [quantidade] - Vende peixes ao mercado, sem argumentos mostra os preços.",
+            );
+            log(
+                "\t $leilao [peixe|n <lance mínimo> <duração>] - Abre um leilão, sem argumentos lista os leilões abertos.",
+            );
+            log("\t $lance <leilão> <valor> - Dá um lance em moedas num leilão.");
+            log(
+                "\t $mural [publicar <oferta> | pegar <id> | retirar <id>] - Ofertas públicas, o primeiro que pegar leva.",
+            );
+            log("\t $ordens - Mostra o livro de ordens de compra e venda.");
+            log(
+                "\t $ordem <comprar|vender> <peixe|n> <item|preço> | cancelar <id> - Registra uma ordem permanente, trocas são propostas quando ela casa com outra.",
+            );
+            log("\t $[q]uit - Encerra o programa.");
+            log("\t $[h]elp - Mostra essa mensagem de ajuda.");
+        }
+        Command::Unknown(unk) => {
+            err(&format!("Comando ({}) não existe", unk));
+        }
+    }
+}
+
+/// Nossa cesta e moedas no formato pedido, `None` se o formato não existe
+fn basket_export(app_state: &AppState, format: &str) -> Option<String> {
+    let items = app_state
+        .basket
+        .lock()
+        .map()
+        .iter()
+        .map(|(k, v)| InventoryItem::new(k.to_string(), *v))
+        .collect();
+    let coins = *app_state.coins.lock();
+    gameplay::export_basket(&app_state.fish_catalog, format, items, coins)
+}
+
+/// Lê a aposta opcional de um duelo. Sem aposta, o duelo vale só a glória
+fn parse_stake(stake_str: Option<String>) -> Option<Vec<InventoryItem>> {
+    match stake_str.map(|s| InventoryItem::from_str(&s)) {
+        None => Some(Vec::new()),
+        Some(Ok(item)) if item.quantity > 0 => Some(vec![item]),
+        Some(_) => None,
     }
 }
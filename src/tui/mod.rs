@@ -5,9 +5,11 @@ mod io;
 mod style;
 
 pub use cli::Args;
+pub use cli::CliCommand;
 pub use io::ask_username;
 pub use style::err;
 pub use style::log;
+pub use style::warn;
 
 use crate::AppState;
 use crate::Event;
@@ -8,6 +8,10 @@ pub fn err(err_msg: &str) {
     println!("{}", style_err_msg(err_msg));
 }
 
+pub fn warn(warn_msg: &str) {
+    println!("{}", style_warn_msg(warn_msg));
+}
+
 /// Colori a mensagem de cinza
 fn style_log_msg(msg: &str) -> String {
     Style::new()
@@ -17,6 +21,15 @@ fn style_log_msg(msg: &str) -> String {
         .to_string()
 }
 
+/// Colori a mensagem de amarelo
+fn style_warn_msg(warn_msg: &str) -> String {
+    Style::new()
+        .fg_rgb::<240, 190, 40>()
+        .italic()
+        .style(warn_msg)
+        .to_string()
+}
+
 /// Colori a mensagem de vermelho
 fn style_err_msg(err_msg: &str) -> String {
     Style::new()